@@ -27,12 +27,21 @@ pub mod built_info {
 #[clap(
     author = "Ryan Svihla",
     version = built_info::PKG_VERSION,
-    about = "gclog analyzes a jdk8 gc log",
-    long_about = "gclog analyzes a jdk8 gc log for a first pass diagnostic, it will not find all things, but it will help with the obvious things",
+    about = "gclog analyzes a jdk8 or jdk9+ unified gc log",
+    long_about = "gclog analyzes a jdk8 or jdk9+ unified gc log from the G1, Parallel, CMS, Serial, ZGC or Shenandoah collectors for a first pass diagnostic, it will not find all things, but it will help with the obvious things",
 )]
 #[clap(propagate_version = true)]
 pub struct Args {
     #[clap()]
-    /// gclog file to parse
-    pub file_name: String,
+    /// gclog files to parse, rotated files and directories of them are read as one timeline.
+    /// Use - or leave empty to read from stdin
+    pub file_names: Vec<String>,
+    #[clap(long)]
+    /// when the jvm started, used to turn the uptimes of logs without date stamps into times.
+    /// Either 2022-07-22T18:41:06.000+0000, RFC 3339 or 2022-07-22 18:41:06 in UTC
+    pub jvm_start: Option<String>,
+    #[clap(long)]
+    /// time zone to show report times in, either UTC or a fixed offset such as +05:30, +5:30 or -0800.
+    /// Defaults to UTC
+    pub tz: Option<String>,
 }
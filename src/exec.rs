@@ -12,11 +12,64 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::{self, IsTerminal};
+
 use crate::args::Args;
 use crate::glog;
+use crate::glog::{
+    exec::ExecOptions,
+    pauses::{parse_datetime_epoch_micros, parse_utc_offset},
+};
 
 pub fn run(args: Args) -> String {
-    let file_name = args.file_name;
-    let result = glog::exec::exec(file_name);
-    result.expect("unexpected error")
+    let mut file_names = args.file_names;
+    if file_names.is_empty() {
+        //nothing is being piped in so there is nothing to read
+        if io::stdin().is_terminal() {
+            return "no gc log to read, pass a file name or pipe a gc log to stdin".to_string();
+        }
+        file_names.push("-".to_string());
+    }
+    let jvm_start_epoch_micros = match args.jvm_start.as_deref().map(parse_datetime_epoch_micros) {
+        Some(Ok(epoch)) => Some(epoch),
+        Some(Err(e)) => return format!("invalid --jvm-start: {e}"),
+        None => None,
+    };
+    let tz = match args.tz.as_deref().map(parse_utc_offset) {
+        Some(Ok(offset)) => Some(offset),
+        Some(Err(e)) => return format!("invalid --tz: {e}"),
+        None => None,
+    };
+    let options = ExecOptions {
+        jvm_start_epoch_micros,
+        tz,
+    };
+    match glog::exec::exec_files(&file_names, &options) {
+        Ok(report) => report,
+        Err(e) => format!("unable to read gc log: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use crate::args::Args;
+
+    use super::run;
+
+    #[test]
+    fn test_run_empty_directory() {
+        let dir = tempdir().expect("unable to make tmp dir");
+        let location = dir.path().to_str().unwrap().to_string();
+        let result = run(Args {
+            file_names: vec![location.to_string()],
+            jvm_start: None,
+            tz: None,
+        });
+        assert_eq!(
+            result,
+            format!("unable to read gc log: no gc log files found in {location}")
+        );
+    }
 }
@@ -11,10 +11,31 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+pub mod classhistogram;
+pub mod cms;
+pub mod concurrent;
 pub mod convert;
+pub mod ergonomics;
 pub mod exec;
 pub mod flags;
+pub mod fullgc;
+pub mod g1cycles;
 pub mod g1gc;
+pub mod g1phases;
+pub mod input;
+pub mod jvmflags;
 pub mod jvmstats;
+pub mod memorybudget;
+pub mod occupancy;
+pub mod parallel;
 pub mod pauses;
 pub mod recommendations;
+pub mod references;
+pub mod rotation;
+pub mod safepoints;
+pub mod serial;
+pub mod stalls;
+pub mod tenuring;
+pub mod times;
+pub mod unified;
+pub mod uptime;
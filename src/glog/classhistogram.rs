@@ -0,0 +1,456 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// parsing for the class histograms written around a full gc with
+// -XX:+PrintClassHistogramBeforeFullGC and -XX:+PrintClassHistogramAfterFullGC
+//
+// 2022-08-24T01:54:38.603+0000: 190268.356: [Class Histogram (before full gc):
+// num     #instances         #bytes  class name
+// ----------------------------------------------
+//     1:        954593     4097572584  [B
+//     4:       2769733      177262912  org.apache.arrow.memory.ArrowBuf
+// Total      34967203     6624451024
+// , 0.9324195 secs]
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+use time::UtcOffset;
+
+use crate::human::{human_bytes, human_bytes_ref, human_time};
+
+use super::pauses::{generate_gc_name, GCPause};
+
+// how many classes are listed for each full gc and in the growth table
+const TOP_CLASSES: usize = 10;
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ClassCount {
+    pub class_name: String,
+    pub instances: i64,
+    pub bytes: i64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ClassHistogram {
+    pub classes: Vec<ClassCount>,
+    pub total_instances: i64,
+    pub total_bytes: i64,
+}
+
+// reads 1:        954593     4097572584  [B
+fn parse_class_count(line: &str) -> Option<ClassCount> {
+    let mut tokens = line.split_whitespace();
+    let rank = tokens.next()?.strip_suffix(':')?;
+    u64::from_str(rank).ok()?;
+    let instances = i64::from_str(tokens.next()?).ok()?;
+    let bytes = i64::from_str(tokens.next()?).ok()?;
+    //jdk 9+ adds the module after the class name
+    let class_name = tokens.collect::<Vec<&str>>().join(" ");
+    if class_name.is_empty() {
+        return None;
+    }
+    Some(ClassCount {
+        class_name,
+        instances,
+        bytes,
+    })
+}
+
+// finds the histogram with the label "before full gc" or "after full gc" in the text of a pause
+pub fn parse_class_histogram(text: &str, label: &str) -> Option<ClassHistogram> {
+    let start = text.find(&format!("Class Histogram ({label})"))?;
+    let mut histogram = ClassHistogram::default();
+    for line in text[start..].lines().skip(1) {
+        let line = line.trim();
+        if let Some(totals) = line.strip_prefix("Total") {
+            let mut tokens = totals.split_whitespace();
+            histogram.total_instances = i64::from_str(tokens.next()?).ok()?;
+            histogram.total_bytes = i64::from_str(tokens.next()?).ok()?;
+            return Some(histogram);
+        }
+        if let Some(count) = parse_class_count(line) {
+            histogram.classes.push(count);
+        }
+    }
+    //a histogram cut off by the end of the log still has the classes read so far
+    if histogram.classes.is_empty() {
+        return None;
+    }
+    Some(histogram)
+}
+
+fn bytes_by_class(histogram: &ClassHistogram) -> HashMap<&str, &ClassCount> {
+    histogram
+        .classes
+        .iter()
+        .map(|c| (c.class_name.as_str(), c))
+        .collect()
+}
+
+#[derive(Tabled)]
+struct ClassRow {
+    #[tabled(rename = "Class")]
+    class_name: String,
+    #[tabled(display_with = "format_count", rename = "Instances Before")]
+    instances_before: Option<i64>,
+    #[tabled(display_with = "format_bytes", rename = "Bytes Before")]
+    bytes_before: Option<i64>,
+    #[tabled(display_with = "format_count", rename = "Instances After")]
+    instances_after: Option<i64>,
+    #[tabled(display_with = "format_bytes", rename = "Bytes After")]
+    bytes_after: Option<i64>,
+    #[tabled(display_with = "format_percent", rename = "Survived")]
+    survived_percent: Option<f64>,
+}
+
+fn format_count(count: &Option<i64>) -> String {
+    match count {
+        Some(count) => count.to_string(),
+        None => "n/a".to_string(),
+    }
+}
+
+fn format_bytes(bytes: &Option<i64>) -> String {
+    match bytes {
+        Some(bytes) => human_bytes(*bytes),
+        None => "n/a".to_string(),
+    }
+}
+
+fn format_percent(percent: &Option<f64>) -> String {
+    match percent {
+        Some(percent) => format!("{percent:.2}%"),
+        None => "n/a".to_string(),
+    }
+}
+
+// the classes using the most memory before the full gc next to how much of them was still
+// alive after it
+fn generate_full_gc_classes(
+    before: Option<&ClassHistogram>,
+    after: Option<&ClassHistogram>,
+) -> String {
+    let (ranked, other) = match (before, after) {
+        (Some(before), after) => (before, after),
+        (None, Some(after)) => (after, None),
+        (None, None) => return "".to_string(),
+    };
+    let other_classes = other.map(bytes_by_class);
+    let mut classes: Vec<&ClassCount> = ranked.classes.iter().collect();
+    classes.sort_by_key(|c| std::cmp::Reverse(c.bytes));
+    let mut rows = vec![];
+    for class in classes.into_iter().take(TOP_CLASSES) {
+        //a class missing from the after histogram had nothing survive
+        let survivor = other_classes.as_ref().map(|classes| {
+            classes
+                .get(class.class_name.as_str())
+                .map(|c| (c.instances, c.bytes))
+                .unwrap_or((0, 0))
+        });
+        let row = if before.is_some() {
+            ClassRow {
+                class_name: class.class_name.to_string(),
+                instances_before: Some(class.instances),
+                bytes_before: Some(class.bytes),
+                instances_after: survivor.map(|(instances, _)| instances),
+                bytes_after: survivor.map(|(_, bytes)| bytes),
+                survived_percent: survivor
+                    .filter(|_| class.bytes > 0)
+                    .map(|(_, bytes)| bytes as f64 / class.bytes as f64 * 100.0),
+            }
+        } else {
+            ClassRow {
+                class_name: class.class_name.to_string(),
+                instances_before: None,
+                bytes_before: None,
+                instances_after: Some(class.instances),
+                bytes_after: Some(class.bytes),
+                survived_percent: None,
+            }
+        };
+        rows.push(row);
+    }
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+// a top classes table for every full gc that logged a class histogram
+pub fn generate_class_histogram_report(pauses: &[GCPause], offset: UtcOffset) -> String {
+    let mut output = vec![];
+    for pause in pauses {
+        let (before, after) = (
+            pause.histogram_before.as_ref(),
+            pause.histogram_after.as_ref(),
+        );
+        if before.is_none() && after.is_none() {
+            continue;
+        }
+        let total = |histogram: Option<&ClassHistogram>| match histogram {
+            Some(histogram) => human_bytes(histogram.total_bytes),
+            None => "n/a".to_string(),
+        };
+        output.push(format!(
+            "{} at {}: {} before, {} after",
+            generate_gc_name(pause),
+            human_time(pause.time_epoch_micros, offset),
+            total(before),
+            total(after),
+        ));
+        output.push(generate_full_gc_classes(before, after));
+    }
+    output.join("\n")
+}
+
+// how the bytes of a class changed across the full gcs that logged a histogram
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ClassGrowth {
+    pub class_name: String,
+    pub first_bytes: i64,
+    pub last_bytes: i64,
+    pub histograms: usize,
+    //never went down from one full gc to the next
+    pub grew_every_time: bool,
+}
+
+// the after histograms are the live data set so they are preferred, the before histograms are
+// used when those were not logged
+fn live_histograms(pauses: &[GCPause]) -> Vec<&ClassHistogram> {
+    let after: Vec<&ClassHistogram> = pauses
+        .iter()
+        .filter_map(|p| p.histogram_after.as_ref())
+        .collect();
+    if !after.is_empty() {
+        return after;
+    }
+    pauses
+        .iter()
+        .filter_map(|p| p.histogram_before.as_ref())
+        .collect()
+}
+
+// the classes that grew the most between the first and the last histogram
+pub fn find_class_growth(pauses: &[GCPause]) -> Vec<ClassGrowth> {
+    let histograms = live_histograms(pauses);
+    if histograms.len() < 2 {
+        return vec![];
+    }
+    let by_class: Vec<HashMap<&str, &ClassCount>> =
+        histograms.iter().map(|h| bytes_by_class(h)).collect();
+    let last = histograms[histograms.len() - 1];
+    let mut growth = vec![];
+    for class in &last.classes {
+        let bytes: Vec<i64> = by_class
+            .iter()
+            .map(|classes| {
+                classes
+                    .get(class.class_name.as_str())
+                    .map(|c| c.bytes)
+                    .unwrap_or_default()
+            })
+            .collect();
+        let first_bytes = bytes[0];
+        if class.bytes <= first_bytes {
+            continue;
+        }
+        growth.push(ClassGrowth {
+            class_name: class.class_name.to_string(),
+            first_bytes,
+            last_bytes: class.bytes,
+            histograms: histograms.len(),
+            grew_every_time: bytes.windows(2).all(|w| w[1] >= w[0]),
+        });
+    }
+    growth.sort_by(|a, b| {
+        (b.last_bytes - b.first_bytes)
+            .cmp(&(a.last_bytes - a.first_bytes))
+            .then(a.class_name.cmp(&b.class_name))
+    });
+    growth
+}
+
+#[derive(Tabled)]
+struct GrowthRow {
+    #[tabled(rename = "Class")]
+    class_name: String,
+    #[tabled(display_with = "human_bytes_ref", rename = "First")]
+    first_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Last")]
+    last_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Growth")]
+    growth_bytes: i64,
+    #[tabled(rename = "Grew Every Full GC")]
+    grew_every_time: bool,
+}
+
+pub fn generate_class_growth_table(pauses: &[GCPause]) -> String {
+    let rows: Vec<GrowthRow> = find_class_growth(pauses)
+        .into_iter()
+        .take(TOP_CLASSES)
+        .map(|g| GrowthRow {
+            growth_bytes: g.last_bytes - g.first_bytes,
+            class_name: g.class_name,
+            first_bytes: g.first_bytes,
+            last_bytes: g.last_bytes,
+            grew_every_time: g.grew_every_time,
+        })
+        .collect();
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::UtcOffset;
+
+    use crate::glog::pauses::GCPause;
+
+    use super::{
+        find_class_growth, generate_class_growth_table, generate_class_histogram_report,
+        parse_class_histogram, ClassCount, ClassHistogram,
+    };
+
+    const FULL_GC: &str = "2022-08-24T01:54:38.603+0000: 190268.356: [Full GC (Allocation Failure) 2022-08-24T01:54:38.603+0000: 190268.356: [Class Histogram (before full gc):
+num     #instances         #bytes  class name
+----------------------------------------------
+    1:        954593     4097572584  [B
+    2:        135687      723913256  [I
+    4:       2769733      177262912  org.apache.arrow.memory.ArrowBuf
+Total      34967203     6624451024
+, 0.9324195 secs]
+ 16364M->4966M(16384M), 3.6564555 secs]
+    2022-08-24T01:54:42.260+0000: 190272.012: [Class Histogram (after full gc):
+    num     #instances         #bytes  class name
+    ----------------------------------------------
+    1:        891298     4042599072  [B
+    3:       1457412       93274368  org.apache.arrow.memory.ArrowBuf
+    Total      21384721     5207859016
+    , 0.4783018 secs]";
+
+    fn histogram(classes: &[(&str, i64)]) -> ClassHistogram {
+        ClassHistogram {
+            classes: classes
+                .iter()
+                .map(|(class_name, bytes)| ClassCount {
+                    class_name: class_name.to_string(),
+                    instances: bytes / 64,
+                    bytes: *bytes,
+                })
+                .collect(),
+            total_instances: 0,
+            total_bytes: classes.iter().map(|(_, bytes)| bytes).sum(),
+        }
+    }
+
+    fn full_gc(after: &[(&str, i64)]) -> GCPause {
+        GCPause {
+            gc_type: "Full GC".to_string(),
+            is_full_gc: true,
+            histogram_after: Some(histogram(after)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_class_histogram() {
+        let before = parse_class_histogram(FULL_GC, "before full gc").unwrap();
+        assert_eq!(before.classes.len(), 3);
+        assert_eq!(
+            before.classes[2],
+            ClassCount {
+                class_name: "org.apache.arrow.memory.ArrowBuf".to_string(),
+                instances: 2769733,
+                bytes: 177262912,
+            }
+        );
+        assert_eq!(before.total_instances, 34967203);
+        assert_eq!(before.total_bytes, 6624451024);
+        let after = parse_class_histogram(FULL_GC, "after full gc").unwrap();
+        assert_eq!(after.classes.len(), 2);
+        assert_eq!(after.total_bytes, 5207859016);
+        assert!(
+            parse_class_histogram("[Full GC (Allocation Failure) 1.0 secs]", "before full gc")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_generate_class_histogram_report() {
+        let pauses = vec![GCPause {
+            gc_type: "Full GC".to_string(),
+            is_full_gc: true,
+            attributes: vec!["Allocation Failure".to_string()],
+            time_epoch_micros: 1661306078603000,
+            histogram_before: parse_class_histogram(FULL_GC, "before full gc"),
+            histogram_after: parse_class_histogram(FULL_GC, "after full gc"),
+            ..Default::default()
+        }];
+        assert_eq!(
+            generate_class_histogram_report(&pauses, UtcOffset::UTC),
+            "Full GC - (Allocation Failure) at 2022-08-24T01:54:38.603Z: 6.17 gb before, 4.85 gb after
++----------------------------------+------------------+--------------+-----------------+-------------+----------+
+| Class                            | Instances Before | Bytes Before | Instances After | Bytes After | Survived |
++----------------------------------+------------------+--------------+-----------------+-------------+----------+
+| [B                               |      954593      |   3.82 gb    |     891298      |   3.76 gb   |  98.66%  |
++----------------------------------+------------------+--------------+-----------------+-------------+----------+
+| [I                               |      135687      |  690.38 mb   |        0        |   0 bytes   |  0.00%   |
++----------------------------------+------------------+--------------+-----------------+-------------+----------+
+| org.apache.arrow.memory.ArrowBuf |     2769733      |  169.05 mb   |     1457412     |  88.95 mb   |  52.62%  |
++----------------------------------+------------------+--------------+-----------------+-------------+----------+
+"
+        );
+        assert_eq!(generate_class_histogram_report(&[], UtcOffset::UTC), "");
+    }
+
+    #[test]
+    fn test_find_class_growth() {
+        let arrow = "org.apache.arrow.memory.ArrowBuf";
+        let pauses = vec![
+            full_gc(&[(arrow, 100), ("[B", 500)]),
+            full_gc(&[(arrow, 200), ("[B", 900)]),
+            full_gc(&[(arrow, 400), ("[B", 600), ("[J", 50)]),
+        ];
+        let growth = find_class_growth(&pauses);
+        assert_eq!(growth.len(), 3);
+        assert_eq!(growth[0].class_name, arrow);
+        assert_eq!(growth[0].first_bytes, 100);
+        assert_eq!(growth[0].last_bytes, 400);
+        assert!(growth[0].grew_every_time);
+        assert_eq!(growth[1].class_name, "[B");
+        assert!(!growth[1].grew_every_time);
+        assert_eq!(growth[2].class_name, "[J");
+        assert_eq!(growth[2].histograms, 3);
+        assert!(find_class_growth(&pauses[..1]).is_empty());
+        assert_eq!(
+            generate_class_growth_table(&pauses),
+            "+----------------------------------+-----------+-----------+-----------+--------------------+
+| Class                            |   First   |   Last    |  Growth   | Grew Every Full GC |
++----------------------------------+-----------+-----------+-----------+--------------------+
+| org.apache.arrow.memory.ArrowBuf | 100 bytes | 400 bytes | 300 bytes |        true        |
++----------------------------------+-----------+-----------+-----------+--------------------+
+| [B                               | 500 bytes | 600 bytes | 100 bytes |       false        |
++----------------------------------+-----------+-----------+-----------+--------------------+
+| [J                               |  0 bytes  | 50 bytes  | 50 bytes  |        true        |
++----------------------------------+-----------+-----------+-----------+--------------------+
+"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::str::FromStr;
+
+use super::{
+    concurrent::ConcurrentPhase,
+    occupancy::{find_space, find_transition_after, Space, SpaceOccupancy},
+    pauses::{get_epoch_micros, get_outer_pause_seconds, has_gc, parse_gc_pause, GCPause},
+};
+
+pub const CONCURRENT_MODE_FAILURE: &str = "concurrent mode failure";
+pub const PROMOTION_FAILED: &str = "promotion failed";
+
+pub fn is_cms_pause(text: &str) -> bool {
+    text.contains("[ParNew")
+        || text.contains("[CMS")
+        || text.contains("CMS-initial-mark")
+        || text.contains("CMS-remark")
+}
+
+pub fn parse_cms_occupancy(text: &str) -> Vec<SpaceOccupancy> {
+    let mut occupancy = vec![];
+    let mut last_generation_end = None;
+    if let Some((young, end)) = find_space(text, "ParNew", Space::Young) {
+        occupancy.push(young);
+        last_generation_end = Some(end);
+    }
+    //on a concurrent mode failure the old gen numbers follow the failure message instead of [CMS:
+    let old_start = ["(concurrent mode failure): ", "[CMS: "]
+        .iter()
+        .find_map(|marker| text.find(marker).map(|i| i + marker.len()));
+    if let Some(start) = old_start {
+        if let Some((old, end)) = find_transition_after(text, start, Space::Old) {
+            occupancy.push(old);
+            last_generation_end = Some(end);
+        }
+    }
+    if let Some(end) = last_generation_end {
+        if let Some((heap, _)) = find_transition_after(text, end, Space::Heap) {
+            occupancy.push(heap);
+        }
+    }
+    if let Some((metaspace, _)) = find_space(text, "Metaspace", Space::Metaspace) {
+        occupancy.push(metaspace);
+    }
+    occupancy
+}
+
+pub fn parse_cms_pause(text: String) -> Result<GCPause, Box<dyn Error>> {
+    //1.234: [GC (Allocation Failure) 1.234: [ParNew: 30720K->3392K(30720K), 0.0123 secs] 40000K->15000K(99008K), 0.0125 secs]
+    //2.345: [GC (CMS Initial Mark) [1 CMS-initial-mark: 20000K(68288K)] 25000K(99008K), 0.0012 secs]
+    //2.500: [GC (CMS Final Remark) [YG occupancy: 5000 K (30720 K)]2.500: [Rescan (parallel) , 0.003 secs]2.505: [scrub string table, 0.0002 secs][1 CMS-remark: 20000K(68288K)] 25000K(99008K), 0.0062 secs]
+    //5.000: [GC (Allocation Failure) 5.000: [ParNew: 30720K->30720K(30720K), 0.0000 secs]5.000: [CMS5.100: [CMS-concurrent-mark: 0.1/0.1 secs]
+    // (concurrent mode failure): 68000K->50000K(68288K), 0.5 secs] 98720K->50000K(99008K), [Metaspace: 3000K->3000K(1056768K)], 0.5 secs]
+    let first_line = text
+        .split('\n')
+        .find(|line| has_gc(line))
+        .unwrap_or_default()
+        .to_string();
+    let mut pause = parse_gc_pause(first_line)?;
+    if let Some(seconds) = get_outer_pause_seconds(&text) {
+        pause.pause_time_seconds = seconds;
+    }
+    //both failures fall back to a single threaded stop the world collection of the old gen
+    for failure in [CONCURRENT_MODE_FAILURE, PROMOTION_FAILED] {
+        if text.contains(failure) {
+            pause.is_full_gc = true;
+            pause.attributes.push(failure.to_string());
+        }
+    }
+    pause.occupancy = parse_cms_occupancy(&text);
+    Ok(pause)
+}
+
+pub fn parse_cms_concurrent(line: &str) -> Vec<ConcurrentPhase> {
+    //2022-01-02T11:11:01.111+0000: 2.400: [CMS-concurrent-mark: 0.050/0.054 secs] [Times: user=0.10 sys=0.00, real=0.05 secs]
+    let time_epoch_micros = line
+        .split(": ")
+        .next()
+        .and_then(|datetime| get_epoch_micros(datetime.to_string()).ok())
+        .unwrap_or_default();
+    let mut phases = vec![];
+    for (index, _) in line.match_indices("[CMS-concurrent-") {
+        let rest = &line[index + 1..];
+        let body = match rest.find(']') {
+            Some(end) => &rest[..end],
+            None => continue,
+        };
+        //the -start lines have no timings
+        let (name, times) = match body.split_once(": ") {
+            Some(split) => split,
+            None => continue,
+        };
+        //the timings are cpu time/wall clock time
+        let wall_seconds = times
+            .trim_end_matches(" secs")
+            .split('/')
+            .nth(1)
+            .and_then(|wall| f64::from_str(wall).ok());
+        if let Some(duration_seconds) = wall_seconds {
+            phases.push(ConcurrentPhase {
+                name: name.to_string(),
+                time_epoch_micros,
+                duration_seconds,
+            });
+        }
+    }
+    phases
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::occupancy::{get_occupancy, Space};
+
+    use super::{is_cms_pause, parse_cms_concurrent, parse_cms_pause};
+
+    #[test]
+    fn test_parse_cms_young_pause() {
+        let line = "2022-01-02T11:11:01.111+0000: 1.234: [GC (Allocation Failure) 2022-01-02T11:11:01.111+0000: 1.234: [ParNew: 30720K->3392K(30720K), 0.0123 secs] 40000K->15000K(99008K), 0.0125 secs] [Times: user=0.03 sys=0.00, real=0.01 secs]";
+        assert!(is_cms_pause(line));
+        let pause = parse_cms_pause(line.to_string()).unwrap();
+        assert_eq!(pause.gc_type, "Allocation Failure");
+        assert!(!pause.is_full_gc);
+        assert_eq!(pause.pause_time_seconds, 0.0125);
+        let young = get_occupancy(&pause, Space::Young).unwrap();
+        assert_eq!(young.before_bytes, 30720 * 1024);
+        assert_eq!(young.after_bytes, 3392 * 1024);
+        let heap = get_occupancy(&pause, Space::Heap).unwrap();
+        assert_eq!(heap.after_bytes, 15000 * 1024);
+    }
+
+    #[test]
+    fn test_parse_cms_initial_mark_and_remark() {
+        let initial_mark = "2.345: [GC (CMS Initial Mark) [1 CMS-initial-mark: 20000K(68288K)] 25000K(99008K), 0.0012 secs] [Times: user=0.00 sys=0.00, real=0.00 secs]";
+        let pause = parse_cms_pause(initial_mark.to_string()).unwrap();
+        assert_eq!(pause.gc_type, "CMS Initial Mark");
+        assert_eq!(pause.pause_time_seconds, 0.0012);
+        let remark = "2.500: [GC (CMS Final Remark) [YG occupancy: 5000 K (30720 K)]2.500: [Rescan (parallel) , 0.003 secs]2.503: [weak refs processing, 0.0001 secs]2.505: [scrub string table, 0.0002 secs][1 CMS-remark: 20000K(68288K)] 25000K(99008K), 0.0062 secs] [Times: user=0.01 sys=0.00, real=0.01 secs]";
+        let pause = parse_cms_pause(remark.to_string()).unwrap();
+        assert_eq!(pause.gc_type, "CMS Final Remark");
+        assert_eq!(pause.pause_time_seconds, 0.0062);
+    }
+
+    #[test]
+    fn test_parse_cms_concurrent_mode_failure() {
+        let text = "5.000: [GC (Allocation Failure) 5.000: [ParNew: 30720K->30720K(30720K), 0.0000 secs]5.000: [CMS5.100: [CMS-concurrent-mark: 0.100/0.120 secs] [Times: user=0.20 sys=0.00, real=0.12 secs]
+ (concurrent mode failure): 68000K->50000K(68288K), 0.5000000 secs] 98720K->50000K(99008K), [Metaspace: 3000K->3000K(1056768K)], 0.5123456 secs] [Times: user=0.50 sys=0.00, real=0.51 secs]";
+        let pause = parse_cms_pause(text.to_string()).unwrap();
+        assert!(pause.is_full_gc);
+        assert!(pause
+            .attributes
+            .contains(&"concurrent mode failure".to_string()));
+        assert_eq!(pause.pause_time_seconds, 0.5123456);
+        let old = get_occupancy(&pause, Space::Old).unwrap();
+        assert_eq!(old.before_bytes, 68000 * 1024);
+        assert_eq!(old.after_bytes, 50000 * 1024);
+        let heap = get_occupancy(&pause, Space::Heap).unwrap();
+        assert_eq!(heap.before_bytes, 98720 * 1024);
+        let metaspace = get_occupancy(&pause, Space::Metaspace).unwrap();
+        assert_eq!(metaspace.after_bytes, 3000 * 1024);
+        let phases = parse_cms_concurrent(text);
+        assert_eq!(phases.len(), 1);
+        assert_eq!(phases[0].name, "CMS-concurrent-mark");
+        assert_eq!(phases[0].duration_seconds, 0.120);
+    }
+
+    #[test]
+    fn test_parse_cms_promotion_failed() {
+        let line = "10.000: [GC (Allocation Failure) 10.000: [ParNew (promotion failed): 30720K->30720K(30720K), 0.0500 secs]10.050: [CMS: 68000K->50000K(68288K), 0.4000 secs] 98000K->50000K(99008K), [Metaspace: 3000K->3000K(1056768K)], 0.4512345 secs] [Times: user=0.50 sys=0.00, real=0.45 secs]";
+        let pause = parse_cms_pause(line.to_string()).unwrap();
+        assert!(pause.is_full_gc);
+        assert_eq!(pause.gc_type, "Allocation Failure");
+        assert_eq!(pause.attributes, vec!["promotion failed"]);
+        assert_eq!(pause.pause_time_seconds, 0.4512345);
+        let young = get_occupancy(&pause, Space::Young).unwrap();
+        assert_eq!(young.after_bytes, 30720 * 1024);
+        let old = get_occupancy(&pause, Space::Old).unwrap();
+        assert_eq!(old.after_bytes, 50000 * 1024);
+    }
+
+    #[test]
+    fn test_parse_cms_concurrent() {
+        let line = "2022-01-02T11:11:01.111+0000: 2.400: [CMS-concurrent-mark: 0.050/0.054 secs] [Times: user=0.10 sys=0.00, real=0.05 secs]";
+        let phases = parse_cms_concurrent(line);
+        assert_eq!(phases.len(), 1);
+        assert_eq!(phases[0].name, "CMS-concurrent-mark");
+        assert_eq!(phases[0].duration_seconds, 0.054);
+        assert_eq!(phases[0].time_epoch_micros, 1641121861111000);
+        assert!(parse_cms_concurrent("2.346: [CMS-concurrent-mark-start]").is_empty());
+        let preclean = "2.500: [CMS-concurrent-abortable-preclean: 0.050/0.100 secs] [Times: user=0.10 sys=0.00, real=0.10 secs]";
+        assert_eq!(
+            parse_cms_concurrent(preclean)[0].name,
+            "CMS-concurrent-abortable-preclean"
+        );
+    }
+}
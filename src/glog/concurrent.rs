@@ -0,0 +1,140 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::format_float;
+
+use super::pauses::GCPause;
+
+// a phase of a gc cycle that runs alongside the application threads
+pub struct ConcurrentPhase {
+    pub name: String,
+    pub time_epoch_micros: i64,
+    pub duration_seconds: f64,
+}
+
+#[derive(Tabled)]
+struct ConcurrentPhaseRow {
+    #[tabled(rename = "Phase")]
+    name: String,
+    #[tabled(rename = "Count")]
+    count: i64,
+    #[tabled(display_with = "format_float", rename = "Total Time")]
+    total_seconds: f64,
+    #[tabled(display_with = "format_float", rename = "Max Time")]
+    max_seconds: f64,
+}
+
+fn generate_phase_table(phases: &[ConcurrentPhase]) -> String {
+    let mut rows: HashMap<String, ConcurrentPhaseRow> = HashMap::new();
+    for phase in phases {
+        rows.entry(phase.name.to_string())
+            .and_modify(|row| {
+                row.count += 1;
+                row.total_seconds += phase.duration_seconds;
+                row.max_seconds = row.max_seconds.max(phase.duration_seconds);
+            })
+            .or_insert(ConcurrentPhaseRow {
+                name: phase.name.to_string(),
+                count: 1,
+                total_seconds: phase.duration_seconds,
+                max_seconds: phase.duration_seconds,
+            });
+    }
+    let mut rows: Vec<ConcurrentPhaseRow> = rows.into_values().collect();
+    rows.sort_by_key(|r| r.name.to_string());
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+// shows the stop the world time next to the time spent in concurrent phases
+pub fn generate_concurrent_table(pauses: &[GCPause], phases: &[ConcurrentPhase]) -> String {
+    if phases.is_empty() {
+        return "".to_string();
+    }
+    let total_stw_seconds: f64 = pauses.iter().map(|p| p.pause_time_seconds).sum();
+    let total_concurrent_seconds: f64 = phases.iter().map(|p| p.duration_seconds).sum();
+    format!(
+        "stop the world time: {:.2} seconds\nconcurrent time:     {:.2} seconds\n{}",
+        total_stw_seconds,
+        total_concurrent_seconds,
+        generate_phase_table(phases)
+    )
+}
+
+// whole gc cycles grouped by what triggered them, these overlap the phases so they are
+// kept out of the concurrent time
+pub fn generate_cycle_table(cycles: &[ConcurrentPhase]) -> String {
+    if cycles.is_empty() {
+        return "".to_string();
+    }
+    generate_phase_table(cycles)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::pauses::GCPause;
+
+    use super::{generate_concurrent_table, generate_cycle_table, ConcurrentPhase};
+
+    #[test]
+    fn test_generate_concurrent_table() {
+        let pauses = vec![GCPause {
+            pause_time_seconds: 0.5,
+            ..Default::default()
+        }];
+        let phases = vec![
+            ConcurrentPhase {
+                name: "CMS-concurrent-mark".to_string(),
+                time_epoch_micros: 0,
+                duration_seconds: 1.25,
+            },
+            ConcurrentPhase {
+                name: "CMS-concurrent-mark".to_string(),
+                time_epoch_micros: 0,
+                duration_seconds: 0.75,
+            },
+            ConcurrentPhase {
+                name: "CMS-concurrent-sweep".to_string(),
+                time_epoch_micros: 0,
+                duration_seconds: 0.5,
+            },
+        ];
+        let output = generate_concurrent_table(&pauses, &phases);
+        assert_eq!(
+            output,
+            "stop the world time: 0.50 seconds
+concurrent time:     2.50 seconds
++----------------------+-------+------------+----------+
+| Phase                | Count | Total Time | Max Time |
++----------------------+-------+------------+----------+
+| CMS-concurrent-mark  |   2   |    2.00    |   1.25   |
++----------------------+-------+------------+----------+
+| CMS-concurrent-sweep |   1   |    0.50    |   0.50   |
++----------------------+-------+------------+----------+
+"
+        );
+    }
+
+    #[test]
+    fn test_generate_concurrent_table_empty() {
+        assert_eq!(generate_concurrent_table(&[], &[]), "");
+        assert_eq!(generate_cycle_table(&[]), "");
+    }
+}
@@ -19,11 +19,42 @@ pub fn convert_bytes_to_gb(b: usize) -> f32 {
     b as f32 / (1024.0 * 1024.0 * 1024.0)
 }
 
+// parses the sizes the jvm writes in gc logs such as 1234K, 10.9G or 0.0B into bytes, and the
+// lowercase 128000000k and 2m of the memory line
+pub fn parse_size_bytes(size: &str) -> Option<i64> {
+    let size = size.trim();
+    let split_at = size.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+    let (number, unit) = size.split_at(split_at);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: f64 = match unit.trim() {
+        "B" | "b" => 1.0,
+        "K" | "k" | "KB" => 1024.0,
+        "M" | "m" | "MB" => 1024.0 * 1024.0,
+        "G" | "g" | "GB" => 1024.0 * 1024.0 * 1024.0,
+        "T" | "t" | "TB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as i64)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::assert_approx_equal;
 
-    use super::{convert_bytes_to_gb, convert_bytes_to_mb};
+    use super::{convert_bytes_to_gb, convert_bytes_to_mb, parse_size_bytes};
+
+    #[test]
+    fn test_parse_size_bytes() {
+        assert_eq!(parse_size_bytes("1234K"), Some(1234 * 1024));
+        assert_eq!(parse_size_bytes("0.0B"), Some(0));
+        assert_eq!(parse_size_bytes("192.0M"), Some(192 * 1024 * 1024));
+        assert_eq!(parse_size_bytes("2G"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size_bytes("10.5G"), Some(11274289152));
+        assert_eq!(parse_size_bytes("128000000k"), Some(128000000 * 1024));
+        assert_eq!(parse_size_bytes("2m"), Some(2 * 1024 * 1024));
+        assert_eq!(parse_size_bytes("1234"), None);
+        assert_eq!(parse_size_bytes("K"), None);
+    }
 
     #[test]
     fn test_convert_bytes_to_mb() {
@@ -0,0 +1,401 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// parsing for the decisions G1 explains with -XX:+PrintAdaptiveSizePolicy
+//
+// 54055.679: [G1Ergonomics (CSet Construction) finish choosing CSet, eden: 62 regions, survivors: 0 regions, old: 0 regions, predicted pause time: 307.19 ms, target pause time: 500.00 ms]
+// 54055.954: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: occupancy higher than threshold, occupancy: 4664066048 bytes, allocation request: 0 bytes, threshold: 3238002675 bytes (25.00 %), source: end of GC]
+// 54056.101: [G1Ergonomics (Mixed GCs) do not start mixed GCs, reason: reclaimable percentage not over threshold, candidate old regions: 46 regions, reclaimable: 77284736 bytes (1.80 %), threshold: 5.00 %]
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::format_float;
+
+use super::pauses::{generate_gc_name, GCPause};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MixedGCDecision {
+    Start,         //start mixed GCs
+    Continue,      //continue mixed GCs
+    DoNotStart,    //do not start mixed GCs
+    DoNotContinue, //do not continue mixed GCs
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ErgonomicsEvent {
+    CSetChosen {
+        eden_regions: u64,
+        survivor_regions: u64,
+        old_regions: u64,
+        predicted_pause_millis: f64,
+        target_pause_millis: f64,
+    },
+    ConcurrentCycleRequested {
+        reason: String,
+        source: Option<String>,
+    },
+    ConcurrentCycleInitiated {
+        reason: String,
+    },
+    MixedGCs {
+        decision: MixedGCDecision,
+        reason: String,
+        candidate_old_regions: Option<u64>,
+        reclaimable_percent: Option<f64>,
+        threshold_percent: Option<f64>,
+    },
+    //heap sizing and the steps of building the collection set that only explain the above
+    Other {
+        category: String,
+        action: String,
+    },
+}
+
+// the number at the start of values such as 62 regions or 307.19 ms
+fn leading_number(value: &str) -> Option<f64> {
+    f64::from_str(value.split(' ').next()?).ok()
+}
+
+// the percentage of values such as 77284736 bytes (1.80 %) or 5.00 %
+fn percent(value: &str) -> Option<f64> {
+    let value = match value.split_once('(') {
+        Some((_, inner)) => inner.trim_end_matches(')'),
+        None => value,
+    };
+    f64::from_str(value.trim_end_matches('%').trim()).ok()
+}
+
+// reads (Mixed GCs) do not start mixed GCs, reason: ..., candidate old regions: 46 regions
+fn parse_ergonomics_entry(entry: &str) -> Option<ErgonomicsEvent> {
+    let rest = entry.strip_prefix("G1Ergonomics (")?;
+    let (category, rest) = rest.split_once(") ")?;
+    let mut parts = rest.split(", ");
+    let action = parts.next()?;
+    let fields: HashMap<&str, &str> = parts.filter_map(|part| part.split_once(": ")).collect();
+    let reason = fields.get("reason").unwrap_or(&"").to_string();
+    let mixed = |decision| ErgonomicsEvent::MixedGCs {
+        decision,
+        reason: reason.to_string(),
+        candidate_old_regions: fields
+            .get("candidate old regions")
+            .and_then(|v| leading_number(v))
+            .map(|v| v as u64),
+        reclaimable_percent: fields.get("reclaimable").and_then(|v| percent(v)),
+        threshold_percent: fields.get("threshold").and_then(|v| percent(v)),
+    };
+    let event = match action {
+        "finish choosing CSet" => {
+            let regions = |key| {
+                fields
+                    .get(key)
+                    .and_then(|v| leading_number(v))
+                    .map(|v| v as u64)
+            };
+            ErgonomicsEvent::CSetChosen {
+                eden_regions: regions("eden")?,
+                survivor_regions: regions("survivors")?,
+                old_regions: regions("old")?,
+                predicted_pause_millis: leading_number(fields.get("predicted pause time")?)?,
+                target_pause_millis: leading_number(fields.get("target pause time")?)?,
+            }
+        }
+        "request concurrent cycle initiation" => ErgonomicsEvent::ConcurrentCycleRequested {
+            reason,
+            source: fields.get("source").map(|s| s.to_string()),
+        },
+        "initiate concurrent cycle" => ErgonomicsEvent::ConcurrentCycleInitiated { reason },
+        "start mixed GCs" => mixed(MixedGCDecision::Start),
+        "continue mixed GCs" => mixed(MixedGCDecision::Continue),
+        "do not start mixed GCs" => mixed(MixedGCDecision::DoNotStart),
+        "do not continue mixed GCs" => mixed(MixedGCDecision::DoNotContinue),
+        _ => ErgonomicsEvent::Other {
+            category: category.to_string(),
+            action: action.to_string(),
+        },
+    };
+    Some(event)
+}
+
+// finds every G1Ergonomics entry in the text of a pause or in a line of its own
+pub fn parse_ergonomics(text: &str) -> Vec<ErgonomicsEvent> {
+    let mut events = vec![];
+    for piece in text.split('[').skip(1) {
+        let entry = match piece.split_once(']') {
+            Some((entry, _)) => entry,
+            None => continue,
+        };
+        if let Some(event) = parse_ergonomics_entry(entry) {
+            events.push(event);
+        }
+    }
+    events
+}
+
+// the predicted and target pause time of the collection set G1 chose for the pause
+pub fn get_cset_prediction(pause: &GCPause) -> Option<(f64, f64)> {
+    pause.ergonomics.iter().rev().find_map(|e| match e {
+        ErgonomicsEvent::CSetChosen {
+            predicted_pause_millis,
+            target_pause_millis,
+            ..
+        } => Some((*predicted_pause_millis, *target_pause_millis)),
+        _ => None,
+    })
+}
+
+// how many times G1 decided not to start mixed GCs because too little of the old generation
+// could be reclaimed, that threshold is -XX:G1HeapWastePercent
+pub fn count_mixed_gcs_skipped(pauses: &[GCPause]) -> i64 {
+    pauses
+        .iter()
+        .flat_map(|p| p.ergonomics.iter())
+        .filter(|e| {
+            matches!(e, ErgonomicsEvent::MixedGCs {
+                decision: MixedGCDecision::DoNotStart,
+                reason,
+                ..
+            } if reason.starts_with("reclaimable percentage"))
+        })
+        .count() as i64
+}
+
+#[derive(Tabled)]
+struct PredictionRow {
+    #[tabled(rename = "GC")]
+    gc_name: String,
+    #[tabled(rename = "Count")]
+    count: i64,
+    #[tabled(display_with = "format_float", rename = "Avg Predicted (ms)")]
+    avg_predicted_millis: f64,
+    #[tabled(display_with = "format_float", rename = "Avg Actual (ms)")]
+    avg_actual_millis: f64,
+    #[tabled(rename = "Over Prediction")]
+    over_prediction: i64,
+    #[tabled(display_with = "format_percent", rename = "Max Over (%)")]
+    max_over_percent: f64,
+    #[tabled(rename = "Over Target")]
+    over_target: i64,
+}
+
+fn format_percent(percent: &f64) -> String {
+    format!("{percent:.2}%")
+}
+
+// how the pause time G1 predicted when it chose the collection set compares to the actual pause
+pub fn generate_prediction_table(pauses: &[GCPause]) -> String {
+    let mut rows: HashMap<String, PredictionRow> = HashMap::new();
+    for pause in pauses {
+        let (predicted, target) = match get_cset_prediction(pause) {
+            Some(prediction) => prediction,
+            None => continue,
+        };
+        let actual = pause.pause_time_seconds * 1000.0;
+        let over_percent = if predicted > 0.0 && actual > predicted {
+            (actual - predicted) / predicted * 100.0
+        } else {
+            0.0
+        };
+        let over_prediction = i64::from(actual > predicted);
+        let over_target = i64::from(actual > target);
+        rows.entry(generate_gc_name(pause))
+            .and_modify(|row| {
+                row.count += 1;
+                row.avg_predicted_millis += predicted;
+                row.avg_actual_millis += actual;
+                row.over_prediction += over_prediction;
+                row.max_over_percent = row.max_over_percent.max(over_percent);
+                row.over_target += over_target;
+            })
+            .or_insert(PredictionRow {
+                gc_name: generate_gc_name(pause),
+                count: 1,
+                avg_predicted_millis: predicted,
+                avg_actual_millis: actual,
+                over_prediction,
+                max_over_percent: over_percent,
+                over_target,
+            });
+    }
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    let mut rows: Vec<PredictionRow> = rows.into_values().collect();
+    for row in rows.iter_mut() {
+        row.avg_predicted_millis /= row.count as f64;
+        row.avg_actual_millis /= row.count as f64;
+    }
+    rows.sort_by_key(|r| r.gc_name.to_string());
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+// one line for each kind of decision G1 made about concurrent cycles and mixed GCs
+pub fn generate_ergonomics_summary(pauses: &[GCPause]) -> String {
+    let mut requested: HashMap<String, i64> = HashMap::new();
+    let mut initiated = 0;
+    let mut mixed: HashMap<(&str, String), i64> = HashMap::new();
+    for event in pauses.iter().flat_map(|p| p.ergonomics.iter()) {
+        match event {
+            ErgonomicsEvent::ConcurrentCycleRequested { reason, .. } => {
+                *requested.entry(reason.to_string()).or_default() += 1;
+            }
+            ErgonomicsEvent::ConcurrentCycleInitiated { .. } => initiated += 1,
+            ErgonomicsEvent::MixedGCs {
+                decision, reason, ..
+            } => {
+                let decision = match decision {
+                    MixedGCDecision::Start => "started",
+                    MixedGCDecision::Continue => "continued",
+                    MixedGCDecision::DoNotStart => "not started",
+                    MixedGCDecision::DoNotContinue => "stopped",
+                };
+                *mixed.entry((decision, reason.to_string())).or_default() += 1;
+            }
+            _ => (),
+        }
+    }
+    let mut lines = vec![];
+    let mut requested: Vec<(String, i64)> = requested.into_iter().collect();
+    requested.sort();
+    for (reason, count) in requested {
+        lines.push(format!(
+            "concurrent cycle requested {count} times, reason: {reason}"
+        ));
+    }
+    if initiated > 0 {
+        lines.push(format!("concurrent cycle initiated {initiated} times"));
+    }
+    let mut mixed: Vec<((&str, String), i64)> = mixed.into_iter().collect();
+    mixed.sort();
+    for ((decision, reason), count) in mixed {
+        lines.push(format!(
+            "mixed GCs {decision} {count} times, reason: {reason}"
+        ));
+    }
+    let skipped = count_mixed_gcs_skipped(pauses);
+    if skipped > 0 {
+        lines.push(format!(
+            "mixed GCs were skipped {skipped} times because reclaimable percentage was below G1HeapWastePercent"
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::pauses::GCPause;
+
+    use super::{
+        count_mixed_gcs_skipped, generate_ergonomics_summary, generate_prediction_table,
+        parse_ergonomics, ErgonomicsEvent, MixedGCDecision,
+    };
+
+    const CSET: &str = "54055.679: [G1Ergonomics (CSet Construction) finish choosing CSet, eden: 62 regions, survivors: 0 regions, old: 0 regions, predicted pause time: 307.19 ms, target pause time: 500.00 ms]";
+    const SKIPPED: &str = "54056.101: [G1Ergonomics (Mixed GCs) do not start mixed GCs, reason: reclaimable percentage not over threshold, candidate old regions: 46 regions, reclaimable: 77284736 bytes (1.80 %), threshold: 5.00 %]";
+    const REQUESTED: &str = "54055.954: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: occupancy higher than threshold, occupancy: 4664066048 bytes, allocation request: 0 bytes, threshold: 3238002675 bytes (25.00 %), source: end of GC]";
+
+    fn predicted_pause(predicted_millis: f64, pause_time_seconds: f64) -> GCPause {
+        GCPause {
+            gc_type: "G1 Evacuation Pause".to_string(),
+            pause_time_seconds,
+            ergonomics: vec![ErgonomicsEvent::CSetChosen {
+                eden_regions: 62,
+                survivor_regions: 0,
+                old_regions: 0,
+                predicted_pause_millis: predicted_millis,
+                target_pause_millis: 500.0,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_ergonomics() {
+        assert_eq!(
+            parse_ergonomics(CSET),
+            vec![ErgonomicsEvent::CSetChosen {
+                eden_regions: 62,
+                survivor_regions: 0,
+                old_regions: 0,
+                predicted_pause_millis: 307.19,
+                target_pause_millis: 500.0,
+            }]
+        );
+        assert_eq!(
+            parse_ergonomics(SKIPPED),
+            vec![ErgonomicsEvent::MixedGCs {
+                decision: MixedGCDecision::DoNotStart,
+                reason: "reclaimable percentage not over threshold".to_string(),
+                candidate_old_regions: Some(46),
+                reclaimable_percent: Some(1.8),
+                threshold_percent: Some(5.0),
+            }]
+        );
+        assert_eq!(
+            parse_ergonomics(REQUESTED),
+            vec![ErgonomicsEvent::ConcurrentCycleRequested {
+                reason: "occupancy higher than threshold".to_string(),
+                source: Some("end of GC".to_string()),
+            }]
+        );
+        assert_eq!(
+            parse_ergonomics("54055.952: [G1Ergonomics (Heap Sizing) expand the heap, requested expansion amount: 1060320051 bytes, attempted expansion amount: 1073741824 bytes]"),
+            vec![ErgonomicsEvent::Other {
+                category: "Heap Sizing".to_string(),
+                action: "expand the heap".to_string(),
+            }]
+        );
+        assert!(parse_ergonomics("[SoftReference, 0 refs, 0.0000831 secs]").is_empty());
+    }
+
+    #[test]
+    fn test_generate_prediction_table() {
+        let pauses = vec![
+            predicted_pause(300.0, 0.6),
+            predicted_pause(100.0, 0.05),
+            GCPause::default(),
+        ];
+        assert_eq!(
+            generate_prediction_table(&pauses),
+            "+---------------------+-------+--------------------+-----------------+-----------------+--------------+-------------+
+| GC                  | Count | Avg Predicted (ms) | Avg Actual (ms) | Over Prediction | Max Over (%) | Over Target |
++---------------------+-------+--------------------+-----------------+-----------------+--------------+-------------+
+| G1 Evacuation Pause |   2   |       200.00       |     325.00      |        1        |   100.00%    |      1      |
++---------------------+-------+--------------------+-----------------+-----------------+--------------+-------------+
+"
+        );
+        assert_eq!(generate_prediction_table(&[]), "");
+    }
+
+    #[test]
+    fn test_generate_ergonomics_summary() {
+        let pauses = vec![GCPause {
+            ergonomics: parse_ergonomics(&format!("{REQUESTED}\n{SKIPPED}\n{SKIPPED}")),
+            ..Default::default()
+        }];
+        assert_eq!(count_mixed_gcs_skipped(&pauses), 2);
+        assert_eq!(
+            generate_ergonomics_summary(&pauses),
+            "concurrent cycle requested 1 times, reason: occupancy higher than threshold
+mixed GCs not started 2 times, reason: reclaimable percentage not over threshold
+mixed GCs were skipped 2 times because reclaimable percentage was below G1HeapWastePercent"
+        );
+        assert_eq!(generate_ergonomics_summary(&[]), "");
+    }
+}
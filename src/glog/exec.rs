@@ -13,19 +13,66 @@
 // limitations under the License.
 
 use std::{
+    collections::HashMap,
     error::Error,
-    fs::File,
-    io::{BufRead, BufReader},
+    io::{self, BufRead, Read},
 };
 
+use time::{PrimitiveDateTime, UtcOffset};
+
 use super::{
+    classhistogram::{
+        generate_class_growth_table, generate_class_histogram_report, parse_class_histogram,
+    },
+    cms::{is_cms_pause, parse_cms_concurrent, parse_cms_pause},
+    concurrent::{generate_concurrent_table, generate_cycle_table, ConcurrentPhase},
+    ergonomics::{generate_ergonomics_summary, generate_prediction_table, parse_ergonomics},
     flags::{parse_gc_flags, Collector, GCFlags},
-    jvmstats::{parse_jdk_stats, parse_memory, MemoryStats},
+    fullgc::generate_full_gc_cause_table,
+    g1cycles::{
+        build_marking_cycles, generate_marking_cycle_table, is_g1_remark_or_cleanup,
+        parse_g1_concurrent, parse_g1_remark_or_cleanup,
+    },
+    g1gc::parse_g1_occupancy,
+    g1phases::{add_g1_phase, generate_g1_phase_table, parse_g1_phase_line},
+    input::{read_log_sources, read_stream_sources},
+    jvmflags::{generate_flag_problems, generate_jvm_flags_table, parse_jvm_flags},
+    jvmstats::{parse_jvm_info, parse_memory, parse_unified_jvm_info, JvmInfo, MemoryStats},
+    memorybudget::{
+        build_memory_budget, generate_memory_budget_summary, generate_memory_budget_table,
+    },
+    occupancy::{
+        generate_heap_after_gc_table, generate_metaspace_growth_table, generate_occupancy_table,
+        SpaceOccupancy,
+    },
+    parallel::{is_parallel_pause, parse_parallel_occupancy, parse_parallel_pause},
     pauses::{
-        generate_pause_table, has_gc, parse_full_gc_pause, parse_gc_pause, show_max_pause_times,
-        GCPause,
+        generate_pause_table, get_utc_offset, has_gc, parse_full_gc_pause, parse_gc_pause,
+        show_max_pause_times, GCPause,
     },
     recommendations::generate_recommendations,
+    references::{generate_reference_table, parse_references},
+    rotation::{
+        created_epoch_micros, expand_log_files, generate_log_files_table, merge_logs, order_logs,
+        parse_log_created_time, rotation_index,
+    },
+    safepoints::{
+        generate_safepoint_summary, generate_safepoint_table, generate_ttsp_outlier_table,
+        parse_application_time, parse_safepoint, Safepoint, TTSP_OUTLIER_SECONDS,
+    },
+    serial::{is_serial_pause, parse_serial_pause},
+    stalls::{generate_allocation_stall_summary, generate_allocation_stall_table, AllocationStall},
+    tenuring::{generate_age_table, generate_tenuring_summary, parse_tenuring},
+    times::{generate_parallelism_table, parse_cpu_times, parse_gc_workers},
+    unified::{
+        is_unified_log, parse_unified_allocation_stall, parse_unified_collector,
+        parse_unified_concurrent, parse_unified_cpu_times, parse_unified_cycle,
+        parse_unified_gc_id, parse_unified_gc_workers, parse_unified_occupancy,
+        parse_unified_pause, parse_unified_region_size_mb, parse_unified_version, SNIFF_LINES,
+    },
+    uptime::{
+        anchor_safepoints, anchor_to_jvm_start, find_dated_jvm_start_epoch, find_jvm_start_epoch,
+    },
 };
 
 fn is_log_finished(multiline_log: &str) -> bool {
@@ -50,11 +97,48 @@ fn is_log_finished(multiline_log: &str) -> bool {
     }
     start == end
 }
-pub fn exec(file_name: String) -> Result<String, Box<dyn Error>> {
-    let file = File::open(file_name)?;
-    let reader = BufReader::new(file);
+
+// everything read out of a single gc log file
+pub struct ParsedLog {
+    pub file_name: String,
+    //from the "GC log file created" header, 0 when the log does not have one
+    pub created_epoch_micros: i64,
+    //the header without a zone, kept until --tz is known when the log has no date stamps
+    pub created_time: Option<PrimitiveDateTime>,
+    pub gc_flags: GCFlags,
+    pub jvm_info: JvmInfo,
+    pub memory_stats: MemoryStats,
+    pub pauses: Vec<GCPause>,
+    pub concurrent_phases: Vec<ConcurrentPhase>,
+    pub cycles: Vec<ConcurrentPhase>,
+    //from -XX:+PrintGCApplicationStoppedTime or -Xlog:safepoint
+    pub safepoints: Vec<Safepoint>,
+    //zgc application threads waiting on memory, these are not pauses
+    pub allocation_stalls: Vec<AllocationStall>,
+}
+
+pub fn parse_log<R: BufRead>(file_name: String, reader: R) -> Result<ParsedLog, Box<dyn Error>> {
+    let mut lines = reader.lines();
+    //read the first few lines so we can pick the parser for the log format
+    let mut head: Vec<String> = vec![];
+    for line_result in lines.by_ref().take(SNIFF_LINES) {
+        head.push(line_result?);
+    }
+    let unified = is_unified_log(&head);
+    let all_lines = head.into_iter().map(Ok).chain(lines);
 
     let mut pauses: Vec<GCPause> = vec![];
+    let mut concurrent_phases: Vec<ConcurrentPhase> = vec![];
+    let mut cycles: Vec<ConcurrentPhase> = vec![];
+    let mut safepoints: Vec<Safepoint> = vec![];
+    let mut allocation_stalls: Vec<AllocationStall> = vec![];
+    //the application time is logged right before the safepoint it ran up to
+    let mut application_seconds: Option<f64> = None;
+    let mut cycle_starts: HashMap<String, f64> = HashMap::new();
+    let mut pending_occupancy: HashMap<String, Vec<SpaceOccupancy>> = HashMap::new();
+    let mut pending_workers: HashMap<String, u32> = HashMap::new();
+    //the cpu times are logged after the pause line
+    let mut pause_index: HashMap<String, usize> = HashMap::new();
     let mut gc_flags: GCFlags = GCFlags {
         collector: Collector::Unknown,
         max_heap_size_gb: 0.0,
@@ -64,11 +148,8 @@ pub fn exec(file_name: String) -> Result<String, Box<dyn Error>> {
         max_direct_memory_gb: 0.0,
         all_flags: vec![],
     };
-    let mut jdk_stats: String = "".to_string();
-    let mut memory_stats: MemoryStats = MemoryStats {
-        physical_memory_str: "".to_string(),
-        physical_memory_bytes: 0,
-    };
+    let mut jvm_info = JvmInfo::default();
+    let mut memory_stats = MemoryStats::default();
     let mut read_multiline = false;
     let mut multiline_log: String = "".to_string();
     let open_bracket = '[';
@@ -76,10 +157,87 @@ pub fn exec(file_name: String) -> Result<String, Box<dyn Error>> {
     let mut already_parsed_cmd = false;
     let mut already_parsed_flags = false;
     let mut already_parsed_memory = false;
-    for line_result in reader.lines() {
+    let mut created_time: Option<PrimitiveDateTime> = None;
+    //the header is in the same zone as the date stamps
+    let mut utc_offset: Option<UtcOffset> = None;
+    for line_result in all_lines {
         let line = line_result?;
+        if !unified {
+            if utc_offset.is_none() {
+                utc_offset = get_utc_offset(&line);
+            }
+            //concurrent phases show up on their own lines and in the middle of pauses
+            concurrent_phases.extend(parse_cms_concurrent(&line));
+            concurrent_phases.extend(parse_g1_concurrent(&line));
+        }
+        if let Some(mut safepoint) = parse_safepoint(&line) {
+            if safepoint.application_seconds.is_none() {
+                safepoint.application_seconds = application_seconds.take();
+            }
+            safepoints.push(safepoint);
+            continue;
+        } else if let Some(seconds) = parse_application_time(&line) {
+            application_seconds = Some(seconds);
+            continue;
+        }
 
-        if !already_parsed_cmd && line.starts_with("CommandLine flags: ") {
+        if unified {
+            let region_size_bytes = (gc_flags.region_size_mb * 1024.0 * 1024.0) as i64;
+            if let Some(mut pause) = parse_unified_pause(&line) {
+                //the per space occupancy is logged before the pause line
+                if let Some(occupancy) =
+                    parse_unified_gc_id(&line).and_then(|id| pending_occupancy.remove(&id))
+                {
+                    pause.occupancy.extend(occupancy);
+                }
+                if let Some(id) = parse_unified_gc_id(&line) {
+                    pause.gc_workers = pending_workers.remove(&id);
+                    pause_index.insert(id, pauses.len());
+                }
+                pauses.push(pause);
+            } else if let Some(stall) = parse_unified_allocation_stall(&line) {
+                allocation_stalls.push(stall);
+            } else if let Some((gc_id, occupancy)) =
+                parse_unified_occupancy(&line, region_size_bytes)
+            {
+                pending_occupancy.entry(gc_id).or_default().push(occupancy);
+            } else if let Some((gc_id, workers)) = parse_unified_gc_workers(&line) {
+                let max_workers = pending_workers.entry(gc_id).or_default();
+                *max_workers = workers.max(*max_workers);
+            } else if let Some((gc_id, cpu_times)) = parse_unified_cpu_times(&line) {
+                if let Some(index) = pause_index.get(&gc_id) {
+                    pauses[*index].cpu_times = Some(cpu_times);
+                }
+            } else if let Some(phase) = parse_unified_concurrent(&line) {
+                concurrent_phases.push(phase);
+            } else if let Some(cycle) = parse_unified_cycle(&line) {
+                if !cycle.finished {
+                    cycle_starts.insert(cycle.gc_id, cycle.seconds);
+                } else {
+                    //the start is missing with -Xlog:gc or when it was logged in the previous
+                    //file, so the duration on the end line comes first
+                    let start = cycle_starts.remove(&cycle.gc_id);
+                    if let Some(duration_seconds) = cycle
+                        .duration_seconds
+                        .or_else(|| start.map(|start| cycle.seconds - start))
+                    {
+                        cycles.push(ConcurrentPhase {
+                            name: cycle.name,
+                            time_epoch_micros: cycle.time_epoch_micros,
+                            duration_seconds,
+                        });
+                    }
+                }
+            } else if let Some(collector) = parse_unified_collector(&line) {
+                gc_flags.collector = collector;
+            } else if let Some(region_size_mb) = parse_unified_region_size_mb(&line) {
+                gc_flags.region_size_mb = region_size_mb;
+            } else if let Some(version) = parse_unified_version(&line) {
+                jvm_info = parse_unified_jvm_info(version);
+            }
+        } else if let Some(time) = parse_log_created_time(&line) {
+            created_time = Some(time);
+        } else if !already_parsed_cmd && line.starts_with("CommandLine flags: ") {
             already_parsed_cmd = true;
             gc_flags = parse_gc_flags(line, memory_stats.physical_memory_bytes);
         } else if !already_parsed_memory && line.starts_with("Memory: ") {
@@ -89,7 +247,24 @@ pub fn exec(file_name: String) -> Result<String, Box<dyn Error>> {
             && (line.starts_with("OpenJDK ") || line.starts_with("Java"))
         {
             already_parsed_flags = true;
-            jdk_stats = parse_jdk_stats(line)
+            jvm_info = parse_jvm_info(line)
+        } else if !read_multiline && line.trim_start().starts_with("[Times: ") {
+            //G1 and detailed full gcs write the cpu time on its own line after the pause
+            if let Some(pause) = pauses.last_mut() {
+                if pause.cpu_times.is_none() {
+                    pause.cpu_times = parse_cpu_times(&line);
+                }
+            }
+        } else if !read_multiline && line.contains("[Eden: ") {
+            //G1 writes the occupancy on its own line after the pause
+            if let Some(pause) = pauses.last_mut() {
+                pause.occupancy.extend(parse_g1_occupancy(&line));
+            }
+        } else if !read_multiline && line.contains("[G1Ergonomics ") && !has_gc(&line) {
+            //decisions made outside of a pause such as starting a cycle for a humongous allocation
+            if let Some(pause) = pauses.last_mut() {
+                pause.ergonomics.extend(parse_ergonomics(&line));
+            }
         } else if read_multiline {
             let new_line = format!("\n{line}");
             let new_line_str = new_line.as_str();
@@ -100,12 +275,53 @@ pub fn exec(file_name: String) -> Result<String, Box<dyn Error>> {
                     continue;
                 }
                 read_multiline = false;
-                let pause = parse_full_gc_pause(multiline_log)?;
+                let cpu_times = parse_cpu_times(&multiline_log);
+                let references = parse_references(&multiline_log);
+                let ergonomics = parse_ergonomics(&multiline_log);
+                let histogram_before = parse_class_histogram(&multiline_log, "before full gc");
+                let histogram_after = parse_class_histogram(&multiline_log, "after full gc");
+                let tenuring = parse_tenuring(&multiline_log);
+                let mut pause = if is_g1_remark_or_cleanup(&multiline_log) {
+                    parse_g1_remark_or_cleanup(multiline_log)?
+                } else if is_cms_pause(&multiline_log) {
+                    parse_cms_pause(multiline_log)?
+                } else if is_serial_pause(&multiline_log) {
+                    parse_serial_pause(multiline_log)?
+                } else {
+                    let occupancy = if is_parallel_pause(&multiline_log) {
+                        parse_parallel_occupancy(&multiline_log)
+                    } else if multiline_log.contains("[Eden: ") {
+                        parse_g1_occupancy(&multiline_log)
+                    } else {
+                        vec![]
+                    };
+                    let mut pause = parse_full_gc_pause(multiline_log)?;
+                    pause.occupancy = occupancy;
+                    pause
+                };
                 multiline_log = "".to_string();
+                pause.cpu_times = cpu_times;
+                pause.references = references;
+                pause.ergonomics = ergonomics;
+                pause.histogram_before = histogram_before;
+                pause.histogram_after = histogram_after;
+                pause.tenuring = tenuring;
                 if pause.gc_type != "after full gc" {
                     pauses.push(pause);
+                } else if let Some(full_gc) = pauses.last_mut() {
+                    //the after histogram is logged on its own once the full gc is done
+                    if full_gc.histogram_after.is_none() {
+                        full_gc.histogram_after = pause.histogram_after;
+                    }
                 }
             }
+        } else if let Some((depth, phase)) = parse_g1_phase_line(&line) {
+            if let Some(pause) = pauses.last_mut() {
+                if phase.name == "Parallel Time" {
+                    pause.gc_workers = parse_gc_workers(&line);
+                }
+                add_g1_phase(&mut pause.g1_phases, depth, phase);
+            }
         } else if !read_multiline
             && line.chars().filter(|x| x == &open_bracket).count()
                 != line.chars().filter(|x| x == &close_bracket).count()
@@ -114,40 +330,316 @@ pub fn exec(file_name: String) -> Result<String, Box<dyn Error>> {
             let new_line = format!("{line}\n");
             let new_line_str = new_line.as_str();
             multiline_log.push_str(new_line_str);
+        } else if is_g1_remark_or_cleanup(&line) {
+            let cpu_times = parse_cpu_times(&line);
+            let references = parse_references(&line);
+            let mut pause = parse_g1_remark_or_cleanup(line)?;
+            pause.cpu_times = cpu_times;
+            pause.references = references;
+            pauses.push(pause);
         } else if has_gc(&line) {
-            let pause: GCPause = parse_gc_pause(line)?;
+            let cpu_times = parse_cpu_times(&line);
+            let references = parse_references(&line);
+            let ergonomics = parse_ergonomics(&line);
+            let mut pause: GCPause = if is_parallel_pause(&line) {
+                parse_parallel_pause(line)?
+            } else if is_cms_pause(&line) {
+                parse_cms_pause(line)?
+            } else if is_serial_pause(&line) {
+                parse_serial_pause(line)?
+            } else {
+                parse_gc_pause(line)?
+            };
+            pause.cpu_times = cpu_times;
+            pause.references = references;
+            pause.ergonomics = ergonomics;
             pauses.push(pause);
         }
     }
+    jvm_info.unified_logging = unified;
+    let created_epoch_micros = match (created_time, utc_offset) {
+        (Some(created_time), Some(offset)) => created_epoch_micros(created_time, offset),
+        _ => 0,
+    };
+    Ok(ParsedLog {
+        file_name,
+        created_epoch_micros,
+        created_time,
+        gc_flags,
+        jvm_info,
+        memory_stats,
+        pauses,
+        concurrent_phases,
+        cycles,
+        safepoints,
+        allocation_stalls,
+    })
+}
+
+fn generate_report(log: ParsedLog, log_files: String, offset: UtcOffset) -> String {
+    let pauses = log.pauses;
+    let gc_flags = log.gc_flags;
     let headline_max = "Max Pause:".to_string();
     let underline_max = "--------".to_string();
-    let pause_table_max = show_max_pause_times(&pauses);
+    let pause_table_max = show_max_pause_times(&pauses, offset);
 
     let headline = "GC Summary:".to_string();
     let underline = "--------".to_string();
     let pause_table = generate_pause_table(&pauses);
 
-    let recommendations = generate_recommendations(&gc_flags, &pauses);
-    Ok([
+    let mut report = vec![
         headline,
         underline,
-        jdk_stats,
-        memory_stats.physical_memory_str,
+        log.jvm_info.header.clone(),
+        log.memory_stats.to_string(),
         gc_flags.to_string(),
-        headline_max,
-        underline_max,
-        pause_table_max,
-        pause_table,
-        recommendations,
-    ]
-    .join("\n"))
+    ];
+    if !log_files.is_empty() {
+        report.push("Log Files:".to_string());
+        report.push("--------".to_string());
+        report.push(log_files);
+    }
+    let jvm_flags = parse_jvm_flags(&gc_flags.all_flags, &gc_flags.collector);
+    if !gc_flags.all_flags.is_empty() {
+        report.push("JVM Flags:".to_string());
+        report.push("--------".to_string());
+        report.push(generate_jvm_flags_table(&jvm_flags));
+        let flag_problems = generate_flag_problems(&jvm_flags);
+        if !flag_problems.is_empty() {
+            report.push(flag_problems);
+        }
+    }
+    if let Some(budget) = build_memory_budget(&gc_flags, &jvm_flags, &log.memory_stats, &pauses) {
+        report.push("Memory Budget:".to_string());
+        report.push("--------".to_string());
+        report.push(generate_memory_budget_table(&budget));
+        report.push(generate_memory_budget_summary(&budget));
+    }
+    report.extend([headline_max, underline_max, pause_table_max, pause_table]);
+    let full_gc_cause_table = generate_full_gc_cause_table(&pauses);
+    if !full_gc_cause_table.is_empty() {
+        report.push("Full GC Causes:".to_string());
+        report.push("--------".to_string());
+        report.push(full_gc_cause_table);
+    }
+    let occupancy_table = generate_occupancy_table(&pauses);
+    if !occupancy_table.is_empty() {
+        report.push("Space Occupancy:".to_string());
+        report.push("--------".to_string());
+        report.push(occupancy_table);
+    }
+    let g1_phase_table = generate_g1_phase_table(&pauses);
+    if !g1_phase_table.is_empty() {
+        report.push("G1 Pause Phases:".to_string());
+        report.push("--------".to_string());
+        report.push(g1_phase_table);
+    }
+    let prediction_table = generate_prediction_table(&pauses);
+    let ergonomics_summary = generate_ergonomics_summary(&pauses);
+    if !prediction_table.is_empty() || !ergonomics_summary.is_empty() {
+        report.push("G1 Ergonomics:".to_string());
+        report.push("--------".to_string());
+        if !prediction_table.is_empty() {
+            report.push(prediction_table);
+        }
+        if !ergonomics_summary.is_empty() {
+            report.push(ergonomics_summary);
+        }
+    }
+    let reference_table = generate_reference_table(&pauses);
+    if !reference_table.is_empty() {
+        report.push("Reference Processing:".to_string());
+        report.push("--------".to_string());
+        report.push(reference_table);
+    }
+    let class_histogram_report = generate_class_histogram_report(&pauses, offset);
+    if !class_histogram_report.is_empty() {
+        report.push("Class Histograms:".to_string());
+        report.push("--------".to_string());
+        report.push(class_histogram_report);
+    }
+    let class_growth_table = generate_class_growth_table(&pauses);
+    if !class_growth_table.is_empty() {
+        report.push("Class Growth Across Full GCs:".to_string());
+        report.push("--------".to_string());
+        report.push(class_growth_table);
+    }
+    let tenuring_summary = generate_tenuring_summary(&pauses);
+    if !tenuring_summary.is_empty() {
+        report.push("Tenuring Distribution:".to_string());
+        report.push("--------".to_string());
+        report.push(tenuring_summary);
+        let age_table = generate_age_table(&pauses);
+        if !age_table.is_empty() {
+            report.push(age_table);
+        }
+    }
+    let heap_after_gc_table = generate_heap_after_gc_table(&pauses);
+    if !heap_after_gc_table.is_empty() {
+        report.push("Heap After GC:".to_string());
+        report.push("--------".to_string());
+        report.push(heap_after_gc_table);
+    }
+    let metaspace_table = generate_metaspace_growth_table(&pauses);
+    if !metaspace_table.is_empty() {
+        report.push("Metaspace Growth:".to_string());
+        report.push("--------".to_string());
+        report.push(metaspace_table);
+    }
+    let parallelism_table =
+        generate_parallelism_table(&jvm_flags, &gc_flags.collector, &log.jvm_info, &pauses);
+    if !parallelism_table.is_empty() {
+        report.push("GC Parallelism:".to_string());
+        report.push("--------".to_string());
+        report.push(parallelism_table);
+    }
+    let concurrent_table = generate_concurrent_table(&pauses, &log.concurrent_phases);
+    if !concurrent_table.is_empty() {
+        report.push("Concurrent Phases:".to_string());
+        report.push("--------".to_string());
+        report.push(concurrent_table);
+    }
+    let cycle_table = generate_cycle_table(&log.cycles);
+    if !cycle_table.is_empty() {
+        report.push("GC Cycles:".to_string());
+        report.push("--------".to_string());
+        report.push(cycle_table);
+    }
+    let safepoint_summary = generate_safepoint_summary(&log.safepoints, &pauses);
+    if !safepoint_summary.is_empty() {
+        report.push("Safepoints:".to_string());
+        report.push("--------".to_string());
+        report.push(safepoint_summary);
+        report.push(generate_safepoint_table(&log.safepoints));
+        let outlier_table = generate_ttsp_outlier_table(&log.safepoints, offset);
+        if !outlier_table.is_empty() {
+            report.push(format!(
+                "safepoints that took more than {} ms to reach:",
+                TTSP_OUTLIER_SECONDS * 1000.0
+            ));
+            report.push(outlier_table);
+        }
+    }
+    let allocation_stall_summary = generate_allocation_stall_summary(&log.allocation_stalls);
+    if !allocation_stall_summary.is_empty() {
+        report.push("Allocation Stalls:".to_string());
+        report.push("--------".to_string());
+        report.push(allocation_stall_summary);
+        report.push(generate_allocation_stall_table(&log.allocation_stalls));
+    }
+    let marking_cycle_table =
+        generate_marking_cycle_table(&build_marking_cycles(&pauses, &log.concurrent_phases));
+    if !marking_cycle_table.is_empty() {
+        report.push("G1 Marking Cycles:".to_string());
+        report.push("--------".to_string());
+        report.push(marking_cycle_table);
+    }
+    report.push(generate_recommendations(
+        &gc_flags,
+        &pauses,
+        &log.safepoints,
+        &log.allocation_stalls,
+        &log.jvm_info,
+        &log.memory_stats,
+    ));
+    report.join("\n")
+}
+
+#[derive(Default)]
+pub struct ExecOptions {
+    //anchors logs that only have uptimes, overrides what is found in the logs
+    pub jvm_start_epoch_micros: Option<i64>,
+    //zone the report times are shown in, UTC when not set
+    pub tz: Option<UtcOffset>,
+}
+
+pub fn exec(file_name: String) -> Result<String, Box<dyn Error>> {
+    exec_files(&[file_name], &ExecOptions::default())
+}
+
+// places the created header in the --tz zone when the log had no date stamps to take it from
+fn place_created_time(log: &mut ParsedLog, options: &ExecOptions) {
+    if log.created_epoch_micros == 0 {
+        if let Some(created_time) = log.created_time {
+            log.created_epoch_micros =
+                created_epoch_micros(created_time, options.tz.unwrap_or(UtcOffset::UTC));
+        }
+    }
+}
+
+// gives every pause that only has an uptime a wall clock time. All the files come from one jvm
+// so they share one start, a date stamp anywhere wins over the headers and only the header of
+// the earliest file can be the start of the jvm, and only when it was not rotated out
+fn anchor_logs(logs: &mut [ParsedLog], options: &ExecOptions) {
+    let jvm_start_epoch_micros = options
+        .jvm_start_epoch_micros
+        .or_else(|| {
+            logs.iter()
+                .find_map(|log| find_dated_jvm_start_epoch(&log.pauses))
+        })
+        .or_else(|| {
+            logs.iter().enumerate().find_map(|(position, log)| {
+                let first_file = position == 0 && rotation_index(&log.file_name) == 0;
+                find_jvm_start_epoch(&log.pauses, log.created_epoch_micros, first_file)
+            })
+        });
+    if let Some(jvm_start_epoch_micros) = jvm_start_epoch_micros {
+        for log in logs {
+            anchor_to_jvm_start(&mut log.pauses, jvm_start_epoch_micros);
+            anchor_safepoints(&mut log.safepoints, jvm_start_epoch_micros);
+        }
+    }
+}
+
+// reads every file, and every gc log in a directory, as one timeline. A file name of - reads
+// the log from stdin
+pub fn exec_files(file_names: &[String], options: &ExecOptions) -> Result<String, Box<dyn Error>> {
+    exec_files_with_stdin(file_names, options, io::stdin())
+}
+
+// the same as exec_files with the reader that - stands for passed in
+pub fn exec_files_with_stdin<R: Read>(
+    file_names: &[String],
+    options: &ExecOptions,
+    mut stdin: R,
+) -> Result<String, Box<dyn Error>> {
+    let mut logs = vec![];
+    let mut visit = |name: String, reader: &mut dyn BufRead| {
+        let mut log = parse_log(name, reader)?;
+        place_created_time(&mut log, options);
+        logs.push(log);
+        Ok(())
+    };
+    for file_name in file_names {
+        if file_name == "-" {
+            read_stream_sources("stdin".to_string(), Box::new(&mut stdin), &mut visit)?;
+            continue;
+        }
+        for path in expand_log_files(&[file_name.to_string()])? {
+            read_log_sources(&path, &mut visit)?;
+        }
+    }
+    let offset = options.tz.unwrap_or(UtcOffset::UTC);
+    let mut logs = order_logs(logs);
+    anchor_logs(&mut logs, options);
+    let log_files = if logs.len() > 1 {
+        generate_log_files_table(&logs, offset)
+    } else {
+        "".to_string()
+    };
+    match merge_logs(logs) {
+        Some(merged) => Ok(generate_report(merged, log_files, offset)),
+        None => Ok("No Pauses".to_string()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::glog::exec::exec;
-    use std::io::Write;
+    use crate::glog::exec::{exec, exec_files, exec_files_with_stdin, ExecOptions};
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::{Cursor, Write};
     use tempfile::NamedTempFile;
+    use time::UtcOffset;
 
     use super::is_log_finished;
 
@@ -229,6 +721,21 @@ Total      34967203     6624451024
             parsed.contains("3.66"),
             "expected 3.66 in the output but had {parsed}"
         );
+        assert!(parsed.contains("Reference Processing:"), "was {parsed}");
+        assert!(
+            parsed.contains("| SoftReference      |   1    |   24521    |"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("Full GC Causes:"), "was {parsed}");
+        assert!(
+            parsed.contains("| Allocation Failure |   1   |    3.66    |   3.66   |"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("Class Histograms:"), "was {parsed}");
+        assert!(
+            parsed.contains("| org.apache.arrow.memory.ArrowBuf               |     2769733      |  169.05 mb   |     1457412     |  88.95 mb   |  52.62%  |"),
+            "was {parsed}"
+        );
     }
 
     #[test]
@@ -285,6 +792,15 @@ Total      99999999     8643256886
             parsed.contains("4.34"),
             "expected 4.34 in the output but had {parsed}"
         );
+        assert!(parsed.contains("Memory Budget:"), "was {parsed}");
+        assert!(
+            parsed.contains("| Metaspace     | 108.51 mb |     max used in log     |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("system ram:          122.07 gb, the jvm can commit 105.54% of it"),
+            "was {parsed}"
+        );
     }
 
     #[test]
@@ -351,6 +867,17 @@ Total      99999999     8643256886
             parsed.contains("0.07"),
             "did not find 0.07. File has {parsed}"
         );
+        assert!(
+            parsed.contains("| Survivors |   1   | 192.00 mb  | 224.00 mb |   -32.00 mb   |"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("G1 Pause Phases:"), "was {parsed}");
+        assert!(parsed.contains("|    Termination    |"), "was {parsed}");
+        assert!(parsed.contains("Heap After GC:"), "was {parsed}");
+        assert!(
+            parsed.contains("| G1 Evacuation Pause - (young) |   1   | 1.17 | 0.23 | 0.07 |    16.71    |     23     |"),
+            "was {parsed}"
+        );
     }
 
     #[test]
@@ -380,6 +907,533 @@ CommandLine flags: -XX:CICompilerCount=4 -XX:ConcGCThreads=2 -XX:ErrorFile=/opt/
             parsed.contains("0.28"),
             "did not find 0.28. File has {parsed}"
         );
+        assert!(parsed.contains("JVM Flags:"), "was {parsed}");
+        assert!(
+            parsed.contains("| MaxGCPauseMillis                 |                500                |   set   |"),
+            "was {parsed}"
+        );
+        assert!(!parsed.contains("unknown flags:"), "was {parsed}");
+        assert!(parsed.contains("G1 Ergonomics:"), "was {parsed}");
+        assert!(
+            parsed.contains("|       307.19       |     275.40      |        0        |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains(
+                "concurrent cycle requested 1 times, reason: occupancy higher than threshold"
+            ),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_tenuring_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "CommandLine flags: -XX:MaxHeapSize=17179869184 -XX:+PrintGC -XX:+PrintGCDateStamps -XX:+PrintGCDetails -XX:+PrintGCTimeStamps -XX:+PrintTenuringDistribution -XX:+UseG1GC
+2022-08-24T01:50:12.381+0000: 190002.134: [GC pause (G1 Evacuation Pause) (young)
+Desired survivor size 54525952 bytes, new threshold 1 (max 15)
+- age   1:   61232376 bytes,   61232376 total
+, 0.0412567 secs]
+   [Eden: 1024.0M(1024.0M)->0.0B(1024.0M) Survivors: 32.0M->64.0M Heap: 9000.0M(16384.0M)->8000.0M(16384.0M)]
+ [Times: user=0.20 sys=0.00, real=0.04 secs]
+2022-08-24T01:50:22.381+0000: 190012.134: [GC pause (G1 Evacuation Pause) (young)
+Desired survivor size 54525952 bytes, new threshold 1 (max 15)
+- age   1:   58232376 bytes,   58232376 total
+, 0.0412567 secs]
+   [Eden: 1024.0M(1024.0M)->0.0B(1024.0M) Survivors: 32.0M->64.0M Heap: 9000.0M(16384.0M)->8000.0M(16384.0M)]
+ [Times: user=0.20 sys=0.00, real=0.04 secs]
+2022-08-24T01:50:32.381+0000: 190022.134: [GC pause (G1 Evacuation Pause) (young)
+Desired survivor size 54525952 bytes, new threshold 2 (max 15)
+- age   1:   41232376 bytes,   41232376 total
+, 0.0412567 secs]
+   [Eden: 1024.0M(1024.0M)->0.0B(1024.0M) Survivors: 32.0M->64.0M Heap: 9000.0M(16384.0M)->8000.0M(16384.0M)]
+ [Times: user=0.20 sys=0.00, real=0.04 secs]
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let parsed = exec(new_file.to_str().unwrap().to_string()).expect("failed to parse");
+        assert!(parsed.contains("Tenuring Distribution:"), "was {parsed}");
+        assert!(
+            parsed.contains("tenuring threshold:       min 1, avg 1.3, max 2 (max 15)"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("| 1   |   3    | 51.08 mb  | 58.40 mb  |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("* the tenuring threshold dropped to 1 in 2 of 3 young pauses"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_g1_marking_cycle_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)
+CommandLine flags: -XX:G1HeapRegionSize=33554432 -XX:InitialHeapSize=17179869184 -XX:InitiatingHeapOccupancyPercent=25 -XX:MaxHeapSize=17179869184 -XX:+PrintGC -XX:+PrintGCDateStamps -XX:+PrintGCDetails -XX:+PrintGCTimeStamps -XX:+UseG1GC
+2022-07-22T18:41:06.500+0000: 54055.939: [GC pause (G1 Humongous Allocation) (young) (initial-mark), 0.0500000 secs]
+    [Eden: 1024.0M(1024.0M)->0.0B(1024.0M) Survivors: 0.0B->0.0B Heap: 9000.0M(16384.0M)->8000.0M(16384.0M)]
+ [Times: user=0.20 sys=0.00, real=0.05 secs]
+2022-07-22T18:41:06.551+0000: 54055.990: [GC concurrent-root-region-scan-start]
+2022-07-22T18:41:06.600+0000: 54056.039: [GC concurrent-root-region-scan-end, 0.0490000 secs]
+2022-07-22T18:41:06.600+0000: 54056.039: [GC concurrent-mark-start]
+2022-07-22T18:41:08.000+0000: 54057.439: [GC concurrent-mark-end, 1.4000000 secs]
+2022-07-22T18:41:08.001+0000: 54057.440: [GC remark 2022-07-22T18:41:08.001+0000: 54057.440: [Finalize Marking, 0.0012 secs] 2022-07-22T18:41:08.002+0000: 54057.441: [GC ref-proc, 0.0100 secs] 2022-07-22T18:41:08.012+0000: 54057.451: [Unloading, 0.0200 secs], 0.0450000 secs]
+ [Times: user=0.20 sys=0.00, real=0.05 secs]
+2022-07-22T18:41:08.050+0000: 54057.489: [GC cleanup 8500M->8200M(16384M), 0.0100000 secs]
+ [Times: user=0.02 sys=0.00, real=0.01 secs]
+2022-07-22T18:41:08.060+0000: 54057.499: [GC concurrent-cleanup-start]
+2022-07-22T18:41:08.061+0000: 54057.500: [GC concurrent-cleanup-end, 0.0010000 secs]
+2022-07-22T18:42:00.000+0000: 54109.439: [GC pause (G1 Evacuation Pause) (young) (initial-mark), 0.0400000 secs]
+    [Eden: 1024.0M(1024.0M)->0.0B(1024.0M) Survivors: 0.0B->0.0B Heap: 9000.0M(16384.0M)->8000.0M(16384.0M)]
+2022-07-22T18:42:01.000+0000: 54110.439: [Full GC (Allocation Failure)  16000M->7000M(16384M), 5.0000000 secs]
+    [Eden: 0.0B(1024.0M)->0.0B(1024.0M) Survivors: 0.0B->0.0B Heap: 16000.0M(16384.0M)->7000.0M(16384.0M)], [Metaspace: 100000K->100000K(1100000K)]
+2022-07-22T18:42:06.100+0000: 54115.539: [GC concurrent-mark-abort]
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(parsed.contains("| Remark "), "was {parsed}");
+        assert!(parsed.contains("| Cleanup "), "was {parsed}");
+        assert!(parsed.contains("G1 Marking Cycles:"), "was {parsed}");
+        assert!(
+            parsed
+                .contains("cycles: 2, completed: 1, aborted: 1, Full GC during concurrent mark: 1"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("| G1-concurrent-mark  "), "was {parsed}");
+        assert!(
+            parsed.contains("Lower -XX:InitiatingHeapOccupancyPercent from 25 to 20"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_parallel_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)
+CommandLine flags: -XX:InitialHeapSize=2048000000 -XX:MaxHeapSize=17179869184 -XX:+PrintGC -XX:+PrintGCDateStamps -XX:+PrintGCDetails -XX:+PrintGCTimeStamps -XX:+UseParallelGC
+2022-01-02T11:11:01.111+0000: 1.234: [GC (Allocation Failure) [PSYoungGen: 524288K->65536K(611840K)] 524288K->70000K(2010112K), 0.0523 secs] [Times: user=0.13 sys=0.02, real=0.05 secs]
+2022-01-02T11:11:02.111+0000: 2.345: [Full GC (Ergonomics) [PSYoungGen: 65536K->0K(611840K)] [ParOldGen: 1398000K->1390000K(1398272K)] 1463536K->1390000K(2010112K), [Metaspace: 3000K->3000K(1056768K)], 2.5012345 secs] [Times: user=9.03 sys=0.00, real=2.50 secs]
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(parsed.contains("Space Occupancy:"), "was {parsed}");
+        assert!(
+            parsed.contains("| Full GC - (Ergonomics) |    Old    |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("1 Full GCs started with the old generation at least 90% full"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_system_gc_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)
+CommandLine flags: -XX:InitialHeapSize=2048000000 -XX:MaxHeapSize=17179869184 -XX:+PrintGC -XX:+PrintGCDateStamps -XX:+PrintGCDetails -XX:+PrintGCTimeStamps -XX:+UseG1GC
+2022-01-02T11:11:01.111+0000: 1.234: [GC pause (G1 Evacuation Pause) (young), 0.0110001 secs]
+2022-01-02T11:11:02.111+0000: 2.345: [Full GC (System.gc())  880219K->614437K(2707200K), 1.5111111 secs]
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(parsed.contains("Full GC - (System.gc())"), "was {parsed}");
+        assert!(
+            parsed.contains("1 Full GCs were caused by System.gc()"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("Add -XX:+ExplicitGCInvokesConcurrent"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_serial_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "Memory: 4k page, physical 1000000k(500000k free), swap 0k(0k free)
+CommandLine flags: -XX:InitialHeapSize=16252928 -XX:MaxHeapSize=16252928 -XX:+PrintGC -XX:+PrintGCDetails -XX:+PrintGCTimeStamps -XX:+UseSerialGC
+0.500: [GC (Allocation Failure) 0.500: [DefNew: 4416K->512K(4928K), 0.0035 secs] 4416K->1500K(15872K), 0.0036 secs] [Times: user=0.00 sys=0.00, real=0.00 secs]
+2.000: [Full GC (Allocation Failure) 2.000: [Tenured: 10000K->8000K(10944K), 0.0200 secs] 14000K->8000K(15872K), [Metaspace: 2000K->2000K(1056768K)], 0.0201 secs] [Times: user=0.02 sys=0.00, real=0.02 secs]
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(
+            parsed.contains("collector:           SerialGC"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("Space Occupancy:"), "was {parsed}");
+        assert!(
+            parsed.contains("| Allocation Failure             |   Young   |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("| Full GC - (Allocation Failure) |    Old    |"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_cms_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)
+CommandLine flags: -XX:CMSInitiatingOccupancyFraction=80 -XX:InitialHeapSize=2048000000 -XX:MaxHeapSize=2048000000 -XX:+PrintGC -XX:+PrintGCDateStamps -XX:+PrintGCDetails -XX:+PrintGCTimeStamps -XX:+UseConcMarkSweepGC -XX:+UseParNewGC
+2022-01-02T11:11:01.111+0000: 1.234: [GC (Allocation Failure) 2022-01-02T11:11:01.111+0000: 1.234: [ParNew: 30720K->3392K(30720K), 0.0123 secs] 40000K->15000K(99008K), 0.0125 secs] [Times: user=0.03 sys=0.00, real=0.01 secs]
+2022-01-02T11:11:02.111+0000: 2.345: [GC (CMS Initial Mark) [1 CMS-initial-mark: 60000K(68288K)] 65000K(99008K), 0.0012 secs] [Times: user=0.00 sys=0.00, real=0.00 secs]
+2022-01-02T11:11:02.112+0000: 2.346: [CMS-concurrent-mark-start]
+2022-01-02T11:11:02.166+0000: 2.400: [CMS-concurrent-mark: 0.050/0.054 secs] [Times: user=0.10 sys=0.00, real=0.05 secs]
+2022-01-02T11:11:02.166+0000: 2.400: [CMS-concurrent-preclean-start]
+2022-01-02T11:11:02.170+0000: 2.404: [CMS-concurrent-preclean: 0.004/0.004 secs] [Times: user=0.00 sys=0.00, real=0.00 secs]
+2022-01-02T11:11:03.000+0000: 5.000: [GC (Allocation Failure) 2022-01-02T11:11:03.000+0000: 5.000: [ParNew: 30720K->30720K(30720K), 0.0000 secs]2022-01-02T11:11:03.000+0000: 5.000: [CMS2022-01-02T11:11:03.100+0000: 5.100: [CMS-concurrent-abortable-preclean: 0.100/0.120 secs] [Times: user=0.20 sys=0.00, real=0.12 secs]
+ (concurrent mode failure): 68000K->50000K(68288K), 0.5000000 secs] 98720K->50000K(99008K), [Metaspace: 3000K->3000K(1056768K)], 0.5123456 secs] [Times: user=0.50 sys=0.00, real=0.51 secs]
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(parsed.contains("collector:           CMS"), "was {parsed}");
+        assert!(parsed.contains("CMS Initial Mark"), "was {parsed}");
+        assert!(
+            parsed.contains("Allocation Failure - (concurrent mode failure)"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("Concurrent Phases:"), "was {parsed}");
+        assert!(
+            parsed.contains("stop the world time: 0.53 seconds"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("CMS-concurrent-abortable-preclean"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("-XX:CMSInitiatingOccupancyFraction=70"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_unified_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "[2023-01-01T10:00:00.001+0000][0.005s][info][gc] Using G1
+[2023-01-01T10:00:00.010+0000][0.014s][info][gc,init] Version: 17.0.2+8 (release)
+[2023-01-01T10:00:00.010+0000][0.014s][info][gc,init] Heap Region Size: 4M
+[2023-01-01T10:00:12.000+0000][12.004s][info][gc,start    ] GC(0) Pause Young (Normal) (G1 Evacuation Pause)
+[2023-01-01T10:00:12.000+0000][12.004s][info][gc,task     ] GC(0) Using 8 workers of 8 for evacuation
+[2023-01-01T10:00:12.123+0000][12.127s][info][gc,heap     ] GC(0) Eden regions: 96->0(100)
+[2023-01-01T10:00:12.123+0000][12.127s][info][gc,heap     ] GC(0) Survivor regions: 0->4(13)
+[2023-01-01T10:00:12.123+0000][12.127s][info][gc,metaspace] GC(0) Metaspace: 21248K(21504K)->21248K(21504K) NonClass: 18816K(18944K)->18816K(18944K) Class: 2432K(2560K)->2432K(2560K)
+[2023-01-01T10:00:12.123+0000][12.127s][info][gc          ] GC(0) Pause Young (Normal) (G1 Evacuation Pause) 512M->128M(2048M) 123.456ms
+[2023-01-01T10:00:12.123+0000][12.127s][info][gc,cpu      ] GC(0) User=0.48s Sys=0.01s Real=0.12s
+[2023-01-01T10:00:20.000+0000][20.004s][info][gc          ] GC(1) Pause Young (Concurrent Start) (G1 Humongous Allocation) 900M->850M(2048M) 45.1ms
+[2023-01-01T10:00:30.000+0000][30.004s][info][gc,metaspace] GC(2) Metaspace: 25000K(25600K)->24000K(25600K) NonClass: 18816K(18944K)->18816K(18944K) Class: 2432K(2560K)->2432K(2560K)
+[2023-01-01T10:00:30.000+0000][30.004s][info][gc          ] GC(2) Pause Full (System.gc()) 600M->200M(2048M) 1456.7ms
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(parsed.contains("collector:           G1GC"), "was {parsed}");
+        assert!(
+            parsed.contains("region size:         4.00 mb"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("JDK 17.0.2+8 (release)"), "was {parsed}");
+        assert!(
+            parsed.contains("G1 Evacuation Pause - (young)"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("G1 Humongous Allocation - (concurrent start)(young)"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("Full GC - (System.gc())"), "was {parsed}");
+        assert!(parsed.contains("1.46"), "was {parsed}");
+        assert!(
+            parsed.contains("| G1 Evacuation Pause - (young)                       |   Eden    |   1   | 384.00 mb  |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("| Full GCs |   1   | 200.00 mb |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("|  20.75 mb   |  23.44 mb  | +2.69 mb |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("| G1 Evacuation Pause - (young) |   1   | 0.48 | 0.01 | 0.12 |    4.00     |     8      |"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_zgc_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "[0.010s][info][gc,init] Initializing The Z Garbage Collector
+[0.010s][info][gc,init] Version: 17.0.2+8 (release)
+[0.011s][info][gc     ] Using The Z Garbage Collector
+[2.100s][info][gc          ] Allocation Stall (main) 12.345ms
+[2.345s][info][gc,start    ] GC(3) Garbage Collection (Allocation Rate)
+[2.346s][info][gc,phases   ] GC(3) Pause Mark Start 0.012ms
+[2.400s][info][gc,phases   ] GC(3) Concurrent Mark 53.839ms
+[2.401s][info][gc,phases   ] GC(3) Pause Mark End 0.021ms
+[2.410s][info][gc,phases   ] GC(3) Concurrent Select Relocation Set 4.456ms
+[2.411s][info][gc,phases   ] GC(3) Pause Relocate Start 0.015ms
+[2.420s][info][gc,phases   ] GC(3) Concurrent Relocate 9.345ms
+[2.445s][info][gc          ] GC(3) Garbage Collection (Allocation Rate) 1234M(60%)->456M(22%)
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(parsed.contains("collector:           ZGC"), "was {parsed}");
+        assert!(parsed.contains("Mark Start"), "was {parsed}");
+        assert!(parsed.contains("Relocate Start"), "was {parsed}");
+        assert!(parsed.contains("Concurrent Relocate"), "was {parsed}");
+        assert!(
+            parsed.contains("| Garbage Collection (Allocation Rate) |   1   |    0.10    |"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("allocation stalls:   1"), "was {parsed}");
+        assert!(!parsed.contains("| Allocation Stall"), "was {parsed}");
+        assert!(
+            parsed.contains("1 allocation stalls blocked application threads"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_zgc_cycle_without_start() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        //-Xlog:gc leaves out the gc,start line, the end line still has the duration
+        let lines = "[0.011s][info][gc] Using The Z Garbage Collector
+[2.445s][info][gc] GC(3) Major Collection (Allocation Rate) 1234M(60%)->456M(22%) 0.250s
+[3.445s][info][gc] GC(4) Garbage Collection (Warmup) 1234M(60%)->456M(22%)
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(
+            parsed.contains("| Major Collection (Allocation Rate) |   1   |    0.25    |"),
+            "was {parsed}"
+        );
+        assert!(
+            !parsed.contains("Garbage Collection (Warmup)"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_shenandoah_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "[0.010s][info][gc] Using Shenandoah
+[10.124s][info][gc] GC(5) Pause Init Mark (unload classes) 0.345ms
+[10.200s][info][gc] GC(5) Concurrent marking (unload classes) 600M->610M(1024M) 75.905ms
+[10.201s][info][gc] GC(5) Pause Final Mark (unload classes) 0.750ms
+[10.230s][info][gc] GC(5) Concurrent evacuation 500M->520M(1024M) 20.1ms
+[11.000s][info][gc] GC(6) Pause Degenerated GC (Mark) 900M->600M(1024M) 150.123ms
+[12.000s][info][gc] GC(7) Pause Full 1000M->400M(1024M) 1234.5ms
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        assert!(
+            parsed.contains("collector:           Shenandoah"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("Init Mark - (unload classes)"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("Concurrent marking"), "was {parsed}");
+        assert!(
+            parsed.contains("1 degenerated GCs adding 0.15 total seconds pause time"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("1 Full GCs after Shenandoah gave up on a degenerated GC"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_uptime_only_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log
+CommandLine flags: -XX:MaxHeapSize=17179869184 -XX:+PrintGC -XX:+PrintGCTimeStamps -XX:+UseParallelGC
+12.000: [GC (Allocation Failure)  880219K->614437K(2707200K), 0.0111111 secs]
+19999.636: [Full GC (Ergonomics)  880219K->614437K(2707200K), 1.5111111 secs]
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let new_file_str = new_file.to_str().unwrap().to_string();
+        let parsed = exec(new_file_str.to_string()).expect("failed to parse");
+        //the only file is created when the jvm starts
+        assert!(
+            parsed.contains("Timestamp: 2022-07-23T00:14:25.636Z"),
+            "was {parsed}"
+        );
+        let options = ExecOptions {
+            jvm_start_epoch_micros: Some(1658500000000000),
+            tz: Some(UtcOffset::from_hms(5, 30, 0).unwrap()),
+        };
+        let parsed = exec_files(&[new_file_str], &options).expect("failed to parse");
+        assert!(
+            parsed.contains("Timestamp: 2022-07-23T01:29:59.636+05:30"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_rotated_uptime_only_logs() {
+        let dir = tempfile::tempdir().expect("unable to make tmp dir");
+        let flags = "CommandLine flags: -XX:MaxHeapSize=17179869184 -XX:+PrintGC -XX:+PrintGCTimeStamps -XX:+UseParallelGC -XX:+UseGCLogFileRotation";
+        let files = [
+            (
+                "gc.log.1",
+                "2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log.1",
+                "100.000: [GC (Allocation Failure)  880219K->614437K(2707200K), 0.0111111 secs]",
+            ),
+            (
+                "gc.log.2",
+                "2022-07-22 18:50:00 GC log file created /opt/dremio/data/gc.log.2",
+                "640.000: [Full GC (Ergonomics)  880219K->614437K(2707200K), 1.5111111 secs]",
+            ),
+        ];
+        for (name, created, pause) in files {
+            std::fs::write(
+                dir.path().join(name),
+                format!("{created}\n{flags}\n{pause}\n"),
+            )
+            .unwrap();
+        }
+        let parsed = exec(dir.path().display().to_string()).expect("failed to parse");
+        //gc.log.1 was rotated to right before the pause at 100 seconds, so the jvm started at
+        //18:39:26 and the pause in gc.log.2 is placed from that and not from its own header
+        assert!(
+            parsed.contains("Timestamp: 2022-07-22T18:50:06.000Z"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_safepoint_log() {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        let lines = "2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log
+CommandLine flags: -XX:MaxHeapSize=17179869184 -XX:+PrintGC -XX:+PrintGCApplicationConcurrentTime -XX:+PrintGCApplicationStoppedTime -XX:+PrintGCTimeStamps -XX:+UseParallelGC
+11.500: Application time: 11.4000000 seconds
+12.000: [GC (Allocation Failure)  880219K->614437K(2707200K), 0.0111111 secs]
+12.011: Total time for which application threads were stopped: 0.0120000 seconds, Stopping threads took: 0.0008000 seconds
+20.000: Application time: 7.9000000 seconds
+20.100: Total time for which application threads were stopped: 1.8000000 seconds, Stopping threads took: 1.7500000 seconds
+";
+        write!(file, "{lines}").unwrap();
+        let new_file = file.into_temp_path();
+        let parsed = exec(new_file.to_str().unwrap().to_string()).expect("failed to parse");
+        assert!(parsed.contains("Safepoints:"), "was {parsed}");
+        assert!(
+            parsed.contains("stopped outside gc pauses: 1.80 seconds"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed
+                .contains("| 2022-07-22T18:41:26.100Z |    n/a    |      1.7500       | 1.8000  |"),
+            "was {parsed}"
+        );
+        assert!(
+            parsed.contains("* 1 safepoints took more than 50 ms to reach"),
+            "was {parsed}"
+        );
+    }
+
+    #[test]
+    fn test_gzip_log() {
+        let file = NamedTempFile::new().expect("unable to make tmp file");
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        let lines = "CommandLine flags: -XX:MaxHeapSize=17179869184 -XX:+UseG1GC
+2021-02-22T01:01:02.120+0000: 22000.498: [GC pause (G1 Humongous Allocation) (young) (initial-mark), 0.0911111 secs]
+";
+        write!(encoder, "{lines}").unwrap();
+        let new_file = encoder.finish().unwrap().into_temp_path();
+        let parsed = exec(new_file.to_str().unwrap().to_string()).expect("failed to parse");
+        assert!(parsed.contains("G1 Humongous Allocation"), "was {parsed}");
+        assert!(parsed.contains("0.09"), "was {parsed}");
+    }
+
+    #[test]
+    fn test_stdin_log() {
+        let lines = "CommandLine flags: -XX:MaxHeapSize=17179869184 -XX:+UseG1GC
+2021-02-22T01:01:02.120+0000: 22000.498: [GC pause (G1 Evacuation Pause) (young), 0.0110001 secs]
+";
+        let parsed = exec_files_with_stdin(
+            &["-".to_string()],
+            &ExecOptions::default(),
+            Cursor::new(lines),
+        )
+        .expect("failed to parse");
+        assert!(parsed.contains("G1 Evacuation Pause"), "was {parsed}");
+        assert!(parsed.contains("0.01"), "was {parsed}");
+    }
+
+    #[test]
+    fn test_rotated_log_directory() {
+        let dir = tempfile::tempdir().expect("unable to make tmp dir");
+        let header = "OpenJDK 64-Bit Server VM (25.332-b09) for linux-amd64 JRE (1.8.0_332-b09), built on Apr 20 2022 08:18:57 by \"openjdk\" with gcc 4.4.7 20120313 (Red Hat 4.4.7-23)
+Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)
+CommandLine flags: -XX:MaxHeapSize=17179869184 -XX:NumberOfGCLogFiles=3 -XX:+UseG1GC -XX:+UseGCLogFileRotation";
+        let files = [
+            (
+                "gc.log.1",
+                "2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log.1",
+                "2022-07-22T18:41:07.000+0000: 1.000: [GC pause (G1 Evacuation Pause) (young), 0.0100000 secs]",
+            ),
+            (
+                "gc.log.2",
+                "2022-07-22 18:41:10 GC log file created /opt/dremio/data/gc.log.2",
+                "2022-07-22T18:41:11.000+0000: 5.000: [GC pause (G1 Evacuation Pause) (young), 0.0200000 secs]",
+            ),
+            (
+                "gc.log.0.current",
+                "2022-07-22 19:00:00 GC log file created /opt/dremio/data/gc.log.0.current",
+                "2022-07-22T19:00:01.000+0000: 1135.000: [GC pause (G1 Humongous Allocation) (young), 3.0000000 secs]",
+            ),
+        ];
+        for (name, created, pause) in files {
+            std::fs::write(
+                dir.path().join(name),
+                format!("{created}\n{header}\n{pause}\n"),
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.path().join("server.out"), "not a gc log").unwrap();
+        let parsed = exec(dir.path().display().to_string()).expect("failed to parse");
+        assert!(parsed.contains("Log Files:"), "was {parsed}");
+        assert_eq!(parsed.matches("CommandLine").count(), 0, "was {parsed}");
+        assert_eq!(parsed.matches("-XX:+UseG1GC").count(), 1, "was {parsed}");
+        assert!(!parsed.contains("server.out"), "was {parsed}");
+        let first = parsed.find("gc.log.1 ").expect("gc.log.1 missing");
+        let last = parsed
+            .find("gc.log.0.current ")
+            .expect("gc.log.0.current missing");
+        assert!(first < last, "was {parsed}");
+        assert!(
+            parsed.contains("* no gc events for 18.82 minutes between 2022-07-22T18:41:11.000Z"),
+            "was {parsed}"
+        );
+        assert!(parsed.contains("G1 Humongous Allocation"), "was {parsed}");
     }
 
     #[test]
@@ -17,6 +17,7 @@ use std::fmt;
 use super::{
     convert::convert_bytes_to_gb,
     g1gc::{get_g1_gc_region_size_mb, get_g1_target_millis},
+    jvmflags::{parse_jvm_flags, JvmFlags},
 };
 
 pub struct GCFlags {
@@ -71,7 +72,7 @@ flags:\n{}",
 pub enum Collector {
     SerialGC,   //-XX:+UseSerialGC
     G1GC,       //-XX:+UseG1GC
-    CMS,        //-XX:+UseParNewGC
+    CMS,        //-XX:+UseConcMarkSweepGC or -XX:+UseParNewGC
     Parallel,   //-XX:+UseParallelGC
     ZGC,        //-XX:+UseZGC
     Shenandoah, //-XX:+UseShenandoahGC
@@ -88,7 +89,7 @@ fn get_collector(gc_flags: &Vec<String>) -> Collector {
             return Collector::Shenandoah;
         } else if flag == "-XX:+UseParallelGC" {
             return Collector::Parallel;
-        } else if flag == "-XX:+UseParNewGC" {
+        } else if flag == "-XX:+UseConcMarkSweepGC" || flag == "-XX:+UseParNewGC" {
             return Collector::CMS;
         } else if flag == "-XX:+UseSerialGC" {
             return Collector::SerialGC;
@@ -97,15 +98,9 @@ fn get_collector(gc_flags: &Vec<String>) -> Collector {
     Collector::Unknown
 }
 
-fn get_min_heap_size_gb(gc_flags: &Vec<String>, physical_memory_bytes: i64) -> f32 {
-    for flag in gc_flags {
-        if flag.starts_with("-XX:InitialHeapSize=") {
-            let o = flag
-                .strip_prefix("-XX:InitialHeapSize=")
-                .expect("unable to remove -XX:InitialHeapSize= prefix");
-            let min_heap_bytes = o.parse().expect("unable to parse string");
-            return convert_bytes_to_gb(min_heap_bytes);
-        }
+fn get_min_heap_size_gb(jvm_flags: &JvmFlags, physical_memory_bytes: i64) -> f32 {
+    if let Some(min_heap_bytes) = jvm_flags.get_size("InitialHeapSize") {
+        return convert_bytes_to_gb(min_heap_bytes as usize);
     }
     // source https://www.oracle.com/java/technologies/javase/6u18.html
     // * The maximum heap size is not actually used by the JVM unless your program
@@ -121,15 +116,9 @@ fn get_min_heap_size_gb(gc_flags: &Vec<String>, physical_memory_bytes: i64) -> f
     (expected_min_heap_bytes / 1024.0 / 1024.0 / 1024.0) as f32
 }
 
-fn get_max_heap_size_gb(gc_flags: &Vec<String>, physical_memory_bytes: i64) -> f32 {
-    for flag in gc_flags {
-        if flag.starts_with("-XX:MaxHeapSize=") {
-            let o = flag
-                .strip_prefix("-XX:MaxHeapSize=")
-                .expect("unable to remove -XX:MaxHeapSize= prefix");
-            let max_heap_bytes = o.parse().expect("unable to parse string");
-            return convert_bytes_to_gb(max_heap_bytes);
-        }
+fn get_max_heap_size_gb(jvm_flags: &JvmFlags, physical_memory_bytes: i64) -> f32 {
+    if let Some(max_heap_bytes) = jvm_flags.get_size("MaxHeapSize") {
+        return convert_bytes_to_gb(max_heap_bytes as usize);
     }
     let expected_max_heap = physical_memory_bytes as f64 * 0.25;
     //if less than 1gb return that https://www.oracle.com/java/technologies/javase/6u18.html
@@ -158,14 +147,15 @@ pub fn parse_gc_flags(line: String, physical_memory_bytes: i64) -> GCFlags {
         counter += 1
     }
     let collector = get_collector(&all_flags);
-    let max_heap_size_gb = get_max_heap_size_gb(&all_flags, physical_memory_bytes);
-    let min_heap_size_gb = get_min_heap_size_gb(&all_flags, physical_memory_bytes);
-    let max_direct_memory_gb = get_max_direct_memory_gb(&all_flags, max_heap_size_gb);
+    let jvm_flags = parse_jvm_flags(&all_flags, &collector);
+    let max_heap_size_gb = get_max_heap_size_gb(&jvm_flags, physical_memory_bytes);
+    let min_heap_size_gb = get_min_heap_size_gb(&jvm_flags, physical_memory_bytes);
+    let max_direct_memory_gb = get_max_direct_memory_gb(&jvm_flags, max_heap_size_gb);
     let mut target_pause_millis = 0;
     let mut region_size_mb = 0.0;
     if collector == Collector::G1GC {
-        region_size_mb = get_g1_gc_region_size_mb(max_heap_size_gb, &all_flags);
-        target_pause_millis = get_g1_target_millis(&all_flags);
+        region_size_mb = get_g1_gc_region_size_mb(max_heap_size_gb, &jvm_flags);
+        target_pause_millis = get_g1_target_millis(&jvm_flags);
     }
 
     GCFlags {
@@ -179,17 +169,9 @@ pub fn parse_gc_flags(line: String, physical_memory_bytes: i64) -> GCFlags {
     }
 }
 
-fn get_max_direct_memory_gb(gc_flags: &Vec<String>, max_heap_gb: f32) -> f32 {
-    let expected_flag_name = "-XX:MaxDirectMemorySize=";
-    for flag in gc_flags {
-        if flag.starts_with(expected_flag_name) {
-            let err_msg = format!("unable to remove {expected_flag_name} prefix");
-            let o = flag
-                .strip_prefix(expected_flag_name)
-                .unwrap_or(err_msg.as_str());
-            let raw_bytes: i64 = o.parse().expect("unable to parse string");
-            return (raw_bytes as f64 / 1024.0 / 1024.0 / 1024.0) as f32;
-        }
+fn get_max_direct_memory_gb(jvm_flags: &JvmFlags, max_heap_gb: f32) -> f32 {
+    if let Some(raw_bytes) = jvm_flags.get_size("MaxDirectMemorySize") {
+        return (raw_bytes as f64 / 1024.0 / 1024.0 / 1024.0) as f32;
     }
     //default sourced from https://stackoverflow.com/questions/3773775/default-for-xxmaxdirectmemorysize
     //under jdk8
@@ -219,64 +201,88 @@ fn get_max_direct_memory_gb(gc_flags: &Vec<String>, max_heap_gb: f32) -> f32 {
 #[cfg(test)]
 mod tests {
     use crate::{
-        glog::flags::{get_max_heap_size_gb, get_min_heap_size_gb, parse_gc_flags, Collector},
+        glog::{
+            flags::{get_max_heap_size_gb, get_min_heap_size_gb, parse_gc_flags, Collector},
+            jvmflags::{parse_jvm_flags, JvmFlags},
+        },
         tests::{approx_equal, assert_approx_equal},
     };
 
     use super::{get_collector, get_max_direct_memory_gb, GCFlags};
 
+    fn to_jvm_flags(gc_flags: &[String]) -> JvmFlags {
+        parse_jvm_flags(gc_flags, &Collector::G1GC)
+    }
+
     #[test]
     fn test_get_min_heap() {
         let gc_flags = vec!["-XX:InitialHeapSize=64424509440".to_string()];
-        let min_heap_size_gb = get_min_heap_size_gb(&gc_flags, 0);
+        let min_heap_size_gb = get_min_heap_size_gb(&to_jvm_flags(&gc_flags), 0);
         assert_approx_equal(min_heap_size_gb, 60.0, 0.01);
     }
 
     #[test]
     fn test_default_get_min_heap() {
         let gc_flags = vec![];
-        let min_heap_size_gb = get_min_heap_size_gb(&gc_flags, 64 * 1024 * 1024 * 1024);
+        let min_heap_size_gb =
+            get_min_heap_size_gb(&to_jvm_flags(&gc_flags), 64 * 1024 * 1024 * 1024);
         assert_approx_equal(min_heap_size_gb, 1.0, 0.01);
     }
 
     #[test]
     fn test_default_get_min_heap_when_physical_memory_is_below_512mb() {
         let gc_flags = vec![];
-        let min_heap_size_gb = get_min_heap_size_gb(&gc_flags, 256 * 1024 * 1024);
+        let min_heap_size_gb = get_min_heap_size_gb(&to_jvm_flags(&gc_flags), 256 * 1024 * 1024);
         assert_approx_equal(min_heap_size_gb, 8.0 / 1024.0, 0.01);
     }
 
     #[test]
     fn test_get_max_heap() {
         let gc_flags = vec!["-XX:MaxHeapSize=64424509440".to_string()];
-        let max_heap_size_gb = get_max_heap_size_gb(&gc_flags, 0);
+        let max_heap_size_gb = get_max_heap_size_gb(&to_jvm_flags(&gc_flags), 0);
         assert_approx_equal(max_heap_size_gb, 60.0, 0.01);
     }
 
     #[test]
     fn test_default_get_max_heap() {
         let gc_flags = vec![];
-        let max_heap_size_gb = get_max_heap_size_gb(&gc_flags, 240 * 1024 * 1024 * 1024);
+        let max_heap_size_gb =
+            get_max_heap_size_gb(&to_jvm_flags(&gc_flags), 240 * 1024 * 1024 * 1024);
         assert_approx_equal(max_heap_size_gb, 60.0, 0.01);
     }
     #[test]
     fn test_default_get_max_heap_when_physical_memory_is_below_4gb() {
         let gc_flags = vec![];
-        let max_heap_size_gb = get_max_heap_size_gb(&gc_flags, 2 * 1024 * 1024 * 1024);
+        let max_heap_size_gb =
+            get_max_heap_size_gb(&to_jvm_flags(&gc_flags), 2 * 1024 * 1024 * 1024);
         assert_approx_equal(max_heap_size_gb, 1.0, 0.01);
     }
 
+    #[test]
+    fn test_get_heap_with_bad_values_uses_defaults() {
+        let gc_flags = vec![
+            "-XX:MaxHeapSize=lots".to_string(),
+            "-XX:InitialHeapSize=".to_string(),
+        ];
+        let max_heap_size_gb =
+            get_max_heap_size_gb(&to_jvm_flags(&gc_flags), 240 * 1024 * 1024 * 1024);
+        assert_approx_equal(max_heap_size_gb, 60.0, 0.01);
+        let min_heap_size_gb =
+            get_min_heap_size_gb(&to_jvm_flags(&gc_flags), 64 * 1024 * 1024 * 1024);
+        assert_approx_equal(min_heap_size_gb, 1.0, 0.01);
+    }
+
     #[test]
     fn test_get_max_direct_memory() {
         let gc_flags = vec!["-XX:MaxDirectMemorySize=64424509440".to_string()];
-        let max_direct_memory = get_max_direct_memory_gb(&gc_flags, 32.0);
+        let max_direct_memory = get_max_direct_memory_gb(&to_jvm_flags(&gc_flags), 32.0);
         assert_approx_equal(max_direct_memory, 60.0, 0.01);
     }
 
     #[test]
     fn test_get_default_max_direct_memory() {
         let gc_flags = vec![];
-        let max_direct_memory = get_max_direct_memory_gb(&gc_flags, 32.0);
+        let max_direct_memory = get_max_direct_memory_gb(&to_jvm_flags(&gc_flags), 32.0);
         assert!(approx_equal(max_direct_memory, 32.0, 0.01));
     }
 
@@ -408,6 +414,8 @@ flags:
         assert_eq!(parallel_gc, Collector::Parallel);
         let cms = get_collector(&vec!["-XX:+UseParNewGC".to_string(), "".to_string()]);
         assert_eq!(cms, Collector::CMS);
+        let cms = get_collector(&vec!["-XX:+UseConcMarkSweepGC".to_string(), "".to_string()]);
+        assert_eq!(cms, Collector::CMS);
         let serial = get_collector(&vec!["-XX:+UseSerialGC".to_string(), "".to_string()]);
         assert_eq!(serial, Collector::SerialGC);
         let zgc = get_collector(&vec!["-XX:+UseZGC".to_string(), "".to_string()]);
@@ -0,0 +1,237 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// groups the full gcs by the cause the jvm logged for them
+//
+// 2022-08-24T01:54:38.603+0000: 190268.356: [Full GC (System.gc())  880219K->614437K(2707200K), 1.5111111 secs]
+// 2022-08-24T01:54:38.603+0000: 190268.356: [Full GC (Metadata GC Threshold)  880219K->614437K(2707200K), 1.5111111 secs]
+// [12.345s][info][gc] GC(13) Pause Full (Heap Dump Initiated GC) 600M->200M(2048M) 123.456ms
+
+use std::fmt;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::format_float;
+
+use super::pauses::GCPause;
+
+#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
+pub enum FullGCCause {
+    SystemGC,            //System.gc() or Runtime.gc()
+    MetadataGCThreshold, //metaspace reached -XX:MetaspaceSize
+    AllocationFailure,   //the heap was full
+    Ergonomics,          //the parallel collector predicted the next promotion would not fit
+    HeapInspection,      //jmap -histo or GC.class_histogram
+    HeapDump,            //jmap -dump, GC.heap_dump or -XX:+HeapDumpOnOutOfMemoryError
+    GCLocker,            //delayed by threads in jni critical sections
+    Other(String),
+}
+
+impl fmt::Display for FullGCCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FullGCCause::SystemGC => "System.gc()",
+            FullGCCause::MetadataGCThreshold => "Metadata GC Threshold",
+            FullGCCause::AllocationFailure => "Allocation Failure",
+            FullGCCause::Ergonomics => "Ergonomics",
+            FullGCCause::HeapInspection => "Heap Inspection Initiated GC",
+            FullGCCause::HeapDump => "Heap Dump Initiated GC",
+            FullGCCause::GCLocker => "GCLocker Initiated GC",
+            FullGCCause::Other(cause) => cause,
+        };
+        write!(f, "{name}")
+    }
+}
+
+// None for pauses that are not full gcs
+pub fn get_full_gc_cause(pause: &GCPause) -> Option<FullGCCause> {
+    if !pause.is_full_gc {
+        return None;
+    }
+    //a cms or serial young gc that fell back to collecting the old gen keeps the cause as its
+    //type, a real full gc has it as the first group which jdk 8 logs can leave out
+    let cause = if pause.gc_type != "Full GC" {
+        pause.gc_type.as_str()
+    } else {
+        match pause.attributes.first() {
+            Some(cause) => cause.as_str(),
+            None => return Some(FullGCCause::Other("Unknown".to_string())),
+        }
+    };
+    Some(match cause {
+        "System.gc()" => FullGCCause::SystemGC,
+        "Metadata GC Threshold" | "Last ditch collection" => FullGCCause::MetadataGCThreshold,
+        //jdk 10+ G1 names the full gc after the young pause that could not finish
+        "Allocation Failure"
+        | "G1 Evacuation Pause"
+        | "G1 Humongous Allocation"
+        | "G1 Compaction Pause" => FullGCCause::AllocationFailure,
+        "Ergonomics" => FullGCCause::Ergonomics,
+        "Heap Inspection Initiated GC" => FullGCCause::HeapInspection,
+        "Heap Dump Initiated GC" => FullGCCause::HeapDump,
+        "GCLocker Initiated GC" => FullGCCause::GCLocker,
+        other => FullGCCause::Other(other.to_string()),
+    })
+}
+
+pub struct FullGCCauseStats {
+    pub cause: FullGCCause,
+    pub count: i64,
+    pub total_seconds: f64,
+    pub max_seconds: f64,
+}
+
+// the full gcs of each cause in the order of the causes
+pub fn count_full_gc_causes(pauses: &[GCPause]) -> Vec<FullGCCauseStats> {
+    let mut stats: Vec<FullGCCauseStats> = vec![];
+    for pause in pauses {
+        let cause = match get_full_gc_cause(pause) {
+            Some(cause) => cause,
+            None => continue,
+        };
+        match stats.iter_mut().find(|s| s.cause == cause) {
+            Some(s) => {
+                s.count += 1;
+                s.total_seconds += pause.pause_time_seconds;
+                s.max_seconds = s.max_seconds.max(pause.pause_time_seconds);
+            }
+            None => stats.push(FullGCCauseStats {
+                cause,
+                count: 1,
+                total_seconds: pause.pause_time_seconds,
+                max_seconds: pause.pause_time_seconds,
+            }),
+        }
+    }
+    stats.sort_by(|a, b| a.cause.cmp(&b.cause));
+    stats
+}
+
+#[derive(Tabled)]
+struct FullGCCauseRow {
+    #[tabled(rename = "Cause")]
+    cause: String,
+    #[tabled(rename = "Count")]
+    count: i64,
+    #[tabled(display_with = "format_float", rename = "Total Time")]
+    total_seconds: f64,
+    #[tabled(display_with = "format_float", rename = "Max Time")]
+    max_seconds: f64,
+}
+
+pub fn generate_full_gc_cause_table(pauses: &[GCPause]) -> String {
+    let rows: Vec<FullGCCauseRow> = count_full_gc_causes(pauses)
+        .into_iter()
+        .map(|s| FullGCCauseRow {
+            cause: s.cause.to_string(),
+            count: s.count,
+            total_seconds: s.total_seconds,
+            max_seconds: s.max_seconds,
+        })
+        .collect();
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::pauses::GCPause;
+
+    use super::{generate_full_gc_cause_table, get_full_gc_cause, FullGCCause};
+
+    fn full_gc(cause: &str, pause_time_seconds: f64) -> GCPause {
+        GCPause {
+            gc_type: "Full GC".to_string(),
+            is_full_gc: true,
+            attributes: vec![cause.to_string()],
+            pause_time_seconds,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_get_full_gc_cause() {
+        assert_eq!(
+            get_full_gc_cause(&full_gc("System.gc()", 1.0)),
+            Some(FullGCCause::SystemGC)
+        );
+        assert_eq!(
+            get_full_gc_cause(&full_gc("G1 Evacuation Pause", 1.0)),
+            Some(FullGCCause::AllocationFailure)
+        );
+        assert_eq!(
+            get_full_gc_cause(&full_gc("Diagnostic Command", 1.0)),
+            Some(FullGCCause::Other("Diagnostic Command".to_string()))
+        );
+        assert_eq!(
+            get_full_gc_cause(&GCPause {
+                gc_type: "Full GC".to_string(),
+                is_full_gc: true,
+                ..Default::default()
+            }),
+            Some(FullGCCause::Other("Unknown".to_string()))
+        );
+        assert_eq!(
+            get_full_gc_cause(&GCPause {
+                gc_type: "Allocation Failure".to_string(),
+                is_full_gc: true,
+                attributes: vec!["promotion failed".to_string()],
+                ..Default::default()
+            }),
+            Some(FullGCCause::AllocationFailure)
+        );
+        assert_eq!(
+            get_full_gc_cause(&GCPause {
+                gc_type: "G1 Evacuation Pause".to_string(),
+                attributes: vec!["young".to_string()],
+                ..Default::default()
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_generate_full_gc_cause_table() {
+        let pauses = vec![
+            full_gc("Heap Dump Initiated GC", 4.0),
+            full_gc("System.gc()", 1.0),
+            full_gc("Allocation Failure", 3.5),
+            full_gc("System.gc()", 2.0),
+            GCPause {
+                gc_type: "Allocation Failure".to_string(),
+                pause_time_seconds: 0.1,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            generate_full_gc_cause_table(&pauses),
+            "+------------------------+-------+------------+----------+
+| Cause                  | Count | Total Time | Max Time |
++------------------------+-------+------------+----------+
+| System.gc()            |   2   |    3.00    |   2.00   |
++------------------------+-------+------------+----------+
+| Allocation Failure     |   1   |    3.50    |   3.50   |
++------------------------+-------+------------+----------+
+| Heap Dump Initiated GC |   1   |    4.00    |   4.00   |
++------------------------+-------+------------+----------+
+"
+        );
+        assert_eq!(generate_full_gc_cause_table(&pauses[4..]), "");
+    }
+}
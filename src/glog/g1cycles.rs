@@ -0,0 +1,444 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// parsing for the G1 concurrent marking cycle of jdk 8 logs, a cycle starts with an
+// initial-mark young pause and runs alongside the application until the cleanup pause
+//
+// 2022-07-22T18:41:06.551+0000: 54055.990: [GC concurrent-root-region-scan-start]
+// 2022-07-22T18:41:06.600+0000: 54056.039: [GC concurrent-root-region-scan-end, 0.0490000 secs]
+// 2022-07-22T18:41:06.600+0000: 54056.039: [GC concurrent-mark-start]
+// 2022-07-22T18:41:08.000+0000: 54057.439: [GC concurrent-mark-end, 1.4000000 secs]
+// 2022-07-22T18:41:08.001+0000: 54057.440: [GC remark 54057.440: [Finalize Marking, 0.0012 secs] 54057.441: [GC ref-proc, 0.0100 secs] 54057.451: [Unloading, 0.0200 secs], 0.0450000 secs]
+// 2022-07-22T18:41:08.050+0000: 54057.489: [GC cleanup 4500M->4200M(16G), 0.0100000 secs]
+// 2022-07-22T18:41:08.060+0000: 54057.499: [GC concurrent-cleanup-start]
+// 2022-07-22T18:41:08.061+0000: 54057.500: [GC concurrent-cleanup-end, 0.0010000 secs]
+// 2022-07-22T18:41:09.000+0000: 54058.439: [GC concurrent-mark-abort]
+
+use std::error::Error;
+use std::str::FromStr;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use super::{
+    concurrent::ConcurrentPhase,
+    occupancy::{get_occupancy, parse_transition, Space},
+    pauses::{
+        get_epoch_micros, get_outer_pause_seconds, get_uptime_seconds, GCPause, SecondsParseError,
+    },
+};
+
+pub const MARK_ABORT: &str = "G1-concurrent-mark-abort";
+
+pub fn is_g1_remark_or_cleanup(text: &str) -> bool {
+    text.contains("[GC remark") || text.contains("[GC cleanup")
+}
+
+// the remark and cleanup pauses get the same names the unified logging parser gives them
+pub fn parse_g1_remark_or_cleanup(text: String) -> Result<GCPause, Box<dyn Error>> {
+    let time_epoch_micros = text
+        .split(": ")
+        .next()
+        .and_then(|datetime| get_epoch_micros(datetime.to_string()).ok())
+        .unwrap_or_default();
+    let pause_time_seconds = match get_outer_pause_seconds(&text) {
+        Some(seconds) => seconds,
+        None => {
+            return Err(Box::new(SecondsParseError {
+                seconds: "".to_string(),
+                line: text,
+            }))
+        }
+    };
+    let gc_type = if text.contains("[GC remark") {
+        "Remark"
+    } else {
+        "Cleanup"
+    };
+    //[GC cleanup 4500M->4200M(16G), 0.0100000 secs]
+    let heap = text
+        .split(['[', ' ', ','])
+        .filter(|token| token.contains("->"))
+        .find_map(|token| parse_transition(Space::Heap, token));
+    Ok(GCPause {
+        gc_type: gc_type.to_string(),
+        pause_time_seconds,
+        time_epoch_micros,
+        uptime_seconds: get_uptime_seconds(&text),
+        occupancy: heap.into_iter().collect(),
+        ..Default::default()
+    })
+}
+
+// the end of each concurrent phase is logged with how long it took, the -start lines have no
+// timings. An abort is kept as a phase without a duration so it has a time in the cycle
+pub fn parse_g1_concurrent(line: &str) -> Vec<ConcurrentPhase> {
+    let time_epoch_micros = line
+        .split(": ")
+        .next()
+        .and_then(|datetime| get_epoch_micros(datetime.to_string()).ok())
+        .unwrap_or_default();
+    let mut phases = vec![];
+    for (index, _) in line.match_indices("[GC concurrent-") {
+        let rest = &line[index + "[GC ".len()..];
+        let body = match rest.find(']') {
+            Some(end) => &rest[..end],
+            None => continue,
+        };
+        if body == "concurrent-mark-abort" {
+            phases.push(ConcurrentPhase {
+                name: MARK_ABORT.to_string(),
+                time_epoch_micros,
+                duration_seconds: 0.0,
+            });
+            continue;
+        }
+        let (name, seconds) = match body.split_once(", ") {
+            Some(split) => split,
+            None => continue,
+        };
+        let name = match name.strip_suffix("-end") {
+            Some(name) => name,
+            None => continue,
+        };
+        if let Ok(duration_seconds) = f64::from_str(seconds.trim_end_matches(" secs")) {
+            phases.push(ConcurrentPhase {
+                name: format!("G1-{name}"),
+                time_epoch_micros,
+                duration_seconds,
+            });
+        }
+    }
+    phases
+}
+
+pub fn is_initial_mark(pause: &GCPause) -> bool {
+    pause.attributes.iter().any(|a| a == "initial-mark")
+}
+
+// a marking cycle from the initial-mark pause up to the next one
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MarkingCycle {
+    pub start_epoch_micros: i64,
+    pub end_epoch_micros: i64,
+    pub initial_mark_seconds: f64,
+    pub concurrent_seconds: f64,
+    pub remark_seconds: Option<f64>,
+    pub cleanup_seconds: Option<f64>,
+    pub aborted: bool,
+    pub full_gc_during_mark: bool,
+    //the heap and its capacity after the initial-mark pause and the heap before the Full GC
+    //that interrupted the cycle, the difference is how much was allocated while marking
+    pub heap_after_initial_mark: Option<(i64, i64)>,
+    pub heap_before_full_gc_bytes: Option<i64>,
+}
+
+enum MarkingEvent<'a> {
+    Pause(&'a GCPause),
+    Phase(&'a ConcurrentPhase),
+}
+
+// walks the pauses and G1 concurrent phases in time order, the phases are only needed for the
+// concurrent time and aborts, a Full GC during concurrent mark is found from the pauses alone
+pub fn build_marking_cycles(pauses: &[GCPause], phases: &[ConcurrentPhase]) -> Vec<MarkingCycle> {
+    let mut events: Vec<(i64, MarkingEvent)> = pauses
+        .iter()
+        .map(|p| (p.time_epoch_micros, MarkingEvent::Pause(p)))
+        .collect();
+    events.extend(
+        phases
+            .iter()
+            .filter(|p| p.name.starts_with("G1-concurrent-"))
+            .map(|p| (p.time_epoch_micros, MarkingEvent::Phase(p))),
+    );
+    events.sort_by_key(|(time, _)| *time);
+    let mut cycles = vec![];
+    let mut current: Option<MarkingCycle> = None;
+    for (time, event) in events {
+        match event {
+            MarkingEvent::Pause(pause) if is_initial_mark(pause) => {
+                cycles.extend(current.take());
+                current = Some(MarkingCycle {
+                    start_epoch_micros: time,
+                    end_epoch_micros: time,
+                    initial_mark_seconds: pause.pause_time_seconds,
+                    heap_after_initial_mark: get_occupancy(pause, Space::Heap)
+                        .map(|heap| (heap.after_bytes, heap.capacity_bytes)),
+                    ..Default::default()
+                });
+            }
+            MarkingEvent::Pause(pause) => {
+                let cycle = match current.as_mut() {
+                    Some(cycle) => cycle,
+                    None => continue,
+                };
+                if pause.gc_type == "Remark" {
+                    cycle.remark_seconds = Some(pause.pause_time_seconds);
+                    cycle.end_epoch_micros = time;
+                } else if pause.gc_type == "Cleanup" {
+                    cycle.cleanup_seconds = Some(pause.pause_time_seconds);
+                    cycle.end_epoch_micros = time;
+                } else if pause.is_full_gc && cycle.remark_seconds.is_none() {
+                    cycle.full_gc_during_mark = true;
+                    if let Some(heap) = get_occupancy(pause, Space::Heap) {
+                        cycle.heap_before_full_gc_bytes =
+                            cycle.heap_before_full_gc_bytes.max(Some(heap.before_bytes));
+                    }
+                }
+            }
+            MarkingEvent::Phase(phase) => {
+                let cycle = match current.as_mut() {
+                    Some(cycle) => cycle,
+                    None => continue,
+                };
+                cycle.end_epoch_micros = time;
+                if phase.name == MARK_ABORT {
+                    cycle.aborted = true;
+                    cycles.extend(current.take());
+                } else {
+                    cycle.concurrent_seconds += phase.duration_seconds;
+                }
+            }
+        }
+    }
+    cycles.extend(current);
+    cycles
+}
+
+#[derive(Tabled)]
+struct MarkingRow {
+    #[tabled(rename = "Step")]
+    name: String,
+    #[tabled(rename = "Count")]
+    count: i64,
+    #[tabled(display_with = "format_float", rename = "Avg Time")]
+    avg_seconds: f64,
+    #[tabled(display_with = "format_float", rename = "Max Time")]
+    max_seconds: f64,
+}
+
+fn format_float(float: &f64) -> String {
+    format!("{float:.3}")
+}
+
+fn marking_row(name: &str, seconds: Vec<f64>) -> Option<MarkingRow> {
+    if seconds.is_empty() {
+        return None;
+    }
+    Some(MarkingRow {
+        name: name.to_string(),
+        count: seconds.len() as i64,
+        avg_seconds: seconds.iter().sum::<f64>() / seconds.len() as f64,
+        max_seconds: seconds.iter().cloned().fold(0.0, f64::max),
+    })
+}
+
+// how the marking cycles ended followed by the time spent in each step of them
+pub fn generate_marking_cycle_table(cycles: &[MarkingCycle]) -> String {
+    if cycles.is_empty() {
+        return "".to_string();
+    }
+    let completed = cycles
+        .iter()
+        .filter(|c| c.cleanup_seconds.is_some())
+        .count();
+    let aborted = cycles.iter().filter(|c| c.aborted).count();
+    let full_gc = cycles.iter().filter(|c| c.full_gc_during_mark).count();
+    let rows: Vec<MarkingRow> = [
+        marking_row(
+            "Initial Mark",
+            cycles.iter().map(|c| c.initial_mark_seconds).collect(),
+        ),
+        marking_row(
+            "Concurrent",
+            cycles
+                .iter()
+                .filter(|c| c.concurrent_seconds > 0.0)
+                .map(|c| c.concurrent_seconds)
+                .collect(),
+        ),
+        marking_row(
+            "Remark",
+            cycles.iter().filter_map(|c| c.remark_seconds).collect(),
+        ),
+        marking_row(
+            "Cleanup",
+            cycles.iter().filter_map(|c| c.cleanup_seconds).collect(),
+        ),
+        marking_row(
+            "Whole Cycle",
+            cycles
+                .iter()
+                .filter(|c| c.end_epoch_micros > c.start_epoch_micros)
+                .map(|c| (c.end_epoch_micros - c.start_epoch_micros) as f64 / 1_000_000.0)
+                .collect(),
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    format!(
+        "cycles: {}, completed: {completed}, aborted: {aborted}, Full GC during concurrent mark: {full_gc}\n{}",
+        cycles.len(),
+        Table::new(rows)
+            .with(Modify::new(Columns::first()).with(Alignment::left()))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::{
+        concurrent::ConcurrentPhase,
+        occupancy::{Space, SpaceOccupancy},
+        pauses::GCPause,
+    };
+
+    use super::{
+        build_marking_cycles, generate_marking_cycle_table, is_g1_remark_or_cleanup,
+        parse_g1_concurrent, parse_g1_remark_or_cleanup, MARK_ABORT,
+    };
+
+    fn pause(gc_type: &str, time_epoch_micros: i64, pause_time_seconds: f64) -> GCPause {
+        GCPause {
+            gc_type: gc_type.to_string(),
+            time_epoch_micros,
+            pause_time_seconds,
+            ..Default::default()
+        }
+    }
+
+    fn initial_mark(time_epoch_micros: i64, heap_after_bytes: i64) -> GCPause {
+        GCPause {
+            attributes: vec!["young".to_string(), "initial-mark".to_string()],
+            occupancy: vec![SpaceOccupancy {
+                space: Space::Heap,
+                before_bytes: heap_after_bytes,
+                after_bytes: heap_after_bytes,
+                capacity_bytes: 1000,
+            }],
+            ..pause("G1 Humongous Allocation", time_epoch_micros, 0.02)
+        }
+    }
+
+    fn phase(name: &str, time_epoch_micros: i64, duration_seconds: f64) -> ConcurrentPhase {
+        ConcurrentPhase {
+            name: name.to_string(),
+            time_epoch_micros,
+            duration_seconds,
+        }
+    }
+
+    #[test]
+    fn test_parse_g1_remark_and_cleanup() {
+        let remark = "2022-07-22T18:41:08.001+0000: 54057.440: [GC remark 2022-07-22T18:41:08.001+0000: 54057.440: [Finalize Marking, 0.0012 secs] 2022-07-22T18:41:08.002+0000: 54057.441: [GC ref-proc, 0.0100 secs] 2022-07-22T18:41:08.012+0000: 54057.451: [Unloading, 0.0200 secs], 0.0450000 secs]";
+        assert!(is_g1_remark_or_cleanup(remark));
+        let pause = parse_g1_remark_or_cleanup(remark.to_string()).unwrap();
+        assert_eq!(pause.gc_type, "Remark");
+        assert_eq!(pause.pause_time_seconds, 0.045);
+        assert_eq!(pause.uptime_seconds, Some(54057.44));
+        assert_eq!(pause.time_epoch_micros, 1658515268001000);
+        let cleanup = parse_g1_remark_or_cleanup(
+            "54057.489: [GC cleanup 4500M->4200M(16G), 0.0100000 secs]".to_string(),
+        )
+        .unwrap();
+        assert_eq!(cleanup.gc_type, "Cleanup");
+        assert_eq!(cleanup.pause_time_seconds, 0.01);
+        assert_eq!(cleanup.occupancy[0].after_bytes, 4200 * 1024 * 1024);
+        assert!(!is_g1_remark_or_cleanup(
+            "54055.679: [GC pause (G1 Evacuation Pause) (young), 0.2753957 secs]"
+        ));
+    }
+
+    #[test]
+    fn test_parse_g1_concurrent() {
+        let phases = parse_g1_concurrent(
+            "2022-07-22T18:41:08.000+0000: 54057.439: [GC concurrent-mark-end, 1.4000000 secs]",
+        );
+        assert_eq!(phases.len(), 1);
+        assert_eq!(phases[0].name, "G1-concurrent-mark");
+        assert_eq!(phases[0].duration_seconds, 1.4);
+        assert_eq!(phases[0].time_epoch_micros, 1658515268000000);
+        assert!(parse_g1_concurrent("54056.039: [GC concurrent-mark-start]").is_empty());
+        let abort = parse_g1_concurrent("54058.439: [GC concurrent-mark-abort]");
+        assert_eq!(abort[0].name, MARK_ABORT);
+    }
+
+    #[test]
+    fn test_build_marking_cycles() {
+        let pauses = vec![
+            initial_mark(1_000_000, 500),
+            pause("Remark", 3_000_000, 0.05),
+            pause("Cleanup", 3_100_000, 0.01),
+            initial_mark(10_000_000, 600),
+            GCPause {
+                is_full_gc: true,
+                occupancy: vec![SpaceOccupancy {
+                    space: Space::Heap,
+                    before_bytes: 990,
+                    after_bytes: 400,
+                    capacity_bytes: 1000,
+                }],
+                ..pause("Full GC", 11_000_000, 3.0)
+            },
+        ];
+        let phases = vec![
+            phase("G1-concurrent-root-region-scan", 1_100_000, 0.1),
+            phase("G1-concurrent-mark", 2_900_000, 1.8),
+            phase("G1-concurrent-cleanup", 3_200_000, 0.001),
+            phase("CMS-concurrent-mark", 3_300_000, 5.0),
+            phase(MARK_ABORT, 11_100_000, 0.0),
+        ];
+        let cycles = build_marking_cycles(&pauses, &phases);
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0].remark_seconds, Some(0.05));
+        assert_eq!(cycles[0].cleanup_seconds, Some(0.01));
+        assert!((cycles[0].concurrent_seconds - 1.901).abs() < 1e-9);
+        assert_eq!(cycles[0].end_epoch_micros, 3_200_000);
+        assert!(!cycles[0].aborted);
+        assert!(cycles[1].aborted);
+        assert!(cycles[1].full_gc_during_mark);
+        assert_eq!(cycles[1].heap_after_initial_mark, Some((600, 1000)));
+        assert_eq!(cycles[1].heap_before_full_gc_bytes, Some(990));
+    }
+
+    #[test]
+    fn test_generate_marking_cycle_table() {
+        let pauses = vec![
+            initial_mark(1_000_000, 500),
+            pause("Remark", 3_000_000, 0.05),
+            pause("Cleanup", 3_100_000, 0.01),
+        ];
+        let phases = vec![phase("G1-concurrent-mark", 2_900_000, 1.8)];
+        let cycles = build_marking_cycles(&pauses, &phases);
+        assert_eq!(
+            generate_marking_cycle_table(&cycles),
+            "cycles: 1, completed: 1, aborted: 0, Full GC during concurrent mark: 0
++--------------+-------+----------+----------+
+| Step         | Count | Avg Time | Max Time |
++--------------+-------+----------+----------+
+| Initial Mark |   1   |  0.020   |  0.020   |
++--------------+-------+----------+----------+
+| Concurrent   |   1   |  1.800   |  1.800   |
++--------------+-------+----------+----------+
+| Remark       |   1   |  0.050   |  0.050   |
++--------------+-------+----------+----------+
+| Cleanup      |   1   |  0.010   |  0.010   |
++--------------+-------+----------+----------+
+| Whole Cycle  |   1   |  2.100   |  2.100   |
++--------------+-------+----------+----------+
+"
+        );
+        assert_eq!(generate_marking_cycle_table(&[]), "");
+    }
+}
@@ -12,33 +12,50 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::convert::convert_bytes_to_mb;
+use super::{
+    convert::convert_bytes_to_mb,
+    jvmflags::JvmFlags,
+    occupancy::{find_labeled_transition, find_space, Space, SpaceOccupancy},
+};
 
-pub fn get_g1_target_millis(gc_flags: &Vec<String>) -> i32 {
-    for flag in gc_flags {
-        if flag.starts_with("-XX:MaxGCPauseMillis=") {
-            let o = flag
-                .strip_prefix("-XX:MaxGCPauseMillis=")
-                .expect("unable to remove -XX:MaxGCPauseMillis= prefix");
-            return o.parse().expect("unable to parse string");
-        }
+pub fn get_g1_target_millis(jvm_flags: &JvmFlags) -> i32 {
+    if let Some(millis) = jvm_flags.get_number("MaxGCPauseMillis") {
+        return millis as i32;
     }
     //default sourced from https://www.oracle.com/technical-resources/articles/java/g1gc.html
     200
 }
-pub fn get_g1_gc_region_size_mb(min_heap_gb: f32, gc_flags: &Vec<String>) -> f32 {
-    for flag in gc_flags {
-        if flag.starts_with("-XX:G1HeapRegionSize=") {
-            let o = flag
-                .strip_prefix("-XX:G1HeapRegionSize=")
-                .expect("unable to remove -XX:G1HeapRegionSize= prefix");
-            let region_size = o.parse().expect("unable to parse string");
-            return convert_bytes_to_mb(region_size);
-        }
+pub fn get_g1_gc_region_size_mb(min_heap_gb: f32, jvm_flags: &JvmFlags) -> f32 {
+    if let Some(region_size) = jvm_flags.get_size("G1HeapRegionSize") {
+        return convert_bytes_to_mb(region_size as usize);
     }
     get_region_for_heap(min_heap_gb)
 }
 
+// reads the summary G1 writes at the end of every pause with -XX:+PrintGCDetails
+//    [Eden: 10.9G(10.9G)->0.0B(10.9G) Survivors: 192.0M->224.0M Heap: 14.5G(18.5G)->1830.3M(18.5G)]
+//    [Eden: 0.0B(724.0M)->0.0B(6114.0M) Survivors: 0.0B->0.0B Heap: 8185.4M(8192.0M)->1111.0M(5335.0M)], [Metaspace: 111001K->111110K(1111704K)]
+pub fn parse_g1_occupancy(text: &str) -> Vec<SpaceOccupancy> {
+    let mut occupancy = vec![];
+    let summary = match text.find("[Eden: ") {
+        Some(start) => &text[start..],
+        None => return occupancy,
+    };
+    for (label, space) in [
+        ("Eden: ", Space::Eden),
+        ("Survivors: ", Space::Survivors),
+        ("Heap: ", Space::Heap),
+    ] {
+        if let Some(space) = find_labeled_transition(summary, label, space) {
+            occupancy.push(space);
+        }
+    }
+    if let Some((metaspace, _)) = find_space(summary, "Metaspace", Space::Metaspace) {
+        occupancy.push(metaspace);
+    }
+    occupancy
+}
+
 // get_region_for_heap is sourced from the table located here https://stackoverflow.com/questions/46786601/how-to-know-region-size-used-of-g1-garbage-collector
 // G1 region-size in Java-8 is based on startingHeapSize/2048 and rounded DOWN to the first power of 2 between 1MB and 32MB; region sizes <1MB or >32MB are not supported.
 //
@@ -78,22 +95,32 @@ fn get_region_for_heap(min_heap_gb: f32) -> f32 {
 
 #[cfg(test)]
 mod tests {
-    use std::vec;
-
     use crate::{
-        glog::g1gc::{get_g1_gc_region_size_mb, get_g1_target_millis, get_region_for_heap},
+        glog::{
+            flags::Collector,
+            g1gc::{
+                get_g1_gc_region_size_mb, get_g1_target_millis, get_region_for_heap,
+                parse_g1_occupancy,
+            },
+            jvmflags::{parse_jvm_flags, JvmFlags},
+            occupancy::Space,
+        },
         tests::assert_approx_equal,
     };
 
+    fn to_jvm_flags(gc_flags: &[String]) -> JvmFlags {
+        parse_jvm_flags(gc_flags, &Collector::G1GC)
+    }
+
     #[test]
     fn test_get_target_millis() {
-        let default_target_millis = get_g1_target_millis(&vec!["".to_string()]);
+        let default_target_millis = get_g1_target_millis(&to_jvm_flags(&["".to_string()]));
         assert_eq!(default_target_millis, 200);
-        let target_millis_with_flag = get_g1_target_millis(&vec![
+        let target_millis_with_flag = get_g1_target_millis(&to_jvm_flags(&[
             "".to_string(),
             "-XX:MaxGCPauseMillis=500".to_string(),
             "".to_string(),
-        ]);
+        ]));
         assert_eq!(target_millis_with_flag, 500);
     }
 
@@ -101,7 +128,7 @@ mod tests {
     fn test_get_g1_gc_region_size_mb() {
         let region_size = get_g1_gc_region_size_mb(
             0.0,
-            &vec!["-XX:G1HeapRegionSize=33554432".to_string(), "".to_string()],
+            &to_jvm_flags(&["-XX:G1HeapRegionSize=33554432".to_string(), "".to_string()]),
         );
         assert_approx_equal(region_size, 32.0, 0.01);
     }
@@ -112,14 +139,15 @@ mod tests {
         //rounding it down. I am choosing to allow this to happen
         let region_size = get_g1_gc_region_size_mb(
             0.0,
-            &vec!["-XX:G1HeapRegionSize=32000000".to_string(), "".to_string()],
+            &to_jvm_flags(&["-XX:G1HeapRegionSize=32000000".to_string(), "".to_string()]),
         );
         assert_approx_equal(region_size, 30.51, 0.01);
     }
 
     #[test]
     fn test_get_g1_gc_region_size_mb_with_no_region_size() {
-        let region_size = get_g1_gc_region_size_mb(64.0, &vec!["".to_string(), "".to_string()]);
+        let region_size =
+            get_g1_gc_region_size_mb(64.0, &to_jvm_flags(&["".to_string(), "".to_string()]));
         assert_approx_equal(region_size, 32.0, 0.01);
     }
 
@@ -133,4 +161,25 @@ mod tests {
         assert_approx_equal(get_region_for_heap(64.01), 32.0, 0.01);
         assert_approx_equal(get_region_for_heap(128.01), 32.0, 0.01);
     }
+
+    #[test]
+    fn test_parse_g1_occupancy() {
+        let occupancy = parse_g1_occupancy("    [Eden: 10.9G(10.9G)->0.0B(10.9G) Survivors: 192.0M->224.0M Heap: 14.5G(18.5G)->1830.3M(18.5G)]");
+        assert_eq!(occupancy.len(), 3);
+        assert_eq!(occupancy[0].space, Space::Eden);
+        assert_eq!(occupancy[0].before_bytes, 11703785881);
+        assert_eq!(occupancy[0].after_bytes, 0);
+        assert_eq!(occupancy[0].capacity_bytes, 11703785881);
+        assert_eq!(occupancy[1].space, Space::Survivors);
+        assert_eq!(occupancy[1].after_bytes, 224 * 1024 * 1024);
+        assert_eq!(occupancy[1].capacity_bytes, 0);
+        assert_eq!(occupancy[2].space, Space::Heap);
+        assert_eq!(occupancy[2].before_bytes, 15569256448);
+        assert_eq!(occupancy[2].capacity_bytes, 19864223744);
+        let full = parse_g1_occupancy("        [Eden: 0.0B(724.0M)->0.0B(6114.0M) Survivors: 0.0B->0.0B Heap: 8185.4M(8192.0M)->1111.0M(5335.0M)], [Metaspace: 111001K->111110K(1111704K)]");
+        assert_eq!(full[2].after_bytes, 1111 * 1024 * 1024);
+        assert_eq!(full[3].space, Space::Metaspace);
+        assert_eq!(full[3].after_bytes, 111110 * 1024);
+        assert!(parse_g1_occupancy("[Times: user=1.17 sys=0.23, real=0.07 secs]").is_empty());
+    }
 }
@@ -0,0 +1,361 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// parsing for the phase breakdown G1 writes after every pause with -XX:+PrintGCDetails
+//
+//    [Parallel Time: 205.6 ms, GC Workers: 23]
+//        [GC Worker Start (ms): Min: 7004928.5, Avg: 7004929.4, Max: 7004930.3, Diff: 1.7]
+//        [Update RS (ms): Min: 0.0, Avg: 4.2, Max: 6.2, Diff: 6.2, Sum: 95.8]
+//            [Processed Buffers: Min: 0, Avg: 6.6, Max: 23, Diff: 23, Sum: 151]
+//        [Termination (ms): Min: 0.0, Avg: 159.7, Max: 167.3, Diff: 167.3, Sum: 3673.4]
+//    [Clear CT: 4.3 ms]
+//    [Other: 9.0 ms]
+//        [Ref Proc: 3.4 ms]
+//        [Free CSet: 1.5 ms]
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::format_float;
+
+use super::pauses::{generate_gc_name, GCPause};
+
+// how many spaces G1 indents each level of the tree
+const INDENT_PER_LEVEL: usize = 4;
+// only the phases that take the most time are listed for each type of gc
+const TOP_PHASES_PER_GC: usize = 5;
+// these are timestamps or the sum of the other worker phases rather than work of their own
+const WORKER_BOOKKEEPING: [&str; 3] = ["GC Worker Start", "GC Worker End", "GC Worker Total"];
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct WorkerStats {
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+    pub diff: f64,
+    //missing for GC Worker Start and End
+    pub sum: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct G1Phase {
+    pub name: String,
+    //the time of the phase or the average across the workers, None for counters such
+    //as Processed Buffers
+    pub millis: Option<f64>,
+    pub workers: Option<WorkerStats>,
+    pub children: Vec<G1Phase>,
+}
+
+// reads Min: 0.0, Avg: 4.2, Max: 6.2, Diff: 6.2, Sum: 95.8
+fn parse_worker_stats(text: &str) -> Option<WorkerStats> {
+    let mut values: HashMap<&str, f64> = HashMap::new();
+    for pair in text.split(", ") {
+        let (key, value) = pair.split_once(": ")?;
+        values.insert(key.trim(), f64::from_str(value.trim()).ok()?);
+    }
+    Some(WorkerStats {
+        min: *values.get("Min")?,
+        avg: *values.get("Avg")?,
+        max: *values.get("Max")?,
+        diff: *values.get("Diff")?,
+        sum: values.get("Sum").copied(),
+    })
+}
+
+// parses a single line of the tree returning how deep in the tree it is and the phase
+pub fn parse_g1_phase_line(line: &str) -> Option<(usize, G1Phase)> {
+    let indent = line.len() - line.trim_start().len();
+    if indent == 0 {
+        return None;
+    }
+    let body = line
+        .trim()
+        .strip_prefix('[')?
+        .strip_suffix(']')
+        .filter(|body| !body.contains('['))?;
+    let (label, value) = body.split_once(": ")?;
+    let depth = (indent / INDENT_PER_LEVEL).saturating_sub(1);
+    if value.starts_with("Min: ") {
+        let workers = parse_worker_stats(value)?;
+        let (name, millis) = match label.strip_suffix(" (ms)") {
+            Some(name) if !WORKER_BOOKKEEPING.contains(&name) => (name, Some(workers.avg)),
+            Some(name) => (name, None),
+            None => (label, None),
+        };
+        return Some((
+            depth,
+            G1Phase {
+                name: name.to_string(),
+                millis,
+                workers: Some(workers),
+                children: vec![],
+            },
+        ));
+    }
+    //Parallel Time: 205.6 ms, GC Workers: 23
+    let millis = value.split(',').next()?.trim().strip_suffix(" ms")?;
+    Some((
+        depth,
+        G1Phase {
+            name: label.to_string(),
+            millis: Some(f64::from_str(millis).ok()?),
+            workers: None,
+            children: vec![],
+        },
+    ))
+}
+
+// adds the phase under the last phase of the level above it
+pub fn add_g1_phase(phases: &mut Vec<G1Phase>, depth: usize, phase: G1Phase) {
+    if depth > 0 {
+        if let Some(parent) = phases.last_mut() {
+            return add_g1_phase(&mut parent.children, depth - 1, phase);
+        }
+    }
+    phases.push(phase);
+}
+
+pub fn find_g1_phase<'a>(phases: &'a [G1Phase], name: &str) -> Option<&'a G1Phase> {
+    for phase in phases {
+        if phase.name == name {
+            return Some(phase);
+        }
+        if let Some(child) = find_g1_phase(&phase.children, name) {
+            return Some(child);
+        }
+    }
+    None
+}
+
+// the phases that do the actual work, Parallel Time and Other are only the sum of their children
+fn work_phases(phases: &[G1Phase]) -> Vec<&G1Phase> {
+    let mut work = vec![];
+    for phase in phases {
+        if phase.children.is_empty() {
+            if phase.millis.is_some() && phase.name != "GC Worker Other" {
+                work.push(phase);
+            }
+        } else if phase.name == "Parallel Time" || phase.name == "Other" {
+            work.extend(work_phases(&phase.children));
+        } else if phase.millis.is_some() {
+            //Update RS has Processed Buffers under it
+            work.push(phase);
+        }
+    }
+    work
+}
+
+struct PhaseSummary {
+    gc_name: String,
+    name: String,
+    count: i64,
+    total_millis: f64,
+    max_millis: f64,
+    total_pause_millis: f64,
+}
+
+#[derive(Tabled)]
+struct PhaseRow {
+    #[tabled(rename = "GC")]
+    gc_name: String,
+    #[tabled(rename = "Phase")]
+    name: String,
+    #[tabled(rename = "Count")]
+    count: i64,
+    #[tabled(display_with = "format_float", rename = "Avg (ms)")]
+    avg_millis: f64,
+    #[tabled(display_with = "format_float", rename = "Max (ms)")]
+    max_millis: f64,
+    #[tabled(display_with = "format_percent", rename = "% of Pause")]
+    percent_of_pause: f64,
+}
+
+fn format_percent(percent: &f64) -> String {
+    format!("{percent:.2}%")
+}
+
+// the phases that took the most time for every type of gc, the share is of the total time of
+// the pauses that had the phase
+pub fn generate_g1_phase_table(pauses: &[GCPause]) -> String {
+    let mut summaries: HashMap<(String, String), PhaseSummary> = HashMap::new();
+    for pause in pauses {
+        let gc_name = generate_gc_name(pause);
+        let pause_millis = pause.pause_time_seconds * 1000.0;
+        for phase in work_phases(&pause.g1_phases) {
+            let millis = phase.millis.unwrap_or_default();
+            summaries
+                .entry((gc_name.to_string(), phase.name.to_string()))
+                .and_modify(|s| {
+                    s.count += 1;
+                    s.total_millis += millis;
+                    s.max_millis = s.max_millis.max(millis);
+                    s.total_pause_millis += pause_millis;
+                })
+                .or_insert(PhaseSummary {
+                    gc_name: gc_name.to_string(),
+                    name: phase.name.to_string(),
+                    count: 1,
+                    total_millis: millis,
+                    max_millis: millis,
+                    total_pause_millis: pause_millis,
+                });
+        }
+    }
+    if summaries.is_empty() {
+        return "".to_string();
+    }
+    let mut by_gc: HashMap<String, Vec<PhaseSummary>> = HashMap::new();
+    for summary in summaries.into_values() {
+        by_gc
+            .entry(summary.gc_name.to_string())
+            .or_default()
+            .push(summary);
+    }
+    let mut gc_names: Vec<String> = by_gc.keys().cloned().collect();
+    gc_names.sort();
+    let mut rows = vec![];
+    for gc_name in gc_names {
+        let mut phases = by_gc.remove(&gc_name).unwrap_or_default();
+        phases.sort_by(|a, b| b.total_millis.total_cmp(&a.total_millis));
+        for s in phases.into_iter().take(TOP_PHASES_PER_GC) {
+            rows.push(PhaseRow {
+                gc_name: s.gc_name,
+                name: s.name,
+                count: s.count,
+                avg_millis: s.total_millis / s.count as f64,
+                max_millis: s.max_millis,
+                percent_of_pause: if s.total_pause_millis > 0.0 {
+                    s.total_millis / s.total_pause_millis * 100.0
+                } else {
+                    0.0
+                },
+            });
+        }
+    }
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::pauses::GCPause;
+
+    use super::{
+        add_g1_phase, find_g1_phase, generate_g1_phase_table, parse_g1_phase_line, G1Phase,
+    };
+
+    const DETAILS: &str = "    [Parallel Time: 205.6 ms, GC Workers: 23]
+        [GC Worker Start (ms): Min: 7004928.5, Avg: 7004929.4, Max: 7004930.3, Diff: 1.7]
+        [Ext Root Scanning (ms): Min: 1.9, Avg: 11.8, Max: 204.7, Diff: 202.8, Sum: 272.1]
+        [Update RS (ms): Min: 0.0, Avg: 4.2, Max: 6.2, Diff: 6.2, Sum: 95.8]
+            [Processed Buffers: Min: 0, Avg: 6.6, Max: 23, Diff: 23, Sum: 151]
+        [Object Copy (ms): Min: 0.0, Avg: 28.2, Max: 30.1, Diff: 30.1, Sum: 647.8]
+        [Termination (ms): Min: 0.0, Avg: 159.7, Max: 167.3, Diff: 167.3, Sum: 3673.4]
+            [Termination Attempts: Min: 1, Avg: 267.4, Max: 321, Diff: 320, Sum: 6151]
+        [GC Worker Other (ms): Min: 0.0, Avg: 0.1, Max: 0.3, Diff: 0.3, Sum: 2.8]
+        [GC Worker Total (ms): Min: 203.2, Avg: 204.1, Max: 205.2, Diff: 1.9, Sum: 4694.6]
+    [Code Root Fixup: 0.0 ms]
+    [Clear CT: 4.3 ms]
+    [Other: 9.0 ms]
+        [Ref Proc: 3.4 ms]
+        [Free CSet: 1.5 ms]";
+
+    fn parse_details() -> Vec<G1Phase> {
+        let mut phases = vec![];
+        for line in DETAILS.lines() {
+            let (depth, phase) = parse_g1_phase_line(line).expect("expected a phase");
+            add_g1_phase(&mut phases, depth, phase);
+        }
+        phases
+    }
+
+    #[test]
+    fn test_parse_g1_phase_line() {
+        let (depth, phase) = parse_g1_phase_line(
+            "        [Termination (ms): Min: 0.0, Avg: 159.7, Max: 167.3, Diff: 167.3, Sum: 3673.4]",
+        )
+        .unwrap();
+        assert_eq!(depth, 1);
+        assert_eq!(phase.name, "Termination");
+        assert_eq!(phase.millis, Some(159.7));
+        let workers = phase.workers.unwrap();
+        assert_eq!(workers.max, 167.3);
+        assert_eq!(workers.sum, Some(3673.4));
+        let (depth, parallel) =
+            parse_g1_phase_line("    [Parallel Time: 205.6 ms, GC Workers: 23]").unwrap();
+        assert_eq!(depth, 0);
+        assert_eq!(parallel.millis, Some(205.6));
+        let (_, start) = parse_g1_phase_line(
+            "        [GC Worker End (ms): Min: 7005133.4, Avg: 7005133.5, Max: 7005133.8, Diff: 0.5]",
+        )
+        .unwrap();
+        assert_eq!(start.millis, None);
+        assert_eq!(start.workers.unwrap().sum, None);
+        assert!(parse_g1_phase_line("    [Times: user=1.17 sys=0.23, real=0.07 secs]").is_none());
+        assert!(parse_g1_phase_line("    [Eden: 10.9G(10.9G)->0.0B(10.9G) Survivors: 192.0M->224.0M Heap: 14.5G(18.5G)->1830.3M(18.5G)]").is_none());
+        assert!(parse_g1_phase_line("[GC concurrent-root-region-scan-start]").is_none());
+    }
+
+    #[test]
+    fn test_g1_phase_tree() {
+        let phases = parse_details();
+        assert_eq!(phases.len(), 4);
+        assert_eq!(phases[0].name, "Parallel Time");
+        assert_eq!(phases[0].children.len(), 7);
+        assert_eq!(phases[3].name, "Other");
+        assert_eq!(phases[3].children.len(), 2);
+        let update_rs = find_g1_phase(&phases, "Update RS").unwrap();
+        assert_eq!(update_rs.children[0].name, "Processed Buffers");
+        assert_eq!(update_rs.children[0].millis, None);
+        assert_eq!(
+            find_g1_phase(&phases, "Free CSet").unwrap().millis,
+            Some(1.5)
+        );
+    }
+
+    #[test]
+    fn test_generate_g1_phase_table() {
+        let pauses = vec![GCPause {
+            gc_type: "G1 Evacuation Pause".to_string(),
+            attributes: vec!["young".to_string()],
+            pause_time_seconds: 0.2189828,
+            g1_phases: parse_details(),
+            ..Default::default()
+        }];
+        let output = generate_g1_phase_table(&pauses);
+        assert_eq!(
+            output,
+            "+-------------------------------+-------------------+-------+----------+----------+------------+
+| GC                            |       Phase       | Count | Avg (ms) | Max (ms) | % of Pause |
++-------------------------------+-------------------+-------+----------+----------+------------+
+| G1 Evacuation Pause - (young) |    Termination    |   1   |  159.70  |  159.70  |   72.93%   |
++-------------------------------+-------------------+-------+----------+----------+------------+
+| G1 Evacuation Pause - (young) |    Object Copy    |   1   |  28.20   |  28.20   |   12.88%   |
++-------------------------------+-------------------+-------+----------+----------+------------+
+| G1 Evacuation Pause - (young) | Ext Root Scanning |   1   |  11.80   |  11.80   |   5.39%    |
++-------------------------------+-------------------+-------+----------+----------+------------+
+| G1 Evacuation Pause - (young) |     Clear CT      |   1   |   4.30   |   4.30   |   1.96%    |
++-------------------------------+-------------------+-------+----------+----------+------------+
+| G1 Evacuation Pause - (young) |     Update RS     |   1   |   4.20   |   4.20   |   1.92%    |
++-------------------------------+-------------------+-------+----------+----------+------------+
+"
+        );
+        assert_eq!(generate_g1_phase_table(&[]), "");
+    }
+}
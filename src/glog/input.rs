@@ -0,0 +1,285 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// opens gc logs that are plain text, gzip or zstd compressed, or members of a zip or tar
+// archive. The format is picked from the magic bytes so the file names do not matter and
+// everything is decompressed as it is read instead of being unpacked to disk first
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+
+use super::rotation::{is_gc_log_name, NoLogFilesError};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+const ZIP_MAGIC: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
+// tar headers are 512 bytes with "ustar" at offset 257
+const TAR_HEADER_BYTES: usize = 512;
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_MAGIC: &[u8] = b"ustar";
+
+// reads the first bytes of a stream and hands back a reader that still starts at the beginning
+fn sniff<'a>(mut reader: Box<dyn Read + 'a>) -> io::Result<(Vec<u8>, Box<dyn Read + 'a>)> {
+    let mut head = vec![];
+    (&mut reader)
+        .take(TAR_HEADER_BYTES as u64)
+        .read_to_end(&mut head)?;
+    let rest = Cursor::new(head.clone()).chain(reader);
+    Ok((head, Box::new(rest)))
+}
+
+fn is_tar(head: &[u8]) -> bool {
+    head.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len()) == Some(TAR_MAGIC)
+}
+
+// calls visit with every gc log found in the stream and returns how many there were
+fn read_stream<'a, F>(
+    name: String,
+    reader: Box<dyn Read + 'a>,
+    visit: &mut F,
+) -> Result<usize, Box<dyn Error>>
+where
+    F: FnMut(String, &mut dyn BufRead) -> Result<(), Box<dyn Error>>,
+{
+    let (head, reader) = sniff(reader)?;
+    if head.starts_with(&GZIP_MAGIC) {
+        return read_stream(name, Box::new(MultiGzDecoder::new(reader)), visit);
+    }
+    if head.starts_with(&ZSTD_MAGIC) {
+        return read_stream(name, Box::new(zstd::Decoder::new(reader)?), visit);
+    }
+    if is_tar(&head) {
+        return read_tar(&name, reader, visit);
+    }
+    visit(name, &mut BufReader::new(reader))?;
+    Ok(1)
+}
+
+fn read_tar<'a, F>(
+    name: &str,
+    reader: Box<dyn Read + 'a>,
+    visit: &mut F,
+) -> Result<usize, Box<dyn Error>>
+where
+    F: FnMut(String, &mut dyn BufRead) -> Result<(), Box<dyn Error>>,
+{
+    let mut archive = tar::Archive::new(reader);
+    let mut count = 0;
+    for entry_result in archive.entries()? {
+        let entry = entry_result?;
+        let member = entry.path()?.display().to_string();
+        if !entry.header().entry_type().is_file() || !is_gc_log_name(Path::new(&member)) {
+            continue;
+        }
+        count += read_stream(format!("{name}:{member}"), Box::new(entry), visit)?;
+    }
+    Ok(count)
+}
+
+fn read_zip<F>(name: &str, file: File, visit: &mut F) -> Result<usize, Box<dyn Error>>
+where
+    F: FnMut(String, &mut dyn BufRead) -> Result<(), Box<dyn Error>>,
+{
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut count = 0;
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        let member = entry.name().to_string();
+        if entry.is_dir() || !is_gc_log_name(Path::new(&member)) {
+            continue;
+        }
+        count += read_stream(format!("{name}:{member}"), Box::new(entry), visit)?;
+    }
+    Ok(count)
+}
+
+// calls visit with every gc log in a stream such as stdin or a pipe, zip needs to seek so
+// only gzip, zstd and tar work here
+pub fn read_stream_sources<'a, F>(
+    name: String,
+    reader: Box<dyn Read + 'a>,
+    visit: &mut F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut(String, &mut dyn BufRead) -> Result<(), Box<dyn Error>>,
+{
+    if read_stream(name.to_string(), reader, visit)? == 0 {
+        return Err(Box::new(NoLogFilesError { location: name }));
+    }
+    Ok(())
+}
+
+// calls visit with a reader for every gc log in the file, archives can hold several of them
+pub fn read_log_sources<F>(path: &Path, visit: &mut F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut(String, &mut dyn BufRead) -> Result<(), Box<dyn Error>>,
+{
+    let name = path.display().to_string();
+    let mut file = File::open(path)?;
+    let mut magic = vec![];
+    (&mut file)
+        .take(ZIP_MAGIC.len() as u64)
+        .read_to_end(&mut magic)?;
+    //zip keeps its index at the end of the file so it needs to be opened from the start again
+    let count = if magic == ZIP_MAGIC {
+        read_zip(&name, File::open(path)?, visit)?
+    } else {
+        read_stream(
+            name.to_string(),
+            Box::new(Cursor::new(magic).chain(file)),
+            visit,
+        )?
+    };
+    if count == 0 {
+        return Err(Box::new(NoLogFilesError { location: name }));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io::{BufRead, Write};
+
+    use flate2::{write::GzEncoder, Compression};
+    use tempfile::NamedTempFile;
+
+    use super::{read_log_sources, read_stream_sources};
+
+    const LOG: &str = "2021-02-22T01:01:02.120+0000: 22000.498: [GC pause (G1 Evacuation Pause) (young), 0.0911111 secs]\n";
+
+    fn read_all(bytes: &[u8]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+        let mut file = NamedTempFile::new().expect("unable to make tmp file");
+        file.write_all(bytes).unwrap();
+        let path = file.into_temp_path();
+        let mut logs = vec![];
+        read_log_sources(&path, &mut |name: String, reader: &mut dyn BufRead| {
+            let mut text = String::new();
+            reader.read_to_string(&mut text)?;
+            logs.push((name, text));
+            Ok(())
+        })?;
+        //only keep the member part of the name, the temp file name is random
+        Ok(logs
+            .into_iter()
+            .map(|(name, text)| {
+                let member = name.split_once(':').map(|(_, m)| m.to_string());
+                (member.unwrap_or_default(), text)
+            })
+            .collect())
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn tar(members: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(vec![]);
+        for (name, bytes) in members {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *bytes).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_read_plain() {
+        let logs = read_all(LOG.as_bytes()).unwrap();
+        assert_eq!(logs, vec![("".to_string(), LOG.to_string())]);
+    }
+
+    #[test]
+    fn test_read_gzip() {
+        let logs = read_all(&gzip(LOG.as_bytes())).unwrap();
+        assert_eq!(logs[0].1, LOG);
+    }
+
+    #[test]
+    fn test_read_zstd() {
+        let compressed = zstd::encode_all(LOG.as_bytes(), 0).unwrap();
+        let logs = read_all(&compressed).unwrap();
+        assert_eq!(logs[0].1, LOG);
+    }
+
+    #[test]
+    fn test_read_zip() {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+        let options = zip::write::FileOptions::default();
+        writer.add_directory("logs/", options).unwrap();
+        writer.start_file("logs/gc.log.0", options).unwrap();
+        writer.write_all(LOG.as_bytes()).unwrap();
+        writer.start_file("logs/server.log", options).unwrap();
+        writer.write_all(b"not a gc log").unwrap();
+        writer.start_file("logs/gc.log.1.gz", options).unwrap();
+        writer.write_all(&gzip(LOG.as_bytes())).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+        let logs = read_all(&bytes).unwrap();
+        assert_eq!(
+            logs,
+            vec![
+                ("logs/gc.log.0".to_string(), LOG.to_string()),
+                ("logs/gc.log.1.gz".to_string(), LOG.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_tar_gz() {
+        let bytes = gzip(&tar(&[
+            ("gc.log.0", LOG.as_bytes()),
+            ("metadata.json", b"{}"),
+            ("gc.log.1.current", LOG.as_bytes()),
+        ]));
+        let logs = read_all(&bytes).unwrap();
+        assert_eq!(
+            logs,
+            vec![
+                ("gc.log.0".to_string(), LOG.to_string()),
+                ("gc.log.1.current".to_string(), LOG.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_stream() {
+        let mut logs = vec![];
+        let reader = std::io::Cursor::new(gzip(LOG.as_bytes()));
+        read_stream_sources(
+            "stdin".to_string(),
+            Box::new(reader),
+            &mut |name: String, reader: &mut dyn BufRead| {
+                logs.push((name, reader.lines().count()));
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(logs, vec![("stdin".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_read_archive_without_gc_logs() {
+        let bytes = gzip(&tar(&[("metadata.json", b"{}")]));
+        let err = read_all(&bytes).unwrap_err().to_string();
+        assert!(err.starts_with("no gc log files found in"), "was {err}");
+    }
+}
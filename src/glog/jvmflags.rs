@@ -0,0 +1,573 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// typed view of the flags on the jdk 8 CommandLine flags line filled in with the jdk 8
+// defaults of the collector for the flags that were not set
+//
+// CommandLine flags: -XX:+DisableExplicitGC -XX:HeapDumpPath=/opt/dremio/data/ -XX:InitialHeapSize=2048000000 -XX:MaxGCPauseMillis=500 -XX:ThreadStackSize=1024 -XX:+UseG1GC
+
+use std::fmt;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::human_bytes;
+
+use super::{convert::parse_size_bytes, flags::Collector};
+
+use self::FlagKind::{Bool, Bytes, Kilobytes, Number, Text};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FlagKind {
+    Bool,      //-XX:+UseG1GC or -XX:-UseG1GC
+    Bytes,     //-XX:MaxHeapSize=17179869184, also accepts 16g
+    Kilobytes, //-XX:ThreadStackSize=1024
+    Number,    //-XX:MaxGCPauseMillis=500 or -XX:MaxRAMPercentage=75.0
+    Text,      //-XX:HeapDumpPath=/opt/dremio/data/
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum FlagValue {
+    Bool(bool),
+    Size(i64), //bytes
+    Number(f64),
+    Text(String),
+}
+
+impl fmt::Display for FlagValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FlagValue::Bool(value) => write!(f, "{value}"),
+            FlagValue::Size(bytes) => write!(f, "{}", human_bytes(*bytes)),
+            FlagValue::Number(value) => write!(f, "{value}"),
+            FlagValue::Text(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+pub struct FlagDefinition {
+    pub name: &'static str,
+    pub kind: FlagKind,
+    //the collectors the default applies to, empty for all of them
+    pub collectors: &'static [Collector],
+    //none when jdk 8 picks the value at startup from the machine or other flags
+    pub default: Option<&'static str>,
+}
+
+const fn flag(
+    name: &'static str,
+    kind: FlagKind,
+    collectors: &'static [Collector],
+    default: Option<&'static str>,
+) -> FlagDefinition {
+    FlagDefinition {
+        name,
+        kind,
+        collectors,
+        default,
+    }
+}
+
+const ALL: &[Collector] = &[];
+const G1: &[Collector] = &[Collector::G1GC];
+const CMS: &[Collector] = &[Collector::CMS];
+const PARALLEL: &[Collector] = &[Collector::Parallel];
+
+// jdk 8 on 64 bit linux, a flag can be listed more than once when the default depends on
+// the collector, the first one that matches the collector wins
+pub const FLAG_DEFINITIONS: &[FlagDefinition] = &[
+    //collector selection
+    flag("UseG1GC", Bool, ALL, None),
+    flag("UseConcMarkSweepGC", Bool, ALL, None),
+    flag("UseParNewGC", Bool, ALL, None),
+    flag("UseParallelGC", Bool, ALL, None),
+    flag("UseParallelOldGC", Bool, ALL, None),
+    flag("UseSerialGC", Bool, ALL, None),
+    flag("UseZGC", Bool, ALL, None),
+    flag("UseShenandoahGC", Bool, ALL, None),
+    //heap sizing, jdk 8 writes the ergonomic sizes on the flags line
+    flag("MaxHeapSize", Bytes, ALL, None),
+    flag("InitialHeapSize", Bytes, ALL, None),
+    flag("MaxNewSize", Bytes, ALL, None),
+    flag("NewSize", Bytes, ALL, None),
+    flag("OldSize", Bytes, ALL, None),
+    flag("MinHeapDeltaBytes", Bytes, ALL, None),
+    flag("MaxRAM", Bytes, ALL, None),
+    flag("MaxRAMPercentage", Number, ALL, None),
+    flag("MinRAMPercentage", Number, ALL, None),
+    flag("InitialRAMPercentage", Number, ALL, None),
+    flag("NewRatio", Number, ALL, Some("2")),
+    flag("SurvivorRatio", Number, ALL, Some("8")),
+    flag("TargetSurvivorRatio", Number, ALL, Some("50")),
+    flag("MaxTenuringThreshold", Number, CMS, Some("6")),
+    flag("MaxTenuringThreshold", Number, ALL, Some("15")),
+    flag("InitialTenuringThreshold", Number, ALL, Some("7")),
+    flag("MinHeapFreeRatio", Number, ALL, Some("40")),
+    flag("MaxHeapFreeRatio", Number, ALL, Some("70")),
+    flag("AlwaysPreTouch", Bool, ALL, Some("false")),
+    flag("UseAdaptiveSizePolicy", Bool, PARALLEL, Some("true")),
+    //memory outside the heap
+    flag("MaxDirectMemorySize", Bytes, ALL, None),
+    flag("MetaspaceSize", Bytes, ALL, Some("21807104")),
+    flag("MaxMetaspaceSize", Bytes, ALL, None),
+    flag("CompressedClassSpaceSize", Bytes, ALL, Some("1073741824")),
+    flag("ReservedCodeCacheSize", Bytes, ALL, Some("251658240")),
+    flag("InitialCodeCacheSize", Bytes, ALL, Some("2555904")),
+    flag("ThreadStackSize", Kilobytes, ALL, Some("1024")),
+    flag("MarkStackSize", Bytes, ALL, None),
+    flag("MarkStackSizeMax", Bytes, ALL, None),
+    flag("UseCompressedOops", Bool, ALL, Some("true")),
+    flag("UseCompressedClassPointers", Bool, ALL, Some("true")),
+    flag("UseLargePages", Bool, ALL, Some("false")),
+    flag("UseTransparentHugePages", Bool, ALL, Some("false")),
+    flag("UseNUMA", Bool, ALL, Some("false")),
+    //gc threads and work
+    flag("ParallelGCThreads", Number, ALL, None),
+    flag("ConcGCThreads", Number, ALL, None),
+    flag("ParallelRefProcEnabled", Bool, ALL, Some("false")),
+    flag("DisableExplicitGC", Bool, ALL, Some("false")),
+    flag("ExplicitGCInvokesConcurrent", Bool, ALL, Some("false")),
+    flag("SoftRefLRUPolicyMSPerMB", Number, ALL, Some("1000")),
+    flag("UseContainerSupport", Bool, ALL, None),
+    flag("ActiveProcessorCount", Number, ALL, None),
+    //G1
+    flag("MaxGCPauseMillis", Number, G1, Some("200")),
+    flag("MaxGCPauseMillis", Number, ALL, None),
+    flag("GCPauseIntervalMillis", Number, ALL, None),
+    flag("G1HeapRegionSize", Bytes, ALL, None),
+    flag("InitiatingHeapOccupancyPercent", Number, G1, Some("45")),
+    flag("InitiatingHeapOccupancyPercent", Number, ALL, None),
+    flag("G1ReservePercent", Number, G1, Some("10")),
+    flag("G1HeapWastePercent", Number, G1, Some("5")),
+    flag("G1MixedGCLiveThresholdPercent", Number, G1, Some("85")),
+    flag("G1MixedGCCountTarget", Number, G1, Some("8")),
+    flag("G1NewSizePercent", Number, G1, Some("5")),
+    flag("G1MaxNewSizePercent", Number, G1, Some("60")),
+    flag("G1RSetUpdatingPauseTimePercent", Number, G1, Some("10")),
+    flag("G1ConcRefinementThreads", Number, ALL, None),
+    flag("ClassUnloadingWithConcurrentMark", Bool, G1, Some("true")),
+    flag("UseStringDeduplication", Bool, G1, Some("false")),
+    //CMS
+    flag("CMSInitiatingOccupancyFraction", Number, CMS, Some("-1")),
+    flag("CMSInitiatingOccupancyFraction", Number, ALL, None),
+    flag("UseCMSInitiatingOccupancyOnly", Bool, CMS, Some("false")),
+    flag("UseCMSInitiatingOccupancyOnly", Bool, ALL, None),
+    flag("CMSClassUnloadingEnabled", Bool, CMS, Some("true")),
+    flag("CMSClassUnloadingEnabled", Bool, ALL, None),
+    flag("CMSParallelRemarkEnabled", Bool, CMS, Some("true")),
+    flag("CMSParallelRemarkEnabled", Bool, ALL, None),
+    flag("CMSScavengeBeforeRemark", Bool, CMS, Some("false")),
+    flag("CMSScavengeBeforeRemark", Bool, ALL, None),
+    //Shenandoah
+    flag("ShenandoahGCHeuristics", Text, ALL, None),
+    flag("ShenandoahMinFreeThreshold", Number, ALL, None),
+    //safepoints and the jit
+    flag("UseBiasedLocking", Bool, ALL, Some("true")),
+    flag("UseCountedLoopSafepoints", Bool, ALL, None),
+    flag("SafepointTimeout", Bool, ALL, None),
+    flag("SafepointTimeoutDelay", Number, ALL, None),
+    flag("TieredCompilation", Bool, ALL, None),
+    flag("CICompilerCount", Number, ALL, None),
+    flag("UnlockDiagnosticVMOptions", Bool, ALL, None),
+    flag("UnlockExperimentalVMOptions", Bool, ALL, None),
+    flag("UseFastUnorderedTimeStamps", Bool, ALL, None),
+    //logging
+    flag("PrintGC", Bool, ALL, None),
+    flag("PrintGCDetails", Bool, ALL, None),
+    flag("PrintGCDateStamps", Bool, ALL, None),
+    flag("PrintGCTimeStamps", Bool, ALL, None),
+    flag("PrintGCCause", Bool, ALL, None),
+    flag("PrintGCApplicationStoppedTime", Bool, ALL, None),
+    flag("PrintGCApplicationConcurrentTime", Bool, ALL, None),
+    flag("PrintTenuringDistribution", Bool, ALL, None),
+    flag("PrintAdaptiveSizePolicy", Bool, ALL, None),
+    flag("PrintReferenceGC", Bool, ALL, None),
+    flag("PrintHeapAtGC", Bool, ALL, None),
+    flag("PrintClassHistogramBeforeFullGC", Bool, ALL, None),
+    flag("PrintClassHistogramAfterFullGC", Bool, ALL, None),
+    flag("PrintSafepointStatistics", Bool, ALL, None),
+    flag("PrintSafepointStatisticsCount", Number, ALL, None),
+    flag("UseGCLogFileRotation", Bool, ALL, None),
+    flag("NumberOfGCLogFiles", Number, ALL, None),
+    flag("GCLogFileSize", Bytes, ALL, None),
+    //out of memory handling
+    flag("HeapDumpOnOutOfMemoryError", Bool, ALL, None),
+    flag("HeapDumpPath", Text, ALL, None),
+    flag("ErrorFile", Text, ALL, None),
+    flag("OnOutOfMemoryError", Text, ALL, None),
+    flag("ExitOnOutOfMemoryError", Bool, ALL, None),
+    flag("CrashOnOutOfMemoryError", Bool, ALL, None),
+];
+
+// the definition of the flag for the collector
+pub fn find_flag_definition(name: &str, collector: &Collector) -> Option<&'static FlagDefinition> {
+    FLAG_DEFINITIONS
+        .iter()
+        .find(|d| d.name == name && (d.collectors.is_empty() || d.collectors.contains(collector)))
+}
+
+// reads the part after the = into the kind of the flag
+pub fn parse_flag_value(kind: FlagKind, raw: &str) -> Option<FlagValue> {
+    match kind {
+        FlagKind::Bool => match raw {
+            "true" => Some(FlagValue::Bool(true)),
+            "false" => Some(FlagValue::Bool(false)),
+            _ => None,
+        },
+        FlagKind::Bytes => raw
+            .parse::<i64>()
+            .ok()
+            .or_else(|| parse_size_bytes(raw))
+            .map(FlagValue::Size),
+        FlagKind::Kilobytes => raw
+            .parse::<i64>()
+            .ok()
+            .map(|k| k * 1024)
+            .or_else(|| parse_size_bytes(raw))
+            .map(FlagValue::Size),
+        FlagKind::Number => raw.parse::<f64>().ok().map(FlagValue::Number),
+        FlagKind::Text => Some(FlagValue::Text(raw.to_string())),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FlagSetting {
+    pub name: String,
+    pub value: FlagValue,
+    //false when the value is the jdk 8 default
+    pub explicit: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct JvmFlags {
+    //sorted by name
+    pub settings: Vec<FlagSetting>,
+    //flags that are not in the definitions, kept as they were logged
+    pub unknown: Vec<String>,
+    //names of flags set more than once, the last one wins like on the java command line
+    pub duplicates: Vec<String>,
+    //known flags with a value that does not fit their kind
+    pub invalid: Vec<String>,
+}
+
+impl JvmFlags {
+    pub fn get(&self, name: &str) -> Option<&FlagSetting> {
+        self.settings.iter().find(|s| s.name == name)
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name)?.value {
+            FlagValue::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_size(&self, name: &str) -> Option<i64> {
+        match self.get(name)?.value {
+            FlagValue::Size(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn get_number(&self, name: &str) -> Option<f64> {
+        match self.get(name)?.value {
+            FlagValue::Number(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn is_explicit(&self, name: &str) -> bool {
+        self.get(name).map(|s| s.explicit).unwrap_or(false)
+    }
+}
+
+// splits -XX:+UseG1GC, -XX:-UseG1GC and -XX:MaxHeapSize=17179869184 into the name and raw value
+fn split_flag(flag: &str) -> Option<(&str, &str)> {
+    let flag = flag.strip_prefix("-XX:")?;
+    if let Some(name) = flag.strip_prefix('+') {
+        return Some((name, "true"));
+    }
+    if let Some(name) = flag.strip_prefix('-') {
+        return Some((name, "false"));
+    }
+    flag.split_once('=')
+}
+
+pub fn parse_jvm_flags(gc_flags: &[String], collector: &Collector) -> JvmFlags {
+    let mut jvm_flags = JvmFlags::default();
+    for flag in gc_flags.iter().filter(|f| !f.trim().is_empty()) {
+        let (name, raw) = match split_flag(flag) {
+            Some(split) => split,
+            None => {
+                jvm_flags.unknown.push(flag.to_string());
+                continue;
+            }
+        };
+        let definition = match find_flag_definition(name, collector) {
+            Some(definition) => definition,
+            None => {
+                jvm_flags.unknown.push(flag.to_string());
+                continue;
+            }
+        };
+        //a bool flag given a value or a value flag given a +/- is as wrong as a bad number
+        let bool_syntax = !flag.contains('=');
+        let value = match parse_flag_value(definition.kind, raw) {
+            Some(value) if bool_syntax == (definition.kind == FlagKind::Bool) => value,
+            _ => {
+                jvm_flags.invalid.push(flag.to_string());
+                continue;
+            }
+        };
+        match jvm_flags.settings.iter_mut().find(|s| s.name == name) {
+            Some(setting) => {
+                if !jvm_flags.duplicates.iter().any(|d| d == name) {
+                    jvm_flags.duplicates.push(name.to_string());
+                }
+                setting.value = value;
+            }
+            None => jvm_flags.settings.push(FlagSetting {
+                name: name.to_string(),
+                value,
+                explicit: true,
+            }),
+        }
+    }
+    for definition in FLAG_DEFINITIONS {
+        if jvm_flags.get(definition.name).is_some() {
+            continue;
+        }
+        //the default comes from the definition for the collector, not the first one listed
+        let value = find_flag_definition(definition.name, collector)
+            .and_then(|d| parse_flag_value(d.kind, d.default?));
+        if let Some(value) = value {
+            jvm_flags.settings.push(FlagSetting {
+                name: definition.name.to_string(),
+                value,
+                explicit: false,
+            });
+        }
+    }
+    jvm_flags.settings.sort_by(|a, b| a.name.cmp(&b.name));
+    jvm_flags
+}
+
+#[derive(Tabled)]
+struct FlagRow {
+    #[tabled(rename = "Flag")]
+    name: String,
+    #[tabled(rename = "Value")]
+    value: String,
+    #[tabled(display_with = "display_source", rename = "Source")]
+    explicit: bool,
+}
+
+fn display_source(explicit: &bool) -> String {
+    if *explicit {
+        "set".to_string()
+    } else {
+        "default".to_string()
+    }
+}
+
+// every effective flag value and whether it was set or is the jdk 8 default
+pub fn generate_jvm_flags_table(jvm_flags: &JvmFlags) -> String {
+    if jvm_flags.settings.is_empty() {
+        return "".to_string();
+    }
+    let rows: Vec<FlagRow> = jvm_flags
+        .settings
+        .iter()
+        .map(|s| FlagRow {
+            name: s.name.clone(),
+            value: s.value.to_string(),
+            explicit: s.explicit,
+        })
+        .collect();
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+// the flags that could not be read, empty when there were none
+pub fn generate_flag_problems(jvm_flags: &JvmFlags) -> String {
+    let mut lines = vec![];
+    if !jvm_flags.unknown.is_empty() {
+        lines.push(format!(
+            "unknown flags:       {}",
+            jvm_flags.unknown.join(" ")
+        ));
+    }
+    if !jvm_flags.duplicates.is_empty() {
+        lines.push(format!(
+            "duplicate flags:     {}",
+            jvm_flags.duplicates.join(" ")
+        ));
+    }
+    if !jvm_flags.invalid.is_empty() {
+        lines.push(format!(
+            "invalid flags:       {}",
+            jvm_flags.invalid.join(" ")
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::flags::Collector;
+
+    use super::{
+        generate_flag_problems, generate_jvm_flags_table, parse_flag_value, parse_jvm_flags,
+        FlagKind, FlagValue,
+    };
+
+    fn to_flags(flags: &str) -> Vec<String> {
+        flags.split(' ').map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_flag_value() {
+        assert_eq!(
+            parse_flag_value(FlagKind::Bytes, "17179869184"),
+            Some(FlagValue::Size(17179869184))
+        );
+        assert_eq!(
+            parse_flag_value(FlagKind::Bytes, "16g"),
+            Some(FlagValue::Size(17179869184))
+        );
+        assert_eq!(
+            parse_flag_value(FlagKind::Kilobytes, "1024"),
+            Some(FlagValue::Size(1024 * 1024))
+        );
+        assert_eq!(
+            parse_flag_value(FlagKind::Number, "75.0"),
+            Some(FlagValue::Number(75.0))
+        );
+        assert_eq!(parse_flag_value(FlagKind::Number, "lots"), None);
+        assert_eq!(parse_flag_value(FlagKind::Bytes, "-"), None);
+        let bad_heap = parse_jvm_flags(&to_flags("-XX:MaxHeapSize=abc"), &Collector::G1GC);
+        assert_eq!(bad_heap.get_size("MaxHeapSize"), None);
+        assert_eq!(bad_heap.invalid, vec!["-XX:MaxHeapSize=abc"]);
+    }
+
+    #[test]
+    fn test_parse_jvm_flags() {
+        let jvm_flags = parse_jvm_flags(
+            &to_flags("-XX:+UseG1GC -XX:MaxGCPauseMillis=500 -XX:MaxHeapSize=17179869184 -XX:ThreadStackSize=512 -XX:+UseBobFeature -XX:MaxGCPauseMillis=300 -XX:InitialHeapSize=lots -XX:+MaxHeapSize -Xmx16g "),
+            &Collector::G1GC,
+        );
+        assert_eq!(jvm_flags.get_number("MaxGCPauseMillis"), Some(300.0));
+        assert!(jvm_flags.is_explicit("MaxGCPauseMillis"));
+        assert_eq!(jvm_flags.get_size("MaxHeapSize"), Some(17179869184));
+        assert_eq!(jvm_flags.get_size("ThreadStackSize"), Some(512 * 1024));
+        assert_eq!(jvm_flags.get_number("G1HeapWastePercent"), Some(5.0));
+        assert!(!jvm_flags.is_explicit("G1HeapWastePercent"));
+        assert_eq!(jvm_flags.get_bool("UseG1GC"), Some(true));
+        assert_eq!(jvm_flags.get("InitialHeapSize"), None);
+        assert_eq!(jvm_flags.get("CMSInitiatingOccupancyFraction"), None);
+        assert_eq!(jvm_flags.unknown, vec!["-XX:+UseBobFeature", "-Xmx16g"]);
+        assert_eq!(jvm_flags.duplicates, vec!["MaxGCPauseMillis"]);
+        assert_eq!(
+            jvm_flags.invalid,
+            vec!["-XX:InitialHeapSize=lots", "-XX:+MaxHeapSize"]
+        );
+        assert_eq!(
+            generate_flag_problems(&jvm_flags),
+            "unknown flags:       -XX:+UseBobFeature -Xmx16g
+duplicate flags:     MaxGCPauseMillis
+invalid flags:       -XX:InitialHeapSize=lots -XX:+MaxHeapSize"
+        );
+    }
+
+    #[test]
+    fn test_parse_jvm_flags_collector_defaults() {
+        let cms = parse_jvm_flags(&to_flags("-XX:+UseConcMarkSweepGC"), &Collector::CMS);
+        assert_eq!(cms.get_number("MaxTenuringThreshold"), Some(6.0));
+        assert_eq!(cms.get_number("CMSInitiatingOccupancyFraction"), Some(-1.0));
+        assert_eq!(cms.get("MaxGCPauseMillis"), None);
+        let parallel = parse_jvm_flags(&to_flags("-XX:+UseParallelGC"), &Collector::Parallel);
+        assert_eq!(parallel.get_number("MaxTenuringThreshold"), Some(15.0));
+        assert_eq!(parallel.get_bool("UseAdaptiveSizePolicy"), Some(true));
+        assert_eq!(parallel.get("G1HeapWastePercent"), None);
+        assert!(parallel.unknown.is_empty());
+    }
+
+    #[test]
+    fn test_generate_jvm_flags_table() {
+        let jvm_flags = parse_jvm_flags(
+            &to_flags("-XX:MaxHeapSize=17179869184 -XX:+UseSerialGC"),
+            &Collector::SerialGC,
+        );
+        assert_eq!(
+            generate_jvm_flags_table(&jvm_flags),
+            "+-----------------------------+------------+---------+
+| Flag                        |   Value    | Source  |
++-----------------------------+------------+---------+
+| AlwaysPreTouch              |   false    | default |
++-----------------------------+------------+---------+
+| CompressedClassSpaceSize    | 1024.00 mb | default |
++-----------------------------+------------+---------+
+| DisableExplicitGC           |   false    | default |
++-----------------------------+------------+---------+
+| ExplicitGCInvokesConcurrent |   false    | default |
++-----------------------------+------------+---------+
+| InitialCodeCacheSize        |  2.44 mb   | default |
++-----------------------------+------------+---------+
+| InitialTenuringThreshold    |     7      | default |
++-----------------------------+------------+---------+
+| MaxHeapFreeRatio            |     70     | default |
++-----------------------------+------------+---------+
+| MaxHeapSize                 |  16.00 gb  |   set   |
++-----------------------------+------------+---------+
+| MaxTenuringThreshold        |     15     | default |
++-----------------------------+------------+---------+
+| MetaspaceSize               |  20.80 mb  | default |
++-----------------------------+------------+---------+
+| MinHeapFreeRatio            |     40     | default |
++-----------------------------+------------+---------+
+| NewRatio                    |     2      | default |
++-----------------------------+------------+---------+
+| ParallelRefProcEnabled      |   false    | default |
++-----------------------------+------------+---------+
+| ReservedCodeCacheSize       | 240.00 mb  | default |
++-----------------------------+------------+---------+
+| SoftRefLRUPolicyMSPerMB     |    1000    | default |
++-----------------------------+------------+---------+
+| SurvivorRatio               |     8      | default |
++-----------------------------+------------+---------+
+| TargetSurvivorRatio         |     50     | default |
++-----------------------------+------------+---------+
+| ThreadStackSize             | 1024.00 kb | default |
++-----------------------------+------------+---------+
+| UseBiasedLocking            |    true    | default |
++-----------------------------+------------+---------+
+| UseCompressedClassPointers  |    true    | default |
++-----------------------------+------------+---------+
+| UseCompressedOops           |    true    | default |
++-----------------------------+------------+---------+
+| UseLargePages               |   false    | default |
++-----------------------------+------------+---------+
+| UseNUMA                     |   false    | default |
++-----------------------------+------------+---------+
+| UseSerialGC                 |    true    |   set   |
++-----------------------------+------------+---------+
+| UseTransparentHugePages     |   false    | default |
++-----------------------------+------------+---------+
+"
+        );
+        assert_eq!(generate_flag_problems(&jvm_flags), "");
+        assert_eq!(generate_jvm_flags_table(&Default::default()), "");
+    }
+}
@@ -12,42 +12,300 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::human::human_bytes_base_1k;
+use std::fmt;
+
+use crate::human::human_bytes;
+
+use super::convert::parse_size_bytes;
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct MemoryStats {
     pub physical_memory_str: String,
     pub physical_memory_bytes: i64,
+    //free when the jvm started, the page cache is not counted as free
+    pub free_memory_bytes: Option<i64>,
+    pub swap_bytes: Option<i64>,
+    pub free_swap_bytes: Option<i64>,
+    pub page_size_bytes: Option<i64>,
+}
+
+impl fmt::Display for MemoryStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut lines = vec![self.physical_memory_str.clone()];
+        if let Some(free) = self.free_memory_bytes {
+            lines.push(format!("Free System RAM:     {}", human_bytes(free)));
+        }
+        if let Some(swap) = self.swap_bytes {
+            lines.push(format!(
+                "Swap:                {} ({} free)",
+                human_bytes(swap),
+                human_bytes(self.free_swap_bytes.unwrap_or_default())
+            ));
+        }
+        if let Some(page_size) = self.page_size_bytes {
+            lines.push(format!("Page Size:           {}", human_bytes(page_size)));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+// reads 128000000k(127996468k free) into the total and free bytes
+fn parse_total_and_free(value: &str) -> (Option<i64>, Option<i64>) {
+    match value.split_once('(') {
+        Some((total, free)) => (
+            parse_size_bytes(total),
+            free.trim_end_matches(')')
+                .trim()
+                .strip_suffix("free")
+                .and_then(parse_size_bytes),
+        ),
+        None => (parse_size_bytes(value), None),
+    }
 }
 
 pub fn parse_memory(line: String) -> MemoryStats {
     //Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)
-    let tokens: Vec<&str> = line.split(' ').collect();
-    let physical_raw: &str = tokens[4];
-    let tokens_for_ram: Vec<&str> = physical_raw.split('(').collect();
-    let total_ram_string = tokens_for_ram[0].trim_end_matches('k');
-    let total_ram_k = total_ram_string.parse::<i64>().unwrap();
-    let total_ram_bytes = total_ram_k * 1000;
-    let total_ram = human_bytes_base_1k(total_ram_bytes);
-    MemoryStats {
-        physical_memory_str: format!("Total System RAM:    {total_ram}"),
-        physical_memory_bytes: total_ram_bytes,
+    let mut stats = MemoryStats::default();
+    let fields = line.trim().trim_start_matches("Memory:");
+    for field in fields.split(',').map(|f| f.trim()) {
+        if let Some(page_size) = field.strip_suffix(" page") {
+            stats.page_size_bytes = parse_size_bytes(page_size);
+        } else if let Some(physical) = field.strip_prefix("physical ") {
+            let (total, free) = parse_total_and_free(physical);
+            stats.physical_memory_bytes = total.unwrap_or_default();
+            stats.free_memory_bytes = free;
+        } else if let Some(swap) = field.strip_prefix("swap ") {
+            (stats.swap_bytes, stats.free_swap_bytes) = parse_total_and_free(swap);
+        }
+    }
+    if stats.physical_memory_bytes > 0 {
+        stats.physical_memory_str = format!(
+            "Total System RAM:    {}",
+            human_bytes(stats.physical_memory_bytes)
+        );
     }
+    stats
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct JvmInfo {
+    //the line as it was logged
+    pub header: String,
+    pub vm_name: String,
+    pub hotspot_build: String,
+    pub java_version: String,
+    //8 for 1.8.0_332-b09, 11 for 11.0.15+10
+    pub major_version: u32,
+    //332 for 1.8.0_332-b09, 15 for 11.0.15+10
+    pub update: Option<u32>,
+    pub vendor: String,
+    pub os: String,
+    pub arch: String,
+    pub build_date: String,
+    //jdk 9+ writes the gc log with unified logging
+    pub unified_logging: bool,
+}
+
+impl JvmInfo {
+    // true when this is the given major version and older than the given update. Unknown
+    // updates are not considered older so advice is not given on a guess
+    pub fn is_older_than(&self, major_version: u32, update: u32) -> bool {
+        self.major_version == major_version && self.update.map(|u| u < update).unwrap_or(false)
+    }
+}
+
+// reads 1.8.0_332-b09, 11.0.15+10 and 17.0.2+8 into the major version and update
+pub fn parse_java_version(version: &str) -> (u32, Option<u32>) {
+    let version = version.split(['-', '+', ' ']).next().unwrap_or_default();
+    if let Some(rest) = version.strip_prefix("1.") {
+        //1.8.0_332
+        let major = rest.split('.').next().and_then(|m| m.parse().ok());
+        let update = rest.split_once('_').and_then(|(_, u)| u.parse().ok());
+        return (major.unwrap_or_default(), update);
+    }
+    let mut parts = version.split('.');
+    let major = parts
+        .next()
+        .and_then(|m| m.parse().ok())
+        .unwrap_or_default();
+    //the minor version is always 0, the security update is the third number
+    let update = parts.nth(1).and_then(|u| u.parse().ok());
+    (major, update)
+}
+
+fn get_vendor(line: &str) -> String {
+    let lower = line.to_lowercase();
+    let vendor = if lower.contains("zulu") {
+        "Zulu"
+    } else if lower.contains("temurin") {
+        "Temurin"
+    } else if lower.contains("adoptopenjdk") {
+        "AdoptOpenJDK"
+    } else if lower.contains("corretto") {
+        "Corretto"
+    } else if line.contains("by \"mockbuild\"") {
+        //the rpm build system of Red Hat, CentOS and Fedora
+        "Red Hat"
+    } else if line.starts_with("Java HotSpot(TM)") || line.contains("by \"java_re\"") {
+        "Oracle"
+    } else if line.starts_with("OpenJDK") {
+        "OpenJDK"
+    } else {
+        "Unknown"
+    };
+    vendor.to_string()
 }
 
-pub fn parse_jdk_stats(line: String) -> String {
+fn between<'a>(line: &'a str, start: &str, end: &str) -> &'a str {
+    line.split_once(start)
+        .and_then(|(_, rest)| rest.split_once(end))
+        .map(|(found, _)| found)
+        .unwrap_or_default()
+}
+
+pub fn parse_jvm_info(line: String) -> JvmInfo {
     //OpenJDK 64-Bit Server VM (25.332-b09) for linux-amd64 JRE (1.8.0_332-b09), built on Apr 20 2022 08:18:57 by "openjdk" with gcc 4.4.7 20120313 (Red Hat 4.4.7-23)
-    line
+    let vm_name = line.split(" (").next().unwrap_or_default().to_string();
+    let hotspot_build = between(&line, " (", ")").to_string();
+    let (os, arch) = between(&line, " for ", " ")
+        .split_once('-')
+        .unwrap_or_default();
+    let jre = between(&line, " JRE (", ", built on");
+    //zulu puts its own version in front, JRE (Zulu 8.70.0.23-CA-macos-aarch64) (1.8.0_372-b07)
+    let java_version = jre
+        .rsplit('(')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(')')
+        .to_string();
+    let (major_version, update) = parse_java_version(&java_version);
+    JvmInfo {
+        vm_name,
+        hotspot_build,
+        major_version,
+        update,
+        java_version,
+        vendor: get_vendor(&line),
+        os: os.to_string(),
+        arch: arch.to_string(),
+        build_date: between(&line, "built on ", " by ").to_string(),
+        header: line,
+        unified_logging: false,
+    }
+}
+
+// the jdk 9+ unified logs only have the version, "JDK 17.0.2+8 (release)"
+pub fn parse_unified_jvm_info(version: String) -> JvmInfo {
+    let java_version = version
+        .trim_start_matches("JDK ")
+        .split(' ')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let (major_version, update) = parse_java_version(&java_version);
+    JvmInfo {
+        java_version,
+        major_version,
+        update,
+        vendor: "Unknown".to_string(),
+        header: version,
+        unified_logging: true,
+        ..Default::default()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_memory;
+    use super::{
+        parse_java_version, parse_jvm_info, parse_memory, parse_unified_jvm_info, MemoryStats,
+    };
 
     #[test]
     fn test_parse_memory() {
         let line =
             "Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)".to_string();
         let result = parse_memory(line);
-        assert_eq!(result.physical_memory_str, "Total System RAM:    128.00 gb");
-        assert_eq!(result.physical_memory_bytes, 128000000 * 1000)
+        assert_eq!(result.physical_memory_str, "Total System RAM:    122.07 gb");
+        assert_eq!(result.physical_memory_bytes, 128000000 * 1024);
+        assert_eq!(result.free_memory_bytes, Some(127996468 * 1024));
+        assert_eq!(result.swap_bytes, Some(0));
+        assert_eq!(result.free_swap_bytes, Some(0));
+        assert_eq!(result.page_size_bytes, Some(4096));
+        assert_eq!(
+            result.to_string(),
+            "Total System RAM:    122.07 gb
+Free System RAM:     122.07 gb
+Swap:                0 bytes (0 bytes free)
+Page Size:           4.00 kb"
+        );
+
+        let result = parse_memory(
+            "Memory: 64k page, physical 16000000k(2000000k free), swap 8000000k(7000000k free)"
+                .to_string(),
+        );
+        assert_eq!(result.page_size_bytes, Some(64 * 1024));
+        assert_eq!(result.free_memory_bytes, Some(2000000 * 1024));
+        assert_eq!(result.swap_bytes, Some(8000000 * 1024));
+        assert_eq!(result.free_swap_bytes, Some(7000000 * 1024));
+    }
+
+    #[test]
+    fn test_parse_memory_unexpected_layout() {
+        let result = parse_memory("Memory: 4k page,  physical 8000000k".to_string());
+        assert_eq!(result.physical_memory_str, "Total System RAM:    7.63 gb");
+        assert_eq!(result.free_memory_bytes, None);
+        assert_eq!(result.swap_bytes, None);
+        let result = parse_memory("Memory: unknown".to_string());
+        assert_eq!(result, MemoryStats::default());
+        assert_eq!(result.to_string(), "");
+    }
+
+    #[test]
+    fn test_parse_jvm_info() {
+        let info = parse_jvm_info("OpenJDK 64-Bit Server VM (25.332-b09) for linux-amd64 JRE (1.8.0_332-b09), built on Apr 20 2022 08:18:57 by \"openjdk\" with gcc 4.4.7 20120313 (Red Hat 4.4.7-23)".to_string());
+        assert_eq!(info.vm_name, "OpenJDK 64-Bit Server VM");
+        assert_eq!(info.hotspot_build, "25.332-b09");
+        assert_eq!(info.java_version, "1.8.0_332-b09");
+        assert_eq!(info.major_version, 8);
+        assert_eq!(info.update, Some(332));
+        assert_eq!(info.vendor, "OpenJDK");
+        assert_eq!(info.os, "linux");
+        assert_eq!(info.arch, "amd64");
+        assert_eq!(info.build_date, "Apr 20 2022 08:18:57");
+        assert!(info.is_older_than(8, 341));
+        assert!(!info.is_older_than(8, 332));
+        assert!(!info.is_older_than(11, 341));
+
+        let zulu = parse_jvm_info("OpenJDK 64-Bit Server VM (25.372-b07) for bsd-aarch64 JRE (Zulu 8.70.0.23-CA-macos-aarch64) (1.8.0_372-b07), built on Apr 19 2023 20:47:05 by \"zulu_re\" with gcc 4.2.1 Compatible Apple LLVM 13.0.0 (clang-1300.0.29.30)".to_string());
+        assert_eq!(zulu.vendor, "Zulu");
+        assert_eq!(zulu.java_version, "1.8.0_372-b07");
+        assert_eq!(zulu.update, Some(372));
+        assert_eq!(zulu.os, "bsd");
+        assert_eq!(zulu.arch, "aarch64");
+
+        let oracle = parse_jvm_info("Java HotSpot(TM) 64-Bit Server VM (25.202-b08) for windows-amd64 JRE (1.8.0_202-b08), built on Dec 15 2018 19:54:30 by \"java_re\" with MS VC++ 10.0 (VS2010)".to_string());
+        assert_eq!(oracle.vendor, "Oracle");
+        assert_eq!(oracle.vm_name, "Java HotSpot(TM) 64-Bit Server VM");
+        assert_eq!(oracle.os, "windows");
+
+        let red_hat = parse_jvm_info("OpenJDK 64-Bit Server VM (25.312-b07) for linux-amd64 JRE (1.8.0_312-b07), built on Oct 18 2021 13:21:40 by \"mockbuild\" with gcc 4.8.5 20150623 (Red Hat 4.8.5-44)".to_string());
+        assert_eq!(red_hat.vendor, "Red Hat");
+
+        let temurin = parse_jvm_info("OpenJDK 64-Bit Server VM (25.345-b01) for linux-amd64 JRE (1.8.0_345-b01), built on Aug  3 2022 12:46:04 by \"temurin\" with gcc 7.5.0".to_string());
+        assert_eq!(temurin.vendor, "Temurin");
+        assert_eq!(temurin.build_date, "Aug  3 2022 12:46:04");
+    }
+
+    #[test]
+    fn test_parse_java_version() {
+        assert_eq!(parse_java_version("1.8.0_332-b09"), (8, Some(332)));
+        assert_eq!(parse_java_version("11.0.15+10"), (11, Some(15)));
+        assert_eq!(parse_java_version("17"), (17, None));
+        assert_eq!(parse_java_version(""), (0, None));
+        let unified = parse_unified_jvm_info("JDK 17.0.2+8".to_string());
+        assert_eq!(unified.java_version, "17.0.2+8");
+        assert_eq!(unified.major_version, 17);
+        assert_eq!(unified.update, Some(2));
+        assert_eq!(unified.header, "JDK 17.0.2+8");
     }
 }
@@ -0,0 +1,258 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// adds up what the JVM is allowed to commit and compares it with the memory of the host
+//
+// Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)
+// CommandLine flags: -XX:MaxDirectMemorySize=120259084288 -XX:MaxHeapSize=17179869184 -XX:+UseG1GC
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::{human_bytes, human_bytes_ref};
+
+use super::{
+    flags::GCFlags,
+    jvmflags::JvmFlags,
+    jvmstats::MemoryStats,
+    occupancy::{get_occupancy, Space},
+    pauses::GCPause,
+};
+
+// metaspace to assume when there is no -XX:MaxMetaspaceSize and the log has no metaspace sizes
+const ESTIMATED_METASPACE_BYTES: i64 = 256 * 1024 * 1024;
+// a busy dremio executor runs several hundred threads
+const ESTIMATED_THREADS: i64 = 500;
+
+pub struct MemoryArea {
+    pub name: &'static str,
+    pub bytes: i64,
+    //where the size came from, the flag, its default or an estimate
+    pub source: String,
+}
+
+pub struct MemoryBudget {
+    pub areas: Vec<MemoryArea>,
+    pub physical_bytes: i64,
+    pub swap_bytes: i64,
+}
+
+impl MemoryBudget {
+    pub fn total_bytes(&self) -> i64 {
+        self.areas.iter().map(|a| a.bytes).sum()
+    }
+
+    // metaspace, code cache and thread stacks
+    pub fn native_bytes(&self) -> i64 {
+        self.areas
+            .iter()
+            .filter(|a| a.name != "Heap" && a.name != "Direct Memory")
+            .map(|a| a.bytes)
+            .sum()
+    }
+}
+
+fn gb_to_bytes(gb: f32) -> i64 {
+    (gb as f64 * 1024.0 * 1024.0 * 1024.0) as i64
+}
+
+fn flag_source(explicit: bool, name: &str) -> String {
+    if explicit {
+        format!("-XX:{name}")
+    } else {
+        "default".to_string()
+    }
+}
+
+// none when the log has no flags or no physical memory to compare against
+pub fn build_memory_budget(
+    flags: &GCFlags,
+    jvm_flags: &JvmFlags,
+    memory_stats: &MemoryStats,
+    pauses: &[GCPause],
+) -> Option<MemoryBudget> {
+    if flags.all_flags.is_empty() || memory_stats.physical_memory_bytes <= 0 {
+        return None;
+    }
+    let mut areas = vec![
+        MemoryArea {
+            name: "Heap",
+            bytes: gb_to_bytes(flags.max_heap_size_gb),
+            source: flag_source(jvm_flags.is_explicit("MaxHeapSize"), "MaxHeapSize"),
+        },
+        MemoryArea {
+            name: "Direct Memory",
+            bytes: gb_to_bytes(flags.max_direct_memory_gb),
+            source: if jvm_flags.is_explicit("MaxDirectMemorySize") {
+                "-XX:MaxDirectMemorySize".to_string()
+            } else {
+                "default (max heap)".to_string()
+            },
+        },
+    ];
+    let observed_metaspace = pauses
+        .iter()
+        .filter_map(|p| get_occupancy(p, Space::Metaspace))
+        .map(|o| o.before_bytes.max(o.after_bytes))
+        .max();
+    areas.push(
+        match (jvm_flags.get_size("MaxMetaspaceSize"), observed_metaspace) {
+            (Some(bytes), _) => MemoryArea {
+                name: "Metaspace",
+                bytes,
+                source: "-XX:MaxMetaspaceSize".to_string(),
+            },
+            (None, Some(bytes)) => MemoryArea {
+                name: "Metaspace",
+                bytes,
+                source: "max used in log".to_string(),
+            },
+            (None, None) => MemoryArea {
+                name: "Metaspace",
+                bytes: ESTIMATED_METASPACE_BYTES,
+                source: "estimate".to_string(),
+            },
+        },
+    );
+    if let Some(code_cache) = jvm_flags.get_size("ReservedCodeCacheSize") {
+        areas.push(MemoryArea {
+            name: "Code Cache",
+            bytes: code_cache,
+            source: flag_source(
+                jvm_flags.is_explicit("ReservedCodeCacheSize"),
+                "ReservedCodeCacheSize",
+            ),
+        });
+    }
+    if let Some(stack) = jvm_flags.get_size("ThreadStackSize") {
+        areas.push(MemoryArea {
+            name: "Thread Stacks",
+            bytes: stack * ESTIMATED_THREADS,
+            source: format!("estimate, {ESTIMATED_THREADS} threads"),
+        });
+    }
+    Some(MemoryBudget {
+        areas,
+        physical_bytes: memory_stats.physical_memory_bytes,
+        swap_bytes: memory_stats.swap_bytes.unwrap_or_default(),
+    })
+}
+
+#[derive(Tabled)]
+struct MemoryAreaRow {
+    #[tabled(rename = "Area")]
+    name: &'static str,
+    #[tabled(display_with = "human_bytes_ref", rename = "Max Size")]
+    bytes: i64,
+    #[tabled(rename = "Source")]
+    source: String,
+}
+
+pub fn generate_memory_budget_table(budget: &MemoryBudget) -> String {
+    let rows: Vec<MemoryAreaRow> = budget
+        .areas
+        .iter()
+        .map(|a| MemoryAreaRow {
+            name: a.name,
+            bytes: a.bytes,
+            source: a.source.clone(),
+        })
+        .collect();
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+// the total against the ram and swap of the host
+pub fn generate_memory_budget_summary(budget: &MemoryBudget) -> String {
+    let total = budget.total_bytes();
+    [
+        format!("jvm max total:       {}", human_bytes(total)),
+        format!(
+            "system ram:          {}, the jvm can commit {:.2}% of it",
+            human_bytes(budget.physical_bytes),
+            total as f64 / budget.physical_bytes as f64 * 100.0
+        ),
+        format!("swap:                {}", human_bytes(budget.swap_bytes)),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::{
+        flags::{parse_gc_flags, GCFlags},
+        jvmflags::parse_jvm_flags,
+        jvmstats::parse_memory,
+        occupancy::{Space, SpaceOccupancy},
+        pauses::GCPause,
+    };
+
+    use super::{
+        build_memory_budget, generate_memory_budget_summary, generate_memory_budget_table,
+    };
+
+    #[test]
+    fn test_build_memory_budget() {
+        let memory = parse_memory(
+            "Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)".to_string(),
+        );
+        let flags = parse_gc_flags("CommandLine flags: -XX:MaxDirectMemorySize=120259084288 -XX:MaxHeapSize=17179869184 -XX:+UseG1GC".to_string(), memory.physical_memory_bytes);
+        let pauses = vec![GCPause {
+            occupancy: vec![SpaceOccupancy {
+                space: Space::Metaspace,
+                before_bytes: 113664000,
+                after_bytes: 113776640,
+                capacity_bytes: 1138384896,
+            }],
+            ..Default::default()
+        }];
+        let jvm_flags = parse_jvm_flags(&flags.all_flags, &flags.collector);
+        let budget = build_memory_budget(&flags, &jvm_flags, &memory, &pauses).unwrap();
+        assert_eq!(
+            generate_memory_budget_table(&budget),
+            "+---------------+-----------+-------------------------+
+| Area          | Max Size  |         Source          |
++---------------+-----------+-------------------------+
+| Heap          | 16.00 gb  |     -XX:MaxHeapSize     |
++---------------+-----------+-------------------------+
+| Direct Memory | 112.00 gb | -XX:MaxDirectMemorySize |
++---------------+-----------+-------------------------+
+| Metaspace     | 108.51 mb |     max used in log     |
++---------------+-----------+-------------------------+
+| Code Cache    | 240.00 mb |         default         |
++---------------+-----------+-------------------------+
+| Thread Stacks | 500.00 mb |  estimate, 500 threads  |
++---------------+-----------+-------------------------+
+"
+        );
+        assert_eq!(
+            generate_memory_budget_summary(&budget),
+            "jvm max total:       128.83 gb
+system ram:          122.07 gb, the jvm can commit 105.54% of it
+swap:                0 bytes"
+        );
+        let no_flags = GCFlags {
+            collector: crate::glog::flags::Collector::Unknown,
+            max_heap_size_gb: 0.0,
+            min_heap_size_gb: 0.0,
+            region_size_mb: 0.0,
+            target_pause_millis: 0,
+            max_direct_memory_gb: 0.0,
+            all_flags: vec![],
+        };
+        assert!(build_memory_budget(&no_flags, &Default::default(), &memory, &pauses).is_none());
+        assert!(build_memory_budget(&flags, &jvm_flags, &Default::default(), &pauses).is_none());
+    }
+}
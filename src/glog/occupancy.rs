@@ -0,0 +1,452 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::{human_bytes, human_bytes_ref};
+
+use super::{
+    convert::parse_size_bytes,
+    pauses::{generate_gc_name, GCPause},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Space {
+    Young,
+    Eden,
+    Survivors,
+    Old,
+    Heap,
+    Metaspace,
+}
+
+impl fmt::Display for Space {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SpaceOccupancy {
+    pub space: Space,
+    pub before_bytes: i64,
+    pub after_bytes: i64,
+    //capacity after the gc, 0 when the log does not include it
+    pub capacity_bytes: i64,
+}
+
+// parses 1234K->56K(2048K) into before, after and capacity, G1 also writes the capacity
+// before the gc as in 10.9G(10.9G)->0.0B(10.9G)
+pub fn parse_transition(space: Space, transition: &str) -> Option<SpaceOccupancy> {
+    let (before_raw, after_raw) = transition.trim().split_once("->")?;
+    let before = before_raw.split('(').next().unwrap_or_default();
+    let (after, capacity) = match after_raw.split_once('(') {
+        Some((after, capacity)) => (after, capacity.trim_end_matches(')')),
+        None => (after_raw, ""),
+    };
+    Some(SpaceOccupancy {
+        space,
+        before_bytes: parse_size_bytes(before)?,
+        after_bytes: parse_size_bytes(after)?,
+        capacity_bytes: parse_size_bytes(capacity).unwrap_or_default(),
+    })
+}
+
+// finds a labeled space such as [PSYoungGen: 1234K->56K(2048K)] and returns the occupancy
+// and the position in the text right after the transition
+pub fn find_space(text: &str, label: &str, space: Space) -> Option<(SpaceOccupancy, usize)> {
+    let marker = format!("[{label}");
+    let start = text.find(&marker)? + marker.len();
+    //skip things like (promotion failed) until we reach the colon
+    let colon = text[start..].find(':')? + start + 1;
+    let rest = &text[colon..];
+    let transition = rest.trim_start().split([' ', ',', ']']).next()?;
+    let end = colon + (rest.len() - rest.trim_start().len()) + transition.len();
+    parse_transition(space, transition).map(|occupancy| (occupancy, end))
+}
+
+// finds the first transition such as 5000K->3000K(8000K) starting at position and returns
+// the occupancy and the position in the text right after the transition
+pub fn find_transition_after(
+    text: &str,
+    position: usize,
+    space: Space,
+) -> Option<(SpaceOccupancy, usize)> {
+    let rest = text.get(position..)?;
+    let mut offset = position;
+    for token in rest.split([' ', ',', ']', '[']) {
+        offset += token.len() + 1;
+        if token.contains("->") {
+            if let Some(occupancy) = parse_transition(space, token) {
+                return Some((occupancy, offset - 1));
+            }
+        }
+    }
+    None
+}
+
+// finds a transition that follows a label without brackets such as Survivors: 192.0M->224.0M
+pub fn find_labeled_transition(text: &str, label: &str, space: Space) -> Option<SpaceOccupancy> {
+    let start = text.find(label)? + label.len();
+    find_transition_after(text, start, space).map(|(occupancy, _)| occupancy)
+}
+
+pub fn get_occupancy(pause: &GCPause, space: Space) -> Option<&SpaceOccupancy> {
+    pause.occupancy.iter().find(|o| o.space == space)
+}
+
+struct OccupancySummary {
+    gc_name: String,
+    space: Space,
+    count: i64,
+    total_before_bytes: i64,
+    total_after_bytes: i64,
+    max_capacity_bytes: i64,
+}
+
+#[derive(Tabled)]
+struct OccupancySummaryRow {
+    #[tabled(rename = "GC")]
+    gc_name: String,
+    #[tabled(rename = "Space")]
+    space: Space,
+    #[tabled(rename = "Count")]
+    count: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Avg Before")]
+    avg_before_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Avg After")]
+    avg_after_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Avg Reclaimed")]
+    avg_reclaimed_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Total Reclaimed")]
+    total_reclaimed_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Max Capacity")]
+    max_capacity_bytes: i64,
+}
+
+// summarizes how full each space was before and after every type of gc
+pub fn generate_occupancy_table(pauses: &Vec<GCPause>) -> String {
+    let mut summaries: HashMap<(String, Space), OccupancySummary> = HashMap::new();
+    for pause in pauses {
+        let gc_name = generate_gc_name(pause);
+        for occupancy in &pause.occupancy {
+            summaries
+                .entry((gc_name.to_string(), occupancy.space))
+                .and_modify(|summary| {
+                    summary.count += 1;
+                    summary.total_before_bytes += occupancy.before_bytes;
+                    summary.total_after_bytes += occupancy.after_bytes;
+                    summary.max_capacity_bytes =
+                        summary.max_capacity_bytes.max(occupancy.capacity_bytes);
+                })
+                .or_insert(OccupancySummary {
+                    gc_name: gc_name.to_string(),
+                    space: occupancy.space,
+                    count: 1,
+                    total_before_bytes: occupancy.before_bytes,
+                    total_after_bytes: occupancy.after_bytes,
+                    max_capacity_bytes: occupancy.capacity_bytes,
+                });
+        }
+    }
+    if summaries.is_empty() {
+        return "".to_string();
+    }
+    let mut rows: Vec<OccupancySummaryRow> = summaries
+        .values()
+        .map(|s| OccupancySummaryRow {
+            gc_name: s.gc_name.to_string(),
+            space: s.space,
+            count: s.count,
+            avg_before_bytes: s.total_before_bytes / s.count,
+            avg_after_bytes: s.total_after_bytes / s.count,
+            avg_reclaimed_bytes: (s.total_before_bytes - s.total_after_bytes) / s.count,
+            total_reclaimed_bytes: s.total_before_bytes - s.total_after_bytes,
+            max_capacity_bytes: s.max_capacity_bytes,
+        })
+        .collect();
+    rows.sort_by_key(|r| (r.gc_name.to_string(), r.space));
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[derive(Tabled)]
+struct HeapAfterGCRow {
+    #[tabled(rename = "GCs")]
+    name: String,
+    #[tabled(rename = "Count")]
+    count: usize,
+    #[tabled(display_with = "human_bytes_ref", rename = "Min After")]
+    min_after_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Avg After")]
+    avg_after_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Max After")]
+    max_after_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Last After")]
+    last_after_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Max Capacity")]
+    max_capacity_bytes: i64,
+}
+
+fn heap_after_gc_row(name: &str, heaps: &[&SpaceOccupancy]) -> Option<HeapAfterGCRow> {
+    let last = heaps.last()?;
+    let total: i64 = heaps.iter().map(|h| h.after_bytes).sum();
+    Some(HeapAfterGCRow {
+        name: name.to_string(),
+        count: heaps.len(),
+        min_after_bytes: heaps
+            .iter()
+            .map(|h| h.after_bytes)
+            .min()
+            .unwrap_or_default(),
+        avg_after_bytes: total / heaps.len() as i64,
+        max_after_bytes: heaps
+            .iter()
+            .map(|h| h.after_bytes)
+            .max()
+            .unwrap_or_default(),
+        last_after_bytes: last.after_bytes,
+        max_capacity_bytes: heaps
+            .iter()
+            .map(|h| h.capacity_bytes)
+            .max()
+            .unwrap_or_default(),
+    })
+}
+
+// how much of the heap is still in use once a gc is done, after a full gc this is close to the
+// live data set of the application
+pub fn generate_heap_after_gc_table(pauses: &[GCPause]) -> String {
+    let all: Vec<&SpaceOccupancy> = pauses
+        .iter()
+        .filter_map(|p| get_occupancy(p, Space::Heap))
+        .collect();
+    let full: Vec<&SpaceOccupancy> = pauses
+        .iter()
+        .filter(|p| p.is_full_gc)
+        .filter_map(|p| get_occupancy(p, Space::Heap))
+        .collect();
+    let rows: Vec<HeapAfterGCRow> = [
+        heap_after_gc_row("All GCs", &all),
+        heap_after_gc_row("Full GCs", &full),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[derive(Tabled)]
+struct MetaspaceGrowthRow {
+    #[tabled(display_with = "human_bytes_ref", rename = "First After")]
+    first_after_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Last After")]
+    last_after_bytes: i64,
+    #[tabled(display_with = "signed_bytes_ref", rename = "Growth")]
+    growth_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Max After")]
+    max_after_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Max Capacity")]
+    max_capacity_bytes: i64,
+}
+
+fn signed_bytes_ref(bytes: &i64) -> String {
+    if *bytes < 0 {
+        return human_bytes_ref(bytes);
+    }
+    format!("+{}", human_bytes(*bytes))
+}
+
+// steady metaspace growth across gcs points to a class loader leak
+pub fn generate_metaspace_growth_table(pauses: &[GCPause]) -> String {
+    let metaspaces: Vec<&SpaceOccupancy> = pauses
+        .iter()
+        .filter_map(|p| get_occupancy(p, Space::Metaspace))
+        .collect();
+    let (first, last) = match (metaspaces.first(), metaspaces.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return "".to_string(),
+    };
+    let row = MetaspaceGrowthRow {
+        first_after_bytes: first.after_bytes,
+        last_after_bytes: last.after_bytes,
+        growth_bytes: last.after_bytes - first.after_bytes,
+        max_after_bytes: metaspaces
+            .iter()
+            .map(|m| m.after_bytes)
+            .max()
+            .unwrap_or_default(),
+        max_capacity_bytes: metaspaces
+            .iter()
+            .map(|m| m.capacity_bytes)
+            .max()
+            .unwrap_or_default(),
+    };
+    Table::new(vec![row]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::pauses::GCPause;
+
+    use super::{
+        find_labeled_transition, find_space, find_transition_after, generate_heap_after_gc_table,
+        generate_metaspace_growth_table, generate_occupancy_table, parse_transition, Space,
+        SpaceOccupancy,
+    };
+
+    #[test]
+    fn test_parse_transition() {
+        let occupancy = parse_transition(Space::Young, "1234K->56K(2048K)").unwrap();
+        assert_eq!(occupancy.before_bytes, 1234 * 1024);
+        assert_eq!(occupancy.after_bytes, 56 * 1024);
+        assert_eq!(occupancy.capacity_bytes, 2048 * 1024);
+        let no_capacity = parse_transition(Space::Young, "192.0M->224.0M").unwrap();
+        assert_eq!(no_capacity.after_bytes, 224 * 1024 * 1024);
+        assert_eq!(no_capacity.capacity_bytes, 0);
+        assert!(parse_transition(Space::Young, "garbage").is_none());
+    }
+
+    #[test]
+    fn test_find_space() {
+        let line = "[GC (Allocation Failure) [PSYoungGen: 1234K->56K(2048K)] 5000K->3000K(8000K), 0.0123 secs]";
+        let (young, end) = find_space(line, "PSYoungGen", Space::Young).unwrap();
+        assert_eq!(young.before_bytes, 1234 * 1024);
+        let (heap, _) = find_transition_after(line, end, Space::Heap).unwrap();
+        assert_eq!(heap.before_bytes, 5000 * 1024);
+        assert_eq!(heap.capacity_bytes, 8000 * 1024);
+        assert!(find_space(line, "ParOldGen", Space::Old).is_none());
+    }
+
+    #[test]
+    fn test_find_space_with_inner_time() {
+        let line = "[GC (Allocation Failure) 0.5: [DefNew (promotion failed) : 4416K->512K(4928K), 0.0035 secs] 4416K->1500K(15872K), 0.0036 secs]";
+        let (young, end) = find_space(line, "DefNew", Space::Young).unwrap();
+        assert_eq!(young.after_bytes, 512 * 1024);
+        let (heap, _) = find_transition_after(line, end, Space::Heap).unwrap();
+        assert_eq!(heap.after_bytes, 1500 * 1024);
+    }
+
+    #[test]
+    fn test_generate_occupancy_table() {
+        let pauses = vec![
+            GCPause {
+                gc_type: "Allocation Failure".to_string(),
+                occupancy: vec![SpaceOccupancy {
+                    space: Space::Young,
+                    before_bytes: 2048 * 1024,
+                    after_bytes: 1024 * 1024,
+                    capacity_bytes: 4096 * 1024,
+                }],
+                ..Default::default()
+            },
+            GCPause {
+                gc_type: "Allocation Failure".to_string(),
+                occupancy: vec![SpaceOccupancy {
+                    space: Space::Young,
+                    before_bytes: 4096 * 1024,
+                    after_bytes: 1024 * 1024,
+                    capacity_bytes: 4096 * 1024,
+                }],
+                ..Default::default()
+            },
+        ];
+        let output = generate_occupancy_table(&pauses);
+        assert_eq!(output, "+--------------------+-------+-------+------------+------------+---------------+-----------------+--------------+
+| GC                 | Space | Count | Avg Before | Avg After  | Avg Reclaimed | Total Reclaimed | Max Capacity |
++--------------------+-------+-------+------------+------------+---------------+-----------------+--------------+
+| Allocation Failure | Young |   2   |  3.00 mb   | 1024.00 kb |    2.00 mb    |     4.00 mb     |   4.00 mb    |
++--------------------+-------+-------+------------+------------+---------------+-----------------+--------------+
+");
+    }
+
+    #[test]
+    fn test_generate_occupancy_table_empty() {
+        assert_eq!(generate_occupancy_table(&vec![]), "");
+    }
+
+    #[test]
+    fn test_parse_transition_with_capacity_before() {
+        let eden = parse_transition(Space::Eden, "0.0B(724.0M)->0.0B(6114.0M)").unwrap();
+        assert_eq!(eden.before_bytes, 0);
+        assert_eq!(eden.capacity_bytes, 6114 * 1024 * 1024);
+        let survivors = find_labeled_transition(
+            "Survivors: 192.0M->224.0M Heap:",
+            "Survivors: ",
+            Space::Survivors,
+        )
+        .unwrap();
+        assert_eq!(survivors.before_bytes, 192 * 1024 * 1024);
+    }
+
+    fn pause_with(is_full_gc: bool, space: Space, before: i64, after: i64) -> GCPause {
+        GCPause {
+            is_full_gc,
+            occupancy: vec![SpaceOccupancy {
+                space,
+                before_bytes: before * 1024 * 1024,
+                after_bytes: after * 1024 * 1024,
+                capacity_bytes: 4096 * 1024 * 1024,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_heap_after_gc_table() {
+        let pauses = vec![
+            pause_with(false, Space::Heap, 3000, 1000),
+            pause_with(true, Space::Heap, 4000, 2000),
+            pause_with(false, Space::Heap, 3500, 3000),
+        ];
+        let output = generate_heap_after_gc_table(&pauses);
+        assert_eq!(
+            output,
+            "+----------+-------+------------+-----------+-----------+------------+--------------+
+| GCs      | Count | Min After  | Avg After | Max After | Last After | Max Capacity |
++----------+-------+------------+-----------+-----------+------------+--------------+
+| All GCs  |   3   | 1000.00 mb |  1.95 gb  |  2.93 gb  |  2.93 gb   |   4.00 gb    |
++----------+-------+------------+-----------+-----------+------------+--------------+
+| Full GCs |   1   |  1.95 gb   |  1.95 gb  |  1.95 gb  |  1.95 gb   |   4.00 gb    |
++----------+-------+------------+-----------+-----------+------------+--------------+
+"
+        );
+        assert_eq!(generate_heap_after_gc_table(&[]), "");
+    }
+
+    #[test]
+    fn test_generate_metaspace_growth_table() {
+        let pauses = vec![
+            pause_with(false, Space::Metaspace, 100, 100),
+            pause_with(false, Space::Heap, 3000, 1000),
+            pause_with(true, Space::Metaspace, 300, 250),
+        ];
+        let output = generate_metaspace_growth_table(&pauses);
+        assert!(
+            output.contains("|  100.00 mb  | 250.00 mb  | +150.00 mb | 250.00 mb |"),
+            "was {output}"
+        );
+        assert_eq!(generate_metaspace_growth_table(&[]), "");
+    }
+}
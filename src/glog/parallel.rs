@@ -0,0 +1,103 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+
+use super::{
+    occupancy::{find_space, find_transition_after, Space, SpaceOccupancy},
+    pauses::{parse_gc_pause, GCPause},
+};
+
+pub fn is_parallel_pause(line: &str) -> bool {
+    line.contains("[PSYoungGen")
+}
+
+pub fn parse_parallel_occupancy(text: &str) -> Vec<SpaceOccupancy> {
+    //2022-01-02T11:11:01.111+0000: 1.234: [GC (Allocation Failure) [PSYoungGen: 1234K->56K(2048K)] 5000K->3000K(8000K), 0.0123 secs]
+    //2022-01-02T11:11:01.111+0000: 2.345: [Full GC (Ergonomics) [PSYoungGen: 1K->0K(2K)] [ParOldGen: 100K->90K(200K)] 101K->90K(202K), [Metaspace: 3000K->3000K(1056768K)], 0.5 secs]
+    let mut occupancy = vec![];
+    let mut last_generation_end = None;
+    if let Some((young, end)) = find_space(text, "PSYoungGen", Space::Young) {
+        occupancy.push(young);
+        last_generation_end = Some(end);
+    }
+    //jdk 8 only writes ParOldGen, older jvms without -XX:+UseParallelOldGC write PSOldGen
+    let old = find_space(text, "ParOldGen", Space::Old)
+        .or_else(|| find_space(text, "PSOldGen", Space::Old));
+    if let Some((old, end)) = old {
+        occupancy.push(old);
+        last_generation_end = Some(end);
+    }
+    if let Some(end) = last_generation_end {
+        if let Some((heap, _)) = find_transition_after(text, end, Space::Heap) {
+            occupancy.push(heap);
+        }
+    }
+    if let Some((metaspace, _)) = find_space(text, "Metaspace", Space::Metaspace) {
+        occupancy.push(metaspace);
+    }
+    occupancy
+}
+
+pub fn parse_parallel_pause(line: String) -> Result<GCPause, Box<dyn Error>> {
+    let occupancy = parse_parallel_occupancy(&line);
+    let mut pause = parse_gc_pause(line)?;
+    pause.occupancy = occupancy;
+    Ok(pause)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::occupancy::{get_occupancy, Space};
+
+    use super::{is_parallel_pause, parse_parallel_pause};
+
+    #[test]
+    fn test_parse_parallel_young_pause() {
+        let line = "2022-01-02T11:11:01.111+0000: 1.234: [GC (Allocation Failure) [PSYoungGen: 1234K->56K(2048K)] 5000K->3000K(8000K), 0.0123 secs] [Times: user=0.03 sys=0.00, real=0.01 secs]";
+        assert!(is_parallel_pause(line));
+        let pause = parse_parallel_pause(line.to_string()).unwrap();
+        assert_eq!(pause.gc_type, "Allocation Failure");
+        assert!(!pause.is_full_gc);
+        assert_eq!(pause.pause_time_seconds, 0.0123);
+        let young = get_occupancy(&pause, Space::Young).unwrap();
+        assert_eq!(young.before_bytes, 1234 * 1024);
+        assert_eq!(young.after_bytes, 56 * 1024);
+        assert_eq!(young.capacity_bytes, 2048 * 1024);
+        let heap = get_occupancy(&pause, Space::Heap).unwrap();
+        assert_eq!(heap.before_bytes, 5000 * 1024);
+        assert_eq!(heap.after_bytes, 3000 * 1024);
+        assert!(get_occupancy(&pause, Space::Old).is_none());
+    }
+
+    #[test]
+    fn test_parse_parallel_full_pause() {
+        let line = "2022-01-02T11:11:01.111+0000: 2.345: [Full GC (Ergonomics) [PSYoungGen: 1024K->0K(2048K)] [ParOldGen: 100000K->90000K(200000K)] 101024K->90000K(202048K), [Metaspace: 3000K->2900K(1056768K)], 0.5012345 secs] [Times: user=1.03 sys=0.00, real=0.50 secs]";
+        let pause = parse_parallel_pause(line.to_string()).unwrap();
+        assert!(pause.is_full_gc);
+        assert_eq!(pause.gc_type, "Full GC");
+        assert_eq!(pause.attributes, vec!["Ergonomics"]);
+        assert_eq!(pause.pause_time_seconds, 0.5012345);
+        let old = get_occupancy(&pause, Space::Old).unwrap();
+        assert_eq!(old.before_bytes, 100000 * 1024);
+        assert_eq!(old.after_bytes, 90000 * 1024);
+        assert_eq!(old.capacity_bytes, 200000 * 1024);
+        let heap = get_occupancy(&pause, Space::Heap).unwrap();
+        assert_eq!(heap.after_bytes, 90000 * 1024);
+        assert_eq!(heap.capacity_bytes, 202048 * 1024);
+        let metaspace = get_occupancy(&pause, Space::Metaspace).unwrap();
+        assert_eq!(metaspace.before_bytes, 3000 * 1024);
+        assert_eq!(metaspace.after_bytes, 2900 * 1024);
+    }
+}
@@ -18,20 +18,48 @@ use std::{collections::HashMap, error::Error};
 use histogram::Histogram;
 use tabled::object::Columns;
 use tabled::{Alignment, Modify, Table, Tabled};
-use time::{format_description, OffsetDateTime};
+use time::{
+    format_description::{self, well_known::Rfc3339},
+    OffsetDateTime, PrimitiveDateTime, UtcOffset,
+};
 
-use crate::human::{human_duration, human_time};
+use crate::human::{format_float, human_duration, human_time};
 
+use super::{
+    classhistogram::ClassHistogram, ergonomics::ErgonomicsEvent, g1phases::G1Phase,
+    occupancy::SpaceOccupancy, references::ReferenceStats, tenuring::TenuringDistribution,
+    times::CpuTimes,
+};
+
+#[derive(Default)]
 pub struct GCPause {
     pub is_full_gc: bool,
     pub attributes: Vec<String>,
     pub gc_type: String,
     pub pause_time_seconds: f64,
-    pub time_epoch: i64,
+    pub time_epoch_micros: i64,
+    //seconds since the jvm started from -XX:+PrintGCTimeStamps or the uptime decorator
+    pub uptime_seconds: Option<f64>,
     pub heap_sizing: HeapSizing,
+    pub occupancy: Vec<SpaceOccupancy>,
+    pub cpu_times: Option<CpuTimes>,
+    //how many threads the collector used for this pause when the log says
+    pub gc_workers: Option<u32>,
+    //the phase tree G1 writes with -XX:+PrintGCDetails
+    pub g1_phases: Vec<G1Phase>,
+    //the reference processing times from -XX:+PrintReferenceGC
+    pub references: Vec<ReferenceStats>,
+    //the G1Ergonomics decisions from -XX:+PrintAdaptiveSizePolicy logged during or after the pause
+    pub ergonomics: Vec<ErgonomicsEvent>,
+    //the class histograms from -XX:+PrintClassHistogramBeforeFullGC and AfterFullGC
+    pub histogram_before: Option<ClassHistogram>,
+    pub histogram_after: Option<ClassHistogram>,
+    //the survivor ages from -XX:+PrintTenuringDistribution
+    pub tenuring: Option<TenuringDistribution>,
 }
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Default)]
 pub enum HeapSizing {
+    #[default]
     None,
     Expansion,
     Shrinking,
@@ -105,14 +133,10 @@ pub fn parse_full_gc_pause(multiline: String) -> Result<GCPause, Box<dyn Error>>
         }
     }
 
-    Ok(GCPause {
-        attributes: pause.attributes,
-        gc_type: pause.gc_type,
-        pause_time_seconds: seconds,
-        time_epoch: pause.time_epoch,
-        is_full_gc: multiline.contains("Full GC"),
-        heap_sizing,
-    })
+    pause.pause_time_seconds = seconds;
+    pause.is_full_gc = multiline.contains("Full GC");
+    pause.heap_sizing = heap_sizing;
+    Ok(pause)
 }
 
 #[derive(Debug)]
@@ -148,19 +172,134 @@ impl fmt::Display for ParseError {
         )
     }
 }
-fn get_epoch(datetime_line: String) -> Result<i64, ParseError> {
+// microseconds since the unix epoch, the log only has milliseconds but unified logging
+// decorators and application logs may be finer grained
+fn epoch_micros(date: OffsetDateTime) -> i64 {
+    (date.unix_timestamp_nanos() / 1000) as i64
+}
+
+pub fn get_epoch_micros(datetime_line: String) -> Result<i64, ParseError> {
     let format = format_description::parse(
         "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3][offset_hour sign:mandatory][offset_minute]",
     ).unwrap();
     let time_raw = OffsetDateTime::parse(&datetime_line, &format);
     match time_raw {
-        Ok(date) => Ok(date.unix_timestamp()),
+        Ok(date) => Ok(epoch_micros(date)),
         Err(_) => Err(ParseError {
             datetime_str: datetime_line,
         }),
     }
 }
 
+// the zone of the date stamp at the start of a jdk 8 log line
+// 2022-08-24T01:54:38.603+0530: 190268.356: [Full GC (Allocation Failure) ...
+pub fn get_utc_offset(line: &str) -> Option<UtcOffset> {
+    let (datetime, _) = line.split_once(": ")?;
+    //skip the lines without a date stamp before parsing
+    if datetime.len() != "2022-08-24T01:54:38.603+0530".len() {
+        return None;
+    }
+    let format = format_description::parse(
+        "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3][offset_hour sign:mandatory][offset_minute]",
+    ).ok()?;
+    OffsetDateTime::parse(datetime, &format)
+        .ok()
+        .map(|date| date.offset())
+}
+
+// reads a time given on the command line, either in the same format as the gc log date stamps,
+// RFC 3339 or as 2022-07-22 18:41:06 in UTC
+pub fn parse_datetime_epoch_micros(datetime: &str) -> Result<i64, ParseError> {
+    if let Ok(epoch) = get_epoch_micros(datetime.to_string()) {
+        return Ok(epoch);
+    }
+    if let Ok(date) = OffsetDateTime::parse(datetime, &Rfc3339) {
+        return Ok(epoch_micros(date));
+    }
+    let format =
+        format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second]").unwrap();
+    match PrimitiveDateTime::parse(datetime, &format) {
+        Ok(date) => Ok(epoch_micros(date.assume_utc())),
+        Err(_) => Err(ParseError {
+            datetime_str: datetime.to_string(),
+        }),
+    }
+}
+
+// reads the zone report times are shown in, either UTC or a fixed offset such as +05:30,
+// +5:30, +0530 or -08
+pub fn parse_utc_offset(tz: &str) -> Result<UtcOffset, ParseError> {
+    let tz = tz.trim();
+    if tz.eq_ignore_ascii_case("utc") || tz.eq_ignore_ascii_case("z") {
+        return Ok(UtcOffset::UTC);
+    }
+    let error = || ParseError {
+        datetime_str: tz.to_string(),
+    };
+    //the offsets are all ascii, which also keeps the byte slicing below on char boundaries
+    if !tz.is_ascii() {
+        return Err(error());
+    }
+    let (sign, digits) = match tz.chars().next() {
+        Some('+') => (1, &tz[1..]),
+        Some('-') => (-1, &tz[1..]),
+        _ => return Err(error()),
+    };
+    //the hours can be one or two digits, the minutes are always two
+    let (hours, minutes) = match digits.split_once(':') {
+        Some(split) => split,
+        None if digits.len() > 2 => digits.split_at(digits.len() - 2),
+        None => (digits, "00"),
+    };
+    if !(1..=2).contains(&hours.len())
+        || minutes.len() != 2
+        || !hours
+            .chars()
+            .chain(minutes.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(error());
+    }
+    let hours = i8::from_str(hours).map_err(|_| error())?;
+    let minutes = i8::from_str(minutes).map_err(|_| error())?;
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).map_err(|_| error())
+}
+
+// reads the jvm uptime that comes right before the first [ of a jdk 8 log line
+// 2022-08-24T01:54:38.603+0000: 190268.356: [Full GC (Allocation Failure) ...
+// 19999.636: [Full GC (Ergonomics) ...
+pub fn get_uptime_seconds(line: &str) -> Option<f64> {
+    let prefix = &line[..line.find('[')?];
+    prefix
+        .split(": ")
+        .filter_map(|token| f64::from_str(token.trim().trim_end_matches(':')).ok())
+        .last()
+}
+
+// finds the pause time of the outermost [..., 0.0123 secs] in a possibly multiline log entry
+// skipping the nested timings of the individual phases
+pub fn get_outer_pause_seconds(text: &str) -> Option<f64> {
+    let mut depth = 0;
+    let mut segment_start = 0;
+    for (i, c) in text.char_indices() {
+        if c == '[' {
+            if depth == 0 {
+                segment_start = i + 1;
+            }
+            depth += 1;
+        } else if c == ']' && depth > 0 {
+            depth -= 1;
+            if depth == 0 {
+                if let Some(body) = text[segment_start..i].strip_suffix(" secs") {
+                    let (_, seconds) = body.rsplit_once(", ")?;
+                    return f64::from_str(seconds.trim()).ok();
+                }
+            }
+        }
+    }
+    None
+}
+
 pub fn get_gc_resizing(line: &str) -> HeapSizing {
     if line.contains("calculated expansion amount: 0 bytes")
         || line.contains("(Heap Sizing) did not expand the heap")
@@ -193,13 +332,15 @@ pub fn parse_gc_pause(line: String) -> Result<GCPause, Box<dyn Error>> {
     let (head, tail) = tokens.split_at(1);
     let datetime_line = head[0];
     //for now silently ignore this as we are not making good use of logs in any case.
-    let time_epoch = get_epoch(datetime_line.to_string()).unwrap_or_default();
+    let time_epoch_micros = get_epoch_micros(datetime_line.to_string()).unwrap_or_default();
+    let uptime_seconds = get_uptime_seconds(&line);
     let gc_pause = tail.join(": ");
     let mut is_full_gc = false;
     let mut gc_type_str = "".to_string();
     let mut start_looking_for_full_gc = false;
 
-    let mut attribute_start = false;
+    //causes like (System.gc()) have parentheses of their own
+    let mut attribute_depth = 0;
     let mut attribute: String = "".to_string();
     let mut seconds_start: bool = false;
     let mut seconds_str: String = "".to_string();
@@ -241,11 +382,18 @@ pub fn parse_gc_pause(line: String) -> Result<GCPause, Box<dyn Error>> {
         }
 
         if c == '(' {
-            attribute_start = true;
+            attribute_depth += 1;
+            if attribute_depth > 1 {
+                attribute.push(c);
+            }
             continue;
         }
-        if c == ')' {
-            attribute_start = false;
+        if c == ')' && attribute_depth > 0 {
+            attribute_depth -= 1;
+            if attribute_depth > 0 {
+                attribute.push(c);
+                continue;
+            }
             //skip attributes with numbers in them
             if !attribute.chars().any(char::is_numeric) || attribute.contains("G1") {
                 attributes.push(attribute);
@@ -253,7 +401,7 @@ pub fn parse_gc_pause(line: String) -> Result<GCPause, Box<dyn Error>> {
             attribute = "".to_string();
             continue;
         }
-        if attribute_start {
+        if attribute_depth > 0 {
             attribute.push(c);
             continue;
         }
@@ -299,8 +447,10 @@ pub fn parse_gc_pause(line: String) -> Result<GCPause, Box<dyn Error>> {
         attributes,
         gc_type,
         pause_time_seconds,
-        time_epoch,
+        time_epoch_micros,
+        uptime_seconds,
         heap_sizing,
+        ..Default::default()
     })
 }
 
@@ -331,10 +481,6 @@ pub struct GCSummaryRow {
     pub longest_pause_seconds: f64,
 }
 
-fn format_float(float: &f64) -> String {
-    format!("{float:.2}")
-}
-
 pub fn generate_gc_name(pause: &GCPause) -> String {
     let mut attrs = vec![];
     for attr in &pause.attributes {
@@ -346,7 +492,17 @@ pub fn generate_gc_name(pause: &GCPause) -> String {
     }
     format!("{} - {}", pause.gc_type, attrs.join(""))
 }
-pub fn show_max_pause_times(pauses: &Vec<GCPause>) -> String {
+// logs with only -XX:+PrintGCTimeStamps and no way to anchor them still have the uptime
+fn format_pause_time(pause: &GCPause, offset: UtcOffset) -> String {
+    match pause.uptime_seconds {
+        Some(uptime) if pause.time_epoch_micros == 0 => {
+            format!("{uptime:.3} seconds after jvm start")
+        }
+        _ => human_time(pause.time_epoch_micros, offset),
+    }
+}
+
+pub fn show_max_pause_times(pauses: &Vec<GCPause>, offset: UtcOffset) -> String {
     let mut max_pause_time: f64 = 0.0;
     let mut max_pause = None;
     for pause in pauses {
@@ -355,15 +511,14 @@ pub fn show_max_pause_times(pauses: &Vec<GCPause>) -> String {
             max_pause = Some(pause);
         };
     }
-    if max_pause.is_none() {
-        "No Pauses".to_string()
-    } else {
-        format!(
+    match max_pause {
+        None => "No Pauses".to_string(),
+        Some(max_pause) => format!(
             "Timestamp: {}\nPause Time {}\nPause Type {}",
-            human_time(max_pause.unwrap().time_epoch * 1000),
-            human_duration((max_pause.unwrap().pause_time_seconds * 1000.0) as i64),
-            max_pause.unwrap().gc_type
-        )
+            format_pause_time(max_pause, offset),
+            human_duration((max_pause.pause_time_seconds * 1000.0) as i64),
+            max_pause.gc_type
+        ),
     }
 }
 
@@ -426,8 +581,12 @@ mod tests {
         tests::approx_equal_f64,
     };
     use std::vec;
+    use time::UtcOffset;
 
-    use super::{generate_gc_name, generate_pause_table};
+    use super::{
+        generate_gc_name, generate_pause_table, get_epoch_micros, get_uptime_seconds,
+        parse_datetime_epoch_micros, parse_utc_offset, show_max_pause_times,
+    };
 
     #[test]
     fn test_generate_gc_name() {
@@ -436,8 +595,9 @@ mod tests {
             attributes: vec!["to-space exhausted".to_string(), "young".to_string()],
             gc_type: "G1 Humongous Allocation".to_string(),
             pause_time_seconds: 1.2,
-            time_epoch: 1658405469,
+            time_epoch_micros: 1658405469000000,
             heap_sizing: HeapSizing::None,
+            ..Default::default()
         });
         assert_eq!(
             name,
@@ -452,32 +612,36 @@ mod tests {
             attributes: vec!["to-space exhausted".to_string(), "young".to_string()],
             gc_type: "G1 Humongous Allocation".to_string(),
             pause_time_seconds: 1.25,
-            time_epoch: 1658405158,
+            time_epoch_micros: 1658405158000000,
             heap_sizing: HeapSizing::None,
+            ..Default::default()
         };
         let pause2 = GCPause {
             is_full_gc: false,
             attributes: vec!["young".to_string()],
             gc_type: "G1 Evacuation Pause".to_string(),
             pause_time_seconds: 2.75,
-            time_epoch: 1658405169,
+            time_epoch_micros: 1658405169000000,
             heap_sizing: HeapSizing::None,
+            ..Default::default()
         };
         let pause3 = GCPause {
             is_full_gc: false,
             attributes: vec!["young".to_string(), "to-space exhausted".to_string()],
             gc_type: "G1 Evacuation Pause".to_string(),
             pause_time_seconds: 100.15,
-            time_epoch: 1658405469,
+            time_epoch_micros: 1658405469000000,
             heap_sizing: HeapSizing::None,
+            ..Default::default()
         };
         let pause4 = GCPause {
             is_full_gc: false,
             attributes: vec!["young".to_string(), "to-space exhausted".to_string()],
             gc_type: "G1 Evacuation Pause".to_string(),
             pause_time_seconds: 0.15,
-            time_epoch: 1658409469,
+            time_epoch_micros: 1658409469000000,
             heap_sizing: HeapSizing::None,
+            ..Default::default()
         };
         let pauses = vec![pause1, pause2, pause3, pause4];
         let output = generate_pause_table(&pauses);
@@ -502,8 +666,9 @@ mod tests {
                 attributes: vec!["young".to_string(), "to-space exhausted".to_string()],
                 gc_type: "G1 Evacuation Pause".to_string(),
                 pause_time_seconds: 0.15 + i as f64,
-                time_epoch: 1658409469,
+                time_epoch_micros: 1658409469000000,
                 heap_sizing: HeapSizing::None,
+                ..Default::default()
             });
         }
         let output = generate_pause_table(&pauses);
@@ -552,6 +717,108 @@ mod tests {
         assert_eq!(result.pause_time_seconds, 0.0911111);
         assert_eq!(result.gc_type, "Allocation Failure");
         assert_eq!(result.attributes.len(), 0);
+        assert_eq!(result.time_epoch_micros, 0);
+        assert_eq!(result.uptime_seconds, Some(16142.766));
+    }
+
+    #[test]
+    fn test_get_uptime_seconds() {
+        assert_eq!(
+            get_uptime_seconds("2022-08-24T01:54:38.603+0000: 190268.356: [Full GC (Allocation Failure) 2022-08-24T01:54:38.603+0000: 190268.356: [Class Histogram (before full gc):"),
+            Some(190268.356)
+        );
+        assert_eq!(
+            get_uptime_seconds("2022-08-24T01:54:38.603+0000: [GC pause (G1 Evacuation Pause) (young), 0.0110001 secs]"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_datetime_epoch() {
+        assert_eq!(
+            parse_datetime_epoch_micros("2022-07-22T18:41:06.240+0000").unwrap(),
+            1658515266240000
+        );
+        assert_eq!(
+            parse_datetime_epoch_micros("2022-07-22T20:41:06+02:00").unwrap(),
+            1658515266000000
+        );
+        assert_eq!(
+            parse_datetime_epoch_micros("2022-07-22 18:41:06").unwrap(),
+            1658515266000000
+        );
+        assert!(parse_datetime_epoch_micros("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_get_epoch_micros_keeps_milliseconds_and_offset() {
+        assert_eq!(
+            get_epoch_micros("2022-07-22T18:41:06.240+0000".to_string()).unwrap(),
+            1658515266240000
+        );
+        assert_eq!(
+            get_epoch_micros("2022-07-23T00:11:06.240+0530".to_string()).unwrap(),
+            1658515266240000
+        );
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("UTC").unwrap(), UtcOffset::UTC);
+        let kolkata = UtcOffset::from_hms(5, 30, 0).unwrap();
+        assert_eq!(parse_utc_offset("+05:30").unwrap(), kolkata);
+        assert_eq!(parse_utc_offset("+0530").unwrap(), kolkata);
+        assert_eq!(parse_utc_offset("+5:30").unwrap(), kolkata);
+        assert_eq!(parse_utc_offset("+530").unwrap(), kolkata);
+        assert_eq!(
+            parse_utc_offset("-8").unwrap(),
+            UtcOffset::from_hms(-8, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_utc_offset("-08").unwrap(),
+            UtcOffset::from_hms(-8, 0, 0).unwrap()
+        );
+        assert!(parse_utc_offset("Mars/Olympus").is_err());
+        assert!(parse_utc_offset("+99").is_err());
+        assert!(parse_utc_offset("+05:3").is_err());
+        assert!(parse_utc_offset("+123:00").is_err());
+        assert!(parse_utc_offset("+1€").is_err());
+        assert!(parse_utc_offset("-€:30").is_err());
+    }
+
+    #[test]
+    fn test_show_max_pause_times_in_zone() {
+        let pauses = vec![GCPause {
+            gc_type: "Allocation Failure".to_string(),
+            pause_time_seconds: 0.5,
+            time_epoch_micros: 1658515266240000,
+            ..Default::default()
+        }];
+        let utc = show_max_pause_times(&pauses, UtcOffset::UTC);
+        assert!(
+            utc.starts_with("Timestamp: 2022-07-22T18:41:06.240Z"),
+            "was {utc}"
+        );
+        let kolkata = show_max_pause_times(&pauses, parse_utc_offset("+05:30").unwrap());
+        assert!(
+            kolkata.starts_with("Timestamp: 2022-07-23T00:11:06.240+05:30"),
+            "was {kolkata}"
+        );
+    }
+
+    #[test]
+    fn test_show_max_pause_times_without_date_stamps() {
+        let pauses = vec![GCPause {
+            gc_type: "Allocation Failure".to_string(),
+            pause_time_seconds: 0.5,
+            uptime_seconds: Some(19999.636),
+            ..Default::default()
+        }];
+        let output = show_max_pause_times(&pauses, UtcOffset::UTC);
+        assert!(
+            output.starts_with("Timestamp: 19999.636 seconds after jvm start"),
+            "was {output}"
+        );
     }
 
     #[test]
@@ -12,20 +12,905 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::human::human_bytes;
+
 use super::{
+    classhistogram::find_class_growth,
+    cms::{CONCURRENT_MODE_FAILURE, PROMOTION_FAILED},
+    ergonomics::count_mixed_gcs_skipped,
     flags::{Collector, GCFlags},
+    fullgc::{count_full_gc_causes, get_full_gc_cause, FullGCCause},
+    g1cycles::build_marking_cycles,
+    g1phases::find_g1_phase,
+    jvmflags::{parse_jvm_flags, JvmFlags},
+    jvmstats::{JvmInfo, MemoryStats},
+    memorybudget::build_memory_budget,
+    occupancy::{get_occupancy, Space},
     pauses::{GCPause, HeapSizing},
+    references::{get_reference_seconds, REFERENCE_KINDS},
+    safepoints::{find_ttsp_outliers, Safepoint, TTSP_OUTLIER_SECONDS},
+    stalls::AllocationStall,
+    tenuring::TenuringDistribution,
+    times::{get_gc_threads, is_single_threaded_full_gc},
 };
 
-pub fn generate_recommendations(flags: &GCFlags, pauses: &Vec<GCPause>) -> String {
+fn full_gc_cause_recommendations(
+    flags: &GCFlags,
+    jvm_flags: &JvmFlags,
+    pauses: &[GCPause],
+    jvm_info: &JvmInfo,
+) -> Vec<String> {
+    let mut recs = vec![];
+    for stats in count_full_gc_causes(pauses) {
+        let summary = format!(
+            "{} Full GCs were caused by {} adding {:.2} total seconds pause time with a max pause time of {:.2} seconds",
+            stats.count, stats.cause, stats.total_seconds, stats.max_seconds
+        );
+        let share = stats.count as f64 / pauses.len() as f64 * 100.0;
+        //the parallel collector has its own advice based on old gen occupancy
+        let parallel = flags.collector == Collector::Parallel;
+        match &stats.cause {
+            FullGCCause::AllocationFailure if !parallel => {
+                recs.push(format!("* {summary}, {share:.2}% of all GCs. This means the max heap size was too small for use case during that time. Raising the heap size will help minimize the chance of this occuring."));
+            }
+            //shenandoah never logs a cause for its full gcs and has its own advice for them
+            FullGCCause::Other(cause)
+                if cause == "Unknown" && !parallel && flags.collector != Collector::Shenandoah =>
+            {
+                let flag = if jvm_info.unified_logging {
+                    "-Xlog:gc*"
+                } else {
+                    "-XX:+PrintGCDetails"
+                };
+                recs.push(format!("* {summary}, {share:.2}% of all GCs. The log does not say why they happened, if the heap was full raising the heap size will help minimize the chance of this occuring. Add {flag} to log the cause."));
+            }
+            FullGCCause::SystemGC => {
+                let advice = if flags.collector == Collector::G1GC
+                    || flags.collector == Collector::CMS
+                {
+                    "Add -XX:+ExplicitGCInvokesConcurrent so the calls start a concurrent cycle instead, this keeps direct memory cleanup working which -XX:+DisableExplicitGC would break."
+                } else {
+                    "Add -XX:+DisableExplicitGC to ignore the calls, but note direct memory is only released by a GC and the JVM calls System.gc() itself when -XX:MaxDirectMemorySize is reached."
+                };
+                recs.push(format!("* {summary}. Something is calling System.gc(), common sources are RMI distributed GC every hour, direct memory reaching -XX:MaxDirectMemorySize and application or library code. {advice}"));
+            }
+            FullGCCause::MetadataGCThreshold => {
+                let metaspace_size = jvm_flags.get_size("MetaspaceSize").unwrap_or_default();
+                let max_used = pauses
+                    .iter()
+                    .filter_map(|p| get_occupancy(p, Space::Metaspace))
+                    .map(|m| m.after_bytes.max(m.before_bytes))
+                    .max();
+                let target = match max_used {
+                    Some(used) if used > metaspace_size => format!(
+                        " to at least {}, the most metaspace used in this log",
+                        human_bytes(used)
+                    ),
+                    _ => "".to_string(),
+                };
+                recs.push(format!("* {summary}. Metaspace reached -XX:MetaspaceSize={} and the JVM ran a Full GC to unload classes before growing it. Raise -XX:MetaspaceSize{target} so startup and class loading do not trigger Full GCs, if they keep happening at the same size look for class loader leaks.", human_bytes(metaspace_size)));
+            }
+            FullGCCause::HeapInspection => {
+                recs.push(format!("* {summary}. These come from jmap -histo, jcmd GC.class_histogram or a monitoring agent taking class histograms, each one stops the application for a Full GC. Check for tools running them on a schedule."));
+            }
+            FullGCCause::GCLocker => {
+                recs.push(format!("* {summary}. Threads inside JNI critical sections held off the GC until the heap was full, this is usually native compression, zip or NIO code. Look for JNI code holding critical sections for a long time and give the heap more headroom."));
+            }
+            //heap dumps were asked for, the parallel collector handles allocation failures and
+            //ergonomics, and other causes do not have advice
+            _ => {}
+        }
+    }
+    recs
+}
+
+// old gen at or above this percentage of capacity when a full gc starts means it really filled up
+const OLD_GEN_FULL_PERCENT: f64 = 90.0;
+
+fn parallel_full_gc_recommendations(pauses: &[GCPause]) -> Vec<String> {
+    let mut recs = vec![];
+    let mut old_gen_full = 0;
+    let mut old_gen_full_total_pause_time = 0.0;
+    let mut old_gen_full_after_percent = 0.0;
+    let mut ergonomics = 0;
+    let mut ergonomics_total_pause_time = 0.0;
+    let mut ergonomics_before_percent = 0.0;
+    for pause in pauses {
+        if !pause.is_full_gc {
+            continue;
+        }
+        let old = match get_occupancy(pause, Space::Old) {
+            Some(old) if old.capacity_bytes > 0 => old,
+            _ => continue,
+        };
+        let before_percent = old.before_bytes as f64 / old.capacity_bytes as f64 * 100.0;
+        let after_percent = old.after_bytes as f64 / old.capacity_bytes as f64 * 100.0;
+        if before_percent >= OLD_GEN_FULL_PERCENT {
+            old_gen_full += 1;
+            old_gen_full_total_pause_time += pause.pause_time_seconds;
+            old_gen_full_after_percent += after_percent;
+        } else if pause.attributes.contains(&"Ergonomics".to_string()) {
+            ergonomics += 1;
+            ergonomics_total_pause_time += pause.pause_time_seconds;
+            ergonomics_before_percent += before_percent;
+        }
+    }
+    if old_gen_full > 0 {
+        recs.push(format!(
+            "* {} Full GCs started with the old generation at least {:.0}% full adding {:.2} total seconds pause time, after collection old gen was still {:.2}% full on average. The live data is close to the size of the old generation, raising the heap size will give the parallel collector room to work.",
+            old_gen_full,
+            OLD_GEN_FULL_PERCENT,
+            old_gen_full_total_pause_time,
+            old_gen_full_after_percent / old_gen_full as f64,
+        ));
+    }
+    if ergonomics > 0 {
+        recs.push(format!(
+            "* {} Full GCs were triggered by Ergonomics while the old generation was only {:.2}% full on average adding {:.2} total seconds pause time. The adaptive size policy predicted the next promotion would not fit in old gen, consider giving old gen more room with a lower -XX:NewRatio or disabling -XX:-UseAdaptiveSizePolicy and sizing the generations explicitly.",
+            ergonomics,
+            ergonomics_before_percent / ergonomics as f64,
+            ergonomics_total_pause_time,
+        ));
+    }
+    recs
+}
+
+// jdk 8 starts a cms cycle at ((100 - MinHeapFreeRatio) + MinHeapFreeRatio * CMSTriggerRatio / 100)% of old gen
+// which is 92% with the default MinHeapFreeRatio of 40 and CMSTriggerRatio of 80
+const CMS_DEFAULT_INITIATING_OCCUPANCY_FRACTION: i64 = 92;
+// starting the cycle earlier than this mostly just burns cpu on back to back concurrent cycles
+const CMS_MIN_SUGGESTED_INITIATING_OCCUPANCY_FRACTION: i64 = 50;
+
+fn cms_recommendations(jvm_flags: &JvmFlags, pauses: &[GCPause]) -> Vec<String> {
+    let mut recs = vec![];
+    let mut concurrent_mode_failures = 0;
+    let mut concurrent_mode_failures_total_pause_time = 0.0;
+    let mut promotion_failures = 0;
+    let mut promotion_failures_total_pause_time = 0.0;
+    for pause in pauses {
+        if pause
+            .attributes
+            .contains(&CONCURRENT_MODE_FAILURE.to_string())
+        {
+            concurrent_mode_failures += 1;
+            concurrent_mode_failures_total_pause_time += pause.pause_time_seconds;
+        } else if pause.attributes.contains(&PROMOTION_FAILED.to_string()) {
+            promotion_failures += 1;
+            promotion_failures_total_pause_time += pause.pause_time_seconds;
+        }
+    }
+    if concurrent_mode_failures > 0 {
+        //the default of -1 means the fraction is worked out from MinHeapFreeRatio
+        let current_fraction = jvm_flags
+            .get_number("CMSInitiatingOccupancyFraction")
+            .map(|fraction| fraction as i64)
+            .filter(|fraction| *fraction >= 0);
+        let suggestion = match current_fraction {
+            Some(fraction) => format!(
+                "CMSInitiatingOccupancyFraction is set to {}, lower it to -XX:CMSInitiatingOccupancyFraction={}",
+                fraction,
+                (fraction - 10).max(CMS_MIN_SUGGESTED_INITIATING_OCCUPANCY_FRACTION),
+            ),
+            None => format!(
+                "CMSInitiatingOccupancyFraction is not set so the cycle starts at about {CMS_DEFAULT_INITIATING_OCCUPANCY_FRACTION}% of old gen, set -XX:CMSInitiatingOccupancyFraction=70"
+            ),
+        };
+        let occupancy_only = if jvm_flags
+            .get_bool("UseCMSInitiatingOccupancyOnly")
+            .unwrap_or(false)
+        {
+            "".to_string()
+        } else {
+            " and add -XX:+UseCMSInitiatingOccupancyOnly so the JVM does not adjust it on its own"
+                .to_string()
+        };
+        recs.push(format!(
+            "* {concurrent_mode_failures} concurrent mode failures adding {concurrent_mode_failures_total_pause_time:.2} total seconds pause time, the old generation filled up before the concurrent cycle finished and the JVM fell back to a single threaded Full GC. Start the concurrent cycle earlier: {suggestion}{occupancy_only}."
+        ));
+    }
+    if promotion_failures > 0 {
+        recs.push(format!(
+            "* {promotion_failures} promotion failures adding {promotion_failures_total_pause_time:.2} total seconds pause time, there was no contiguous free space in the old generation for objects promoted from the young generation. CMS does not compact so this is usually fragmentation, give the old generation more headroom by raising the heap size or lowering CMSInitiatingOccupancyFraction, and reduce promotion with larger survivor spaces."
+        ));
+    }
+    recs
+}
+
+fn zgc_recommendations(allocation_stalls: &[AllocationStall]) -> Vec<String> {
+    let mut recs = vec![];
+    if !allocation_stalls.is_empty() {
+        let total_stall_time: f64 = allocation_stalls.iter().map(|s| s.stall_seconds).sum();
+        let max_stall_time = allocation_stalls
+            .iter()
+            .map(|s| s.stall_seconds)
+            .fold(0.0, f64::max);
+        recs.push(format!(
+            "* {} allocation stalls blocked application threads for {:.2} total seconds with a max stall of {:.2} seconds, ZGC could not free memory as fast as the application allocated it. Raise the max heap size to give the collector more headroom or raise -XX:ConcGCThreads so cycles finish sooner.",
+            allocation_stalls.len(),
+            total_stall_time,
+            max_stall_time,
+        ));
+    }
+    recs
+}
+
+fn shenandoah_recommendations(pauses: &[GCPause]) -> Vec<String> {
+    let mut recs = vec![];
+    let mut degenerated = 0;
+    let mut degenerated_total_pause_time = 0.0;
+    let mut full_gcs = 0;
+    let mut full_gc_total_pause_time = 0.0;
+    for pause in pauses {
+        if pause.gc_type == "Degenerated GC" {
+            degenerated += 1;
+            degenerated_total_pause_time += pause.pause_time_seconds;
+        } else if !matches!(
+            get_full_gc_cause(pause),
+            None | Some(
+                FullGCCause::SystemGC | FullGCCause::HeapDump | FullGCCause::HeapInspection
+            )
+        ) {
+            //requested full gcs have their own advice and say nothing about the heap size
+            full_gcs += 1;
+            full_gc_total_pause_time += pause.pause_time_seconds;
+        }
+    }
+    if degenerated > 0 {
+        recs.push(format!(
+            "* {degenerated} degenerated GCs adding {degenerated_total_pause_time:.2} total seconds pause time, the heap ran out of free memory before the concurrent cycle finished so Shenandoah completed it with the application stopped. Raise the heap size or start cycles earlier with a higher -XX:ShenandoahMinFreeThreshold."
+        ));
+    }
+    if full_gcs > 0 {
+        recs.push(format!(
+            "* {full_gcs} Full GCs after Shenandoah gave up on a degenerated GC adding {full_gc_total_pause_time:.2} total seconds pause time, the live data set is likely close to the max heap size. Raise the heap size."
+        ));
+    }
+    recs
+}
+
+// pauses shorter than this are fast enough that the phase breakdown is not worth acting on
+const MIN_PHASE_PAUSE_MILLIS: f64 = 50.0;
+// workers waiting in termination for more than this share of Parallel Time means the work was uneven
+const TERMINATION_PARALLEL_PERCENT: f64 = 20.0;
+// share of the pause above which a single phase is worth tuning on its own
+const UPDATE_RS_PAUSE_PERCENT: f64 = 25.0;
+const REF_PROC_PAUSE_PERCENT: f64 = 10.0;
+const FREE_CSET_PAUSE_PERCENT: f64 = 10.0;
+
+fn marking_cycle_recommendations(jvm_flags: &JvmFlags, pauses: &[GCPause]) -> Vec<String> {
+    let mut recs = vec![];
+    //only the pauses are needed to see a Full GC during concurrent mark
+    let cycles = build_marking_cycles(pauses, &[]);
+    let late = cycles.iter().filter(|c| c.full_gc_during_mark).count();
+    if late == 0 {
+        return recs;
+    }
+    let ihop = jvm_flags
+        .get_number("InitiatingHeapOccupancyPercent")
+        .unwrap_or_default();
+    let reserve = jvm_flags.get_number("G1ReservePercent").unwrap_or_default();
+    //the most the heap grew while marking as a share of the heap
+    let growth = cycles
+        .iter()
+        .filter(|c| c.full_gc_during_mark)
+        .filter_map(|c| {
+            let (after_bytes, capacity_bytes) = c.heap_after_initial_mark?;
+            let before_full_gc_bytes = c.heap_before_full_gc_bytes?;
+            if capacity_bytes <= 0 {
+                return None;
+            }
+            Some((before_full_gc_bytes - after_bytes) as f64 / capacity_bytes as f64 * 100.0)
+        })
+        .reduce(f64::max);
+    //start marking early enough that what is allocated while marking still fits in the heap
+    let suggested = match growth {
+        Some(growth) => 100.0 - growth - reserve,
+        None => ihop - 10.0,
+    };
+    let suggested = ((suggested.min(ihop - 5.0) / 5.0).floor() * 5.0).max(5.0);
+    //already at the lowest value worth suggesting
+    if suggested >= ihop {
+        return recs;
+    }
+    let growth_note = match growth {
+        Some(growth) => {
+            format!(" the heap grew by up to {growth:.0}% of its capacity while marking.")
+        }
+        None => "".to_string(),
+    };
+    recs.push(format!(
+        "* {late} G1 marking cycles were interrupted by a Full GC during concurrent mark, the cycles started too late to finish before the heap filled up,{growth_note} Lower -XX:InitiatingHeapOccupancyPercent from {ihop:.0} to {suggested:.0} so marking starts earlier, and raise -XX:ConcGCThreads if marking itself is slow."
+    ));
+    recs
+}
+
+fn parallel_ref_proc_advice(jvm_flags: &JvmFlags) -> &'static str {
+    if jvm_flags
+        .get_bool("ParallelRefProcEnabled")
+        .unwrap_or(false)
+    {
+        "-XX:+ParallelRefProcEnabled is already set, look for heavy use of soft, weak or phantom references and finalizers."
+    } else {
+        "Set -XX:+ParallelRefProcEnabled so references are processed by all GC threads."
+    }
+}
+
+// counts the pauses where a phase took more than the given share of the pause or its parent
+// phase, returning the count and the highest share seen
+fn count_phase_share(
+    pauses: &[GCPause],
+    phase_name: &str,
+    parent_name: Option<&str>,
+    percent: f64,
+) -> (i64, f64) {
+    let mut count = 0;
+    let mut max_share: f64 = 0.0;
+    for pause in pauses {
+        let pause_millis = pause.pause_time_seconds * 1000.0;
+        if pause_millis < MIN_PHASE_PAUSE_MILLIS {
+            continue;
+        }
+        let millis = match find_g1_phase(&pause.g1_phases, phase_name).and_then(|p| p.millis) {
+            Some(millis) => millis,
+            None => continue,
+        };
+        let total_millis = match parent_name {
+            Some(parent) => match find_g1_phase(&pause.g1_phases, parent).and_then(|p| p.millis) {
+                Some(millis) if millis > 0.0 => millis,
+                _ => continue,
+            },
+            None => pause_millis,
+        };
+        let share = millis / total_millis * 100.0;
+        if share > percent {
+            count += 1;
+            max_share = max_share.max(share);
+        }
+    }
+    (count, max_share)
+}
+
+fn g1_phase_recommendations(jvm_flags: &JvmFlags, pauses: &[GCPause]) -> Vec<String> {
+    let mut recs = vec![];
+    let (termination, termination_max) = count_phase_share(
+        pauses,
+        "Termination",
+        Some("Parallel Time"),
+        TERMINATION_PARALLEL_PERCENT,
+    );
+    if termination > 0 {
+        recs.push(format!(
+            "* {termination} pauses spent more than {TERMINATION_PARALLEL_PERCENT:.0}% of Parallel Time in Termination with a max of {termination_max:.2}%, GC workers sat idle waiting for one another. This is usually a single large object array or a long linked list that only one worker can scan, or more GC threads than available cpus. Check -XX:ParallelGCThreads against the cpus available and look for very large arrays or deep linked structures."
+        ));
+    }
+    let (update_rs, update_rs_max) =
+        count_phase_share(pauses, "Update RS", None, UPDATE_RS_PAUSE_PERCENT);
+    if update_rs > 0 {
+        recs.push(format!(
+            "* {update_rs} pauses spent more than {UPDATE_RS_PAUSE_PERCENT:.0}% of the pause in Update RS with a max of {update_rs_max:.2}%, the concurrent refinement threads are falling behind on the remembered sets. Raise -XX:G1ConcRefinementThreads or lower -XX:G1RSetUpdatingPauseTimePercent so more of the work is done outside of the pause."
+        ));
+    }
+    let (ref_proc, ref_proc_max) =
+        count_phase_share(pauses, "Ref Proc", None, REF_PROC_PAUSE_PERCENT);
+    if ref_proc > 0 {
+        recs.push(format!(
+            "* {ref_proc} pauses spent more than {REF_PROC_PAUSE_PERCENT:.0}% of the pause in Ref Proc with a max of {ref_proc_max:.2}%. {}",
+            parallel_ref_proc_advice(jvm_flags)
+        ));
+    }
+    let (free_cset, free_cset_max) =
+        count_phase_share(pauses, "Free CSet", None, FREE_CSET_PAUSE_PERCENT);
+    if free_cset > 0 {
+        recs.push(format!(
+            "* {free_cset} pauses spent more than {FREE_CSET_PAUSE_PERCENT:.0}% of the pause in Free CSet with a max of {free_cset_max:.2}%, freeing the collected regions is slow when there are a lot of them. Raise -XX:G1HeapRegionSize or lower the young generation size so there are fewer regions, newer JDKs also free the regions in parallel."
+        ));
+    }
+    recs
+}
+
+// reference processing above this share of pause time is worth tuning
+const REFERENCE_PAUSE_PERCENT: f64 = 10.0;
+// more finalizable objects than this in an average pause points at classes overriding finalize()
+const FINAL_REFS_PER_PAUSE: u64 = 10_000;
+
+fn reference_recommendations(jvm_flags: &JvmFlags, pauses: &[GCPause]) -> Vec<String> {
+    let mut recs = vec![];
+    let (reference_seconds, pause_seconds) = get_reference_seconds(pauses);
+    if pause_seconds > 0.0 {
+        let share = reference_seconds / pause_seconds * 100.0;
+        if share > REFERENCE_PAUSE_PERCENT {
+            let mut slowest = ("", 0.0);
+            for kind in REFERENCE_KINDS {
+                let seconds: f64 = pauses
+                    .iter()
+                    .flat_map(|p| p.references.iter())
+                    .filter(|r| r.kind == kind)
+                    .map(|r| r.seconds)
+                    .sum();
+                if seconds > slowest.1 {
+                    slowest = (kind, seconds);
+                }
+            }
+            recs.push(format!(
+                "* reference processing took {share:.2}% of pause time adding {reference_seconds:.2} total seconds, most of it in {} with {:.2} seconds. {}",
+                slowest.0,
+                slowest.1,
+                parallel_ref_proc_advice(jvm_flags)
+            ));
+        }
+    }
+    let mut final_pauses = 0;
+    let mut final_refs = 0;
+    for stats in pauses
+        .iter()
+        .flat_map(|p| p.references.iter())
+        .filter(|r| r.kind == "FinalReference")
+    {
+        final_pauses += 1;
+        final_refs += stats.refs.unwrap_or(0);
+    }
+    if final_pauses > 0 && final_refs / final_pauses > FINAL_REFS_PER_PAUSE {
+        recs.push(format!(
+            "* an average of {} FinalReferences were processed per pause, every object of a class that overrides finalize() needs at least two GCs to be freed and the finalizer thread can fall behind. Look for unclosed streams, zip files and sockets in a class histogram and close them explicitly, or replace finalize() with a Cleaner.",
+            final_refs / final_pauses
+        ));
+    }
+    recs
+}
+
+// a class has to grow across at least this many full gcs before it is called a leak suspect
+const MIN_LEAK_FULL_GCS: usize = 3;
+// growth smaller than this is not worth chasing even if it never goes down
+const MIN_LEAK_GROWTH_BYTES: i64 = 100 * 1024 * 1024;
+
+fn class_histogram_recommendations(pauses: &[GCPause]) -> Vec<String> {
+    let mut recs = vec![];
+    let suspects: Vec<_> = find_class_growth(pauses)
+        .into_iter()
+        .filter(|g| {
+            g.grew_every_time
+                && g.histograms >= MIN_LEAK_FULL_GCS
+                && g.last_bytes - g.first_bytes >= MIN_LEAK_GROWTH_BYTES
+        })
+        .collect();
+    for suspect in &suspects {
+        recs.push(format!(
+            "* {} grew from {} to {} without going down across {} Full GCs, it is a leak suspect. Take a heap dump and check what is holding on to it.",
+            suspect.class_name,
+            human_bytes(suspect.first_bytes),
+            human_bytes(suspect.last_bytes),
+            suspect.histograms
+        ));
+    }
+    if suspects.iter().any(|g| {
+        g.class_name.starts_with("org.apache.arrow.") || g.class_name.starts_with("io.netty.")
+    }) {
+        recs.push("* Arrow and Netty buffer objects that keep growing on heap usually mean direct memory buffers that were never closed, each one also holds native memory outside the heap. Look for allocators and buffers that are not released and compare with -XX:MaxDirectMemorySize.".to_string());
+    }
+    recs
+}
+
+// share of young pauses with a tenuring threshold of 1, or with survivors over the desired size,
+// above which objects are being promoted too early
+const PREMATURE_PROMOTION_PERCENT: f64 = 25.0;
+// too few young pauses with a tenuring distribution to say anything
+const MIN_TENURING_PAUSES: usize = 3;
+
+fn survivor_advice(flags: &GCFlags, jvm_flags: &JvmFlags) -> String {
+    let target = jvm_flags
+        .get_number("TargetSurvivorRatio")
+        .unwrap_or_default();
+    if flags.collector == Collector::G1GC {
+        return format!("G1 sizes the survivor space from the young generation so raise -XX:TargetSurvivorRatio from {target} to let it fill further before lowering the threshold, and check that -XX:MaxGCPauseMillis is not keeping the young generation too small.");
+    }
+    let ratio = jvm_flags.get_number("SurvivorRatio").unwrap_or_default();
+    format!("Make the survivor spaces bigger by lowering -XX:SurvivorRatio from {ratio} or raising -XX:TargetSurvivorRatio from {target}.")
+}
+
+fn tenuring_recommendations(
+    flags: &GCFlags,
+    jvm_flags: &JvmFlags,
+    pauses: &[GCPause],
+) -> Vec<String> {
+    let mut recs = vec![];
+    let distributions: Vec<&TenuringDistribution> =
+        pauses.iter().filter_map(|p| p.tenuring.as_ref()).collect();
+    if distributions.len() < MIN_TENURING_PAUSES {
+        return recs;
+    }
+    let old_gen_collections = pauses
+        .iter()
+        .filter(|p| p.is_full_gc || p.attributes.iter().any(|a| a == "mixed"))
+        .count();
+    let share = |count: usize| count as f64 / distributions.len() as f64 * 100.0;
+    let threshold_one = distributions
+        .iter()
+        .filter(|d| d.new_threshold == 1)
+        .count();
+    let overflowed = distributions.iter().filter(|d| d.overflowed()).count();
+    if share(threshold_one) > PREMATURE_PROMOTION_PERCENT {
+        recs.push(format!(
+            "* the tenuring threshold dropped to 1 in {threshold_one} of {} young pauses, objects that survive a single young GC are promoted to the old generation before they have a chance to die and have to be cleaned up by the {old_gen_collections} mixed and Full GCs in this log. {}",
+            distributions.len(),
+            survivor_advice(flags, jvm_flags)
+        ));
+    }
+    if share(overflowed) > PREMATURE_PROMOTION_PERCENT {
+        recs.push(format!(
+            "* survivors were over the desired survivor size in {overflowed} of {} young pauses, what does not fit is promoted straight to the old generation. {}",
+            distributions.len(),
+            survivor_advice(flags, jvm_flags)
+        ));
+    }
+    recs
+}
+
+// stopped time outside gc pauses above this share of all stopped time is worth looking into
+const NON_GC_STOPPED_PERCENT: f64 = 10.0;
+// ignore the non gc stopped time when it adds up to less than this
+const MIN_NON_GC_STOPPED_SECONDS: f64 = 1.0;
+
+// jdk 11 moved the safepoint statistics to unified logging and jdk 15 turned biased locking off
+fn safepoint_statistics_advice(jvm_info: &JvmInfo) -> &'static str {
+    if jvm_info.major_version >= 15 {
+        "Add -Xlog:safepoint+stats=debug to see which operations they were."
+    } else if jvm_info.major_version >= 11 {
+        "Add -Xlog:safepoint+stats=debug to see which operations they were, if most of them are RevokeBias turn off biased locking with -XX:-UseBiasedLocking."
+    } else {
+        "Add -XX:+PrintSafepointStatistics -XX:PrintSafepointStatisticsCount=1 to see which operations they were, if most of them are RevokeBias turn off biased locking with -XX:-UseBiasedLocking."
+    }
+}
+
+fn safepoint_recommendations(
+    pauses: &[GCPause],
+    safepoints: &[Safepoint],
+    jvm_info: &JvmInfo,
+) -> Vec<String> {
+    let mut recs = vec![];
+    let outliers = find_ttsp_outliers(safepoints);
+    if let Some(slowest) = outliers.first() {
+        recs.push(format!(
+            "* {} safepoints took more than {} ms to reach with the slowest at {:.3} seconds, the threads that had already stopped waited on threads that were slow to get to the safepoint, usually long counted loops, large array copies or page faults. Add -XX:+UnlockDiagnosticVMOptions -XX:+SafepointTimeout -XX:SafepointTimeoutDelay={} to log the threads that were late, and -XX:+UseCountedLoopSafepoints if they are in counted loops.",
+            outliers.len(),
+            TTSP_OUTLIER_SECONDS * 1000.0,
+            slowest.ttsp_seconds,
+            TTSP_OUTLIER_SECONDS * 1000.0,
+        ));
+    }
+    let stopped_seconds: f64 = safepoints.iter().map(|s| s.stopped_seconds).sum();
+    let pause_seconds: f64 = pauses.iter().map(|p| p.pause_time_seconds).sum();
+    let non_gc_seconds = stopped_seconds - pause_seconds;
+    if non_gc_seconds > MIN_NON_GC_STOPPED_SECONDS
+        && non_gc_seconds / stopped_seconds * 100.0 > NON_GC_STOPPED_PERCENT
+    {
+        recs.push(format!(
+            "* {:.2} seconds ({:.2}%) of the time the application threads were stopped was outside of GC pauses, these are safepoints for biased lock revocation, deoptimization, thread dumps and similar operations. {}",
+            non_gc_seconds,
+            non_gc_seconds / stopped_seconds * 100.0,
+            safepoint_statistics_advice(jvm_info)
+        ));
+    }
+    recs
+}
+
+// jdk 8 G1 only unloads classes at a Full GC and never eagerly reclaims humongous objects before this update
+const G1_JDK8_MIN_UPDATE: u32 = 40;
+// jdk 8 sizes the heap and gc threads from the host instead of the container before this update
+const CONTAINER_SUPPORT_JDK8_MIN_UPDATE: u32 = 191;
+
+fn jvm_recommendations(flags: &GCFlags, jvm_info: &JvmInfo) -> Vec<String> {
+    let mut recs = vec![];
+    if flags.collector == Collector::G1GC && jvm_info.is_older_than(8, G1_JDK8_MIN_UPDATE) {
+        recs.push(format!(
+            "* G1 is running on {} {}, G1 before 8u{G1_JDK8_MIN_UPDATE} only unloads classes during a Full GC and does not reclaim humongous objects until a marking cycle finishes, which leads to Full GCs that later updates avoid. Upgrade to the latest JDK 8 update.",
+            jvm_info.vendor, jvm_info.java_version
+        ));
+    }
+    if jvm_info.is_older_than(8, CONTAINER_SUPPORT_JDK8_MIN_UPDATE) {
+        recs.push(format!(
+            "* {} {} predates container support (added in 8u{CONTAINER_SUPPORT_JDK8_MIN_UPDATE}), in a container the JVM sizes the default heap and GC threads from the host memory and cpus instead of the container limits. Set -Xmx and -XX:ParallelGCThreads explicitly or upgrade to the latest JDK 8 update.",
+            jvm_info.vendor, jvm_info.java_version
+        ));
+    }
+    recs
+}
+
+// the os, page cache and everything else on the host need at least this share of the ram
+const OS_RESERVED_RAM_PERCENT: f64 = 10.0;
+
+fn memory_budget_recommendations(
+    flags: &GCFlags,
+    jvm_flags: &JvmFlags,
+    memory_stats: &MemoryStats,
+    pauses: &[GCPause],
+) -> Vec<String> {
+    let mut recs = vec![];
+    let budget = match build_memory_budget(flags, jvm_flags, memory_stats, pauses) {
+        Some(budget) => budget,
+        None => return recs,
+    };
+    let total = budget.total_bytes();
+    let summary = format!(
+        "the JVM can commit up to {} ({:.2} gb heap, {:.2} gb direct memory and about {} of metaspace, code cache and thread stacks)",
+        human_bytes(total),
+        flags.max_heap_size_gb,
+        flags.max_direct_memory_gb,
+        human_bytes(budget.native_bytes())
+    );
+    let advice = "Lower -XX:MaxDirectMemorySize or -Xmx so heap plus direct memory fits in the RAM of the host with room to spare.";
+    if total > budget.physical_bytes + budget.swap_bytes {
+        recs.push(format!(
+            "* {summary} but the host only has {} of RAM and {} of swap. Once the JVM uses what it is allowed to the OOM killer ends the process or allocations fail. {advice}",
+            human_bytes(budget.physical_bytes),
+            human_bytes(budget.swap_bytes)
+        ));
+    } else if total > budget.physical_bytes {
+        recs.push(format!(
+            "* {summary} which is more than the {} of RAM on the host, the rest would come from swap and GC pauses would take seconds as the heap is paged back in. {advice}",
+            human_bytes(budget.physical_bytes)
+        ));
+    } else if total as f64
+        > budget.physical_bytes as f64 * (100.0 - OS_RESERVED_RAM_PERCENT) / 100.0
+    {
+        recs.push(format!(
+            "* {summary} which leaves less than {OS_RESERVED_RAM_PERCENT:.0}% of the {} of RAM for the OS, the page cache and other processes. {advice}",
+            human_bytes(budget.physical_bytes)
+        ));
+    }
+    recs
+}
+
+// less free memory than this share of the physical memory when the jvm started is worth a warning
+const LOW_FREE_MEMORY_PERCENT: f64 = 10.0;
+// the usual page size on x86
+const DEFAULT_PAGE_SIZE_BYTES: i64 = 4096;
+
+fn memory_recommendations(memory_stats: &MemoryStats) -> Vec<String> {
+    let mut recs = vec![];
+    if let Some(swap) = memory_stats.swap_bytes.filter(|s| *s > 0) {
+        recs.push(format!(
+            "* swap is enabled with {} ({} free), when any of the heap is swapped out the GC threads wait on the disk and pauses take seconds of real time with little user time. Disable swap or set vm.swappiness=1 on hosts running the JVM.",
+            human_bytes(swap),
+            human_bytes(memory_stats.free_swap_bytes.unwrap_or_default())
+        ));
+    }
+    if let Some(free) = memory_stats.free_memory_bytes {
+        let total = memory_stats.physical_memory_bytes;
+        let free_percent = free as f64 / total as f64 * 100.0;
+        if total > 0 && free_percent < LOW_FREE_MEMORY_PERCENT {
+            recs.push(format!(
+                "* only {} of {} ({free_percent:.2}%) system RAM was free when the JVM started. The page cache does not count as free so this can be harmless, but if other processes hold the memory the heap will be swapped or the JVM killed by the OOM killer as it grows. Check what else runs on the host and set -Xms equal to -Xmx with -XX:+AlwaysPreTouch so the heap is committed at startup.",
+                human_bytes(free),
+                human_bytes(total)
+            ));
+        }
+    }
+    if let Some(page_size) = memory_stats
+        .page_size_bytes
+        .filter(|p| *p > DEFAULT_PAGE_SIZE_BYTES)
+    {
+        recs.push(format!(
+            "* the page size was {} instead of the usual 4 kb, large pages or a kernel with large base pages (common on aarch64 and ppc64) were in use. Memory is committed in whole pages so thread stacks and other small allocations take more RAM than the flags suggest, and the heap should be sized with that in mind.",
+            human_bytes(page_size)
+        ));
+    }
+    recs
+}
+
+// the collectors that can be picked on the command line, UseParNewGC and UseParallelOldGC
+// only go with CMS and the parallel collector
+const COLLECTOR_FLAGS: &[&str] = &[
+    "UseG1GC",
+    "UseConcMarkSweepGC",
+    "UseParallelGC",
+    "UseSerialGC",
+    "UseZGC",
+    "UseShenandoahGC",
+];
+// flag name prefixes that only mean something to one collector
+const COLLECTOR_FLAG_PREFIXES: &[(&str, Collector)] = &[
+    ("G1", Collector::G1GC),
+    ("CMS", Collector::CMS),
+    ("UseCMS", Collector::CMS),
+    ("Shenandoah", Collector::Shenandoah),
+];
+// need at least this many young pauses before saying the pause target is out of reach
+const MIN_PAUSE_TARGET_PAUSES: usize = 10;
+
+fn median_young_pause_millis(pauses: &[GCPause]) -> Option<f64> {
+    let mut millis: Vec<f64> = pauses
+        .iter()
+        .filter(|p| !p.is_full_gc)
+        .map(|p| p.pause_time_seconds * 1000.0)
+        .collect();
+    if millis.len() < MIN_PAUSE_TARGET_PAUSES {
+        return None;
+    }
+    millis.sort_by(|a, b| a.total_cmp(b));
+    Some(millis[millis.len() / 2])
+}
+
+// flags that contradict each other, do nothing for the collector or are risky together
+fn flag_recommendations(flags: &GCFlags, jvm_flags: &JvmFlags, pauses: &[GCPause]) -> Vec<String> {
+    let mut recs = vec![];
+    if flags.all_flags.is_empty() {
+        return recs;
+    }
+    let enabled = |name: &str| jvm_flags.get_bool(name).unwrap_or(false);
+
+    let collectors: Vec<String> = COLLECTOR_FLAGS
+        .iter()
+        .filter(|name| enabled(name))
+        .map(|name| format!("-XX:+{name}"))
+        .collect();
+    if collectors.len() > 1 {
+        recs.push(format!(
+            "* more than one collector was selected with {}, newer JDKs refuse to start with this and JDK 8 quietly picks one of them. Keep only the flag of the collector you want.",
+            collectors.join(" ")
+        ));
+    }
+    let other_collector_flags: Vec<&str> = jvm_flags
+        .settings
+        .iter()
+        .filter(|s| s.explicit)
+        .filter(|s| {
+            COLLECTOR_FLAG_PREFIXES.iter().any(|(prefix, collector)| {
+                s.name.starts_with(prefix) && *collector != flags.collector
+            })
+        })
+        .map(|s| s.name.as_str())
+        .collect();
+    //without a collector flag there is no telling which collector the flags were meant for
+    if !other_collector_flags.is_empty() && flags.collector != Collector::Unknown {
+        recs.push(format!(
+            "* flags for a collector other than {:?} are set ({}), they have no effect. Remove them so the flags show what is actually tuned.",
+            flags.collector,
+            other_collector_flags.join(", ")
+        ));
+    }
+    if flags.collector == Collector::G1GC {
+        if jvm_flags.is_explicit("NewSize") || jvm_flags.is_explicit("NewRatio") {
+            recs.push("* the young generation is fixed with -Xmn, -XX:NewSize or -XX:NewRatio while using G1. G1 sizes the young generation on every pause to meet -XX:MaxGCPauseMillis and a fixed size turns that off, so the pause time goal is ignored. Remove these flags and let G1 size the young generation.".to_string());
+        }
+        if let Some(median) = median_young_pause_millis(pauses) {
+            if flags.target_pause_millis > 0 && median > flags.target_pause_millis as f64 {
+                recs.push(format!(
+                    "* -XX:MaxGCPauseMillis={} but the median young pause was {median:.2} ms, the target is below what this heap can achieve. G1 keeps shrinking the young generation to chase it which makes GCs more frequent without making them shorter, raise -XX:MaxGCPauseMillis to at least {:.0}.",
+                    flags.target_pause_millis,
+                    median.ceil()
+                ));
+            }
+        }
+        if let Some(region_size) = jvm_flags.get_size("G1HeapRegionSize") {
+            let mb = 1024 * 1024;
+            if region_size.count_ones() != 1 || !(mb..=32 * mb).contains(&region_size) {
+                recs.push(format!(
+                    "* -XX:G1HeapRegionSize={region_size} is not a power of two between 1 mb and 32 mb, G1 rounds it to one. Set it to the size you want it to be such as -XX:G1HeapRegionSize=16m."
+                ));
+            }
+        }
+    }
+    if enabled("AlwaysPreTouch") && (flags.max_heap_size_gb - flags.min_heap_size_gb).abs() > 0.01 {
+        recs.push(format!(
+            "* -XX:+AlwaysPreTouch is on but the initial heap of {:.2} gb is smaller than the max heap of {:.2} gb, only the initial heap is touched at startup and the heap still pays for the page faults as it grows. Set -Xms equal to -Xmx.",
+            flags.min_heap_size_gb, flags.max_heap_size_gb
+        ));
+    }
+    if enabled("DisableExplicitGC") && enabled("ExplicitGCInvokesConcurrent") {
+        recs.push("* both -XX:+DisableExplicitGC and -XX:+ExplicitGCInvokesConcurrent are set, System.gc() is ignored so ExplicitGCInvokesConcurrent does nothing. Keep the one you want, ExplicitGCInvokesConcurrent lets direct memory cleanup through System.gc() still work.".to_string());
+    }
+    if enabled("UseGCLogFileRotation")
+        && jvm_flags
+            .get_number("NumberOfGCLogFiles")
+            .map(|files| files < 1.0)
+            .unwrap_or(true)
+    {
+        recs.push("* -XX:+UseGCLogFileRotation is on without -XX:NumberOfGCLogFiles, JDK 8 turns rotation off when the file count is missing and the gc log grows without limit. Add -XX:NumberOfGCLogFiles=10 and -XX:GCLogFileSize=10m.".to_string());
+    }
+    if enabled("HeapDumpOnOutOfMemoryError") && jvm_flags.get("HeapDumpPath").is_none() {
+        recs.push("* -XX:+HeapDumpOnOutOfMemoryError is on without -XX:HeapDumpPath, the heap dump is written to the working directory of the JVM which is often small, read only or cleaned up on restart. Set -XX:HeapDumpPath to a directory with room for a dump the size of the max heap.".to_string());
+    }
+    if !jvm_flags.duplicates.is_empty() {
+        recs.push(format!(
+            "* {} set more than once, the last value on the command line wins which is easy to miss when reading the flags. Remove the extra ones.",
+            jvm_flags.duplicates.join(", ")
+        ));
+    }
+    if !jvm_flags.invalid.is_empty() {
+        recs.push(format!(
+            "* {} could not be read as the kind of value the flag takes, check the spelling and units.",
+            jvm_flags.invalid.join(" ")
+        ));
+    }
+    recs
+}
+
+// pauses shorter than this have too little cpu time for the user, sys and real ratios to mean much
+const MIN_CPU_TIME_PAUSE_SECONDS: f64 = 0.1;
+// sys time above this share of user time means the kernel was doing a lot of work for the gc
+const HIGH_SYS_TO_USER_RATIO: f64 = 0.3;
+// real time this much above user + sys means the gc threads spent time waiting instead of running
+const REAL_TO_CPU_RATIO: f64 = 1.2;
+// gc threads busy less than this share of the time means they did not get the cpus they expected
+const MIN_PARALLELISM_TO_THREADS_RATIO: f64 = 0.5;
+
+fn cpu_time_recommendations(
+    flags: &GCFlags,
+    jvm_flags: &JvmFlags,
+    pauses: &[GCPause],
+    jvm_info: &JvmInfo,
+) -> Vec<String> {
+    let mut recs = vec![];
+    let mut high_sys = 0;
+    let mut high_sys_max: f64 = 0.0;
+    let mut waiting = 0;
+    let mut waiting_total_seconds = 0.0;
+    let mut waiting_max_real: f64 = 0.0;
+    let mut total_user = 0.0;
+    let mut total_real = 0.0;
+    for pause in pauses {
+        let times = match pause.cpu_times {
+            Some(times) if times.real_seconds >= MIN_CPU_TIME_PAUSE_SECONDS => times,
+            _ => continue,
+        };
+        //a single threaded full gc would drag the parallelism of the gc threads down
+        if !is_single_threaded_full_gc(pause, &flags.collector, jvm_info) {
+            total_user += times.user_seconds;
+            total_real += times.real_seconds;
+        }
+        if times.sys_seconds >= MIN_CPU_TIME_PAUSE_SECONDS
+            && times.sys_seconds > times.user_seconds * HIGH_SYS_TO_USER_RATIO
+        {
+            high_sys += 1;
+            high_sys_max = high_sys_max.max(times.sys_seconds);
+        }
+        let cpu_seconds = times.user_seconds + times.sys_seconds;
+        if times.real_seconds > cpu_seconds * REAL_TO_CPU_RATIO {
+            waiting += 1;
+            waiting_total_seconds += times.real_seconds - cpu_seconds;
+            waiting_max_real = waiting_max_real.max(times.real_seconds);
+        }
+    }
+    if high_sys > 0 {
+        recs.push(format!(
+            "* {high_sys} pauses spent more than {:.0}% of their user time in sys time with a max sys time of {high_sys_max:.2} seconds, the kernel was busy on behalf of the GC threads. This is usually the heap being swapped or paged in, transparent huge page compaction, or the heap being committed as it grows. Disable swap, set transparent huge pages to madvise, and set -Xms equal to -Xmx with -XX:+AlwaysPreTouch.",
+            HIGH_SYS_TO_USER_RATIO * 100.0,
+        ));
+    }
+    if waiting > 0 {
+        recs.push(format!(
+            "* {waiting} pauses took longer in real time than the user and sys time of all GC threads combined, adding {waiting_total_seconds:.2} total seconds of waiting with a max real time of {waiting_max_real:.2} seconds. The GC threads were not running, this is usually swapping, I/O stalls writing the gc log (keep it on a local disk), or CPU starvation from a container CPU limit or a busy host."
+        ));
+    }
+    if let Some(threads) = get_gc_threads(jvm_flags, pauses) {
+        if threads > 1 && total_real > 0.0 {
+            let parallelism = total_user / total_real;
+            if parallelism < threads as f64 * MIN_PARALLELISM_TO_THREADS_RATIO {
+                recs.push(format!(
+                    "* GC threads averaged a parallelism of {parallelism:.2} (user / real) with {threads} GC threads, most GC threads were idle or waiting for a cpu. The host or container likely has fewer cpus available than the JVM assumed, set -XX:ParallelGCThreads to the number of cpus actually available."
+                ));
+            }
+        }
+    }
+    recs
+}
+
+pub fn generate_recommendations(
+    flags: &GCFlags,
+    pauses: &Vec<GCPause>,
+    safepoints: &[Safepoint],
+    allocation_stalls: &[AllocationStall],
+    jvm_info: &JvmInfo,
+    memory_stats: &MemoryStats,
+) -> String {
     let mut recs: Vec<String> = vec![];
+    //parsed once here and handed to everything that reads a flag
+    let jvm_flags = parse_jvm_flags(&flags.all_flags, &flags.collector);
     let mut to_space_exhausted = 0;
     let mut to_space_exhausted_total_pause_time = 0.0;
     let mut to_space_exhausted_max_pause_time: f64 = 0.0;
     let total_pauses = pauses.len();
     let mut total_full_gcs = 0;
-    let mut full_gc_max_pause_time: f64 = 0.0;
-    let mut full_gc_total_pause_time: f64 = 0.0;
     let mut humongous_collections = 0;
     let mut humongous_collections_total_pause_time = 0.0;
     let mut total_resizes_up = 0;
@@ -48,8 +933,6 @@ pub fn generate_recommendations(flags: &GCFlags, pauses: &Vec<GCPause>) -> Strin
             humongous_collections_total_pause_time += pause.pause_time_seconds;
         } else if pause.is_full_gc {
             total_full_gcs += 1;
-            full_gc_max_pause_time = full_gc_max_pause_time.max(pause.pause_time_seconds);
-            full_gc_total_pause_time += pause.pause_time_seconds;
         }
     }
     match flags.collector {
@@ -76,22 +959,41 @@ pub fn generate_recommendations(flags: &GCFlags, pauses: &Vec<GCPause>) -> Strin
                     "* {per_total_pauses:.2}% of GCs were humongous allocations adding {humongous_collections_total_pause_time:.2} total seconds pause time, this indicates there are objects to big for your GC configuration. {recommend_new_region_size}"
                 ))
             }
+            recs.extend(g1_phase_recommendations(&jvm_flags, pauses));
+            recs.extend(marking_cycle_recommendations(&jvm_flags, pauses));
+            let mixed_gcs_skipped = count_mixed_gcs_skipped(pauses);
+            if mixed_gcs_skipped > 0 && total_full_gcs > 0 {
+                let heap_waste_percent = jvm_flags
+                    .get_number("G1HeapWastePercent")
+                    .unwrap_or_default();
+                recs.push(format!(
+                    "* mixed GCs were skipped {mixed_gcs_skipped} times because the reclaimable percentage of the old generation was below -XX:G1HeapWastePercent={heap_waste_percent} and there were {total_full_gcs} Full GCs. The old generation is filling up with regions G1 considers not worth collecting, lower -XX:G1HeapWastePercent or raise -XX:G1MixedGCLiveThresholdPercent so mixed GCs clean up more of it."
+                ));
+            }
+        },
+        Collector::CMS => {
+            recs.push("* CMS GC collector detected. This is an older collector and is removed in java 14. This can actually be a very performant collector, and if the machine is well tuned, it is best to leave it as it was. However, if you intend to raise the heap size consider the G1GC collector.".to_string());
+            recs.extend(cms_recommendations(&jvm_flags, pauses));
+        },
+        Collector::Parallel => {
+            recs.push("* Parallel GC collector detected. This is an older collector and it will lead to long pauses. Use G1GC instead.".to_string());
+            recs.extend(parallel_full_gc_recommendations(pauses));
+        },
+        Collector::ZGC => {
+            recs.push("* ZGC GC collector detected. This is a newer collector optimized for shorter gc pauses, however, it has some known issues with dremio (see https://dremio.atlassian.net/browse/DX-46569?focusedCommentId=494918). Consider using G1GC or CMS instead.".to_string());
+            recs.extend(zgc_recommendations(allocation_stalls));
+        },
+        Collector::Shenandoah => {
+            recs.push("* Shenandoah GC collector detected. This is a newer collector and is not yet full supported by Dremio (see https://dremio.atlassian.net/browse/DX-37567) and there may be some unexpected behavior consider using the G1GC collector.".to_string());
+            recs.extend(shenandoah_recommendations(pauses));
         },
-        Collector::CMS =>
-        recs.push("* CMS GC collector detected. This is an older collector and is removed in java 14. This can actually be a very performant collector, and if the machine is well tuned, it is best to leave it as it was. However, if you intend to raise the heap size consider the G1GC collector.".to_string()),
-        Collector::Parallel =>
-        recs.push("* Parallel GC collector detected. This is an older collector and it will lead to long pauses. Use G1GC instead.".to_string()),
-        Collector::ZGC =>
-        recs.push("* ZGC GC collector detected. This is a newer collector optimized for shorter gc pauses, however, it has some known issues with dremio (see https://dremio.atlassian.net/browse/DX-46569?focusedCommentId=494918). Consider using G1GC or CMS instead.".to_string()),
-        Collector::Shenandoah =>
-        recs.push("* Shenandoah GC collector detected. This is a newer collector and is not yet full supported by Dremio (see https://dremio.atlassian.net/browse/DX-37567) and there may be some unexpected behavior consider using the G1GC collector.".to_string()),
         Collector::Unknown =>
         recs.push("* Unknown GC collector detected. Review the JVM flags and try and submit a bug report for this new collector to https://dremio.atlassian.net/jira/software/c/projects/ST/issues/?filter=allissues".to_string()),
     }
 
-    if total_full_gcs > 0 && Collector::Parallel != flags.collector {
-        recs.push(format!("* {:.2}% of GCs were Full GCs adding {:.2} total seconds pause time with a max pause time of {:.2} seconds,  this means the max heap size was too small for use case during that time. Raising the heap size will help minimize the chance of this occuring.", total_full_gcs as f64/ total_pauses as f64, full_gc_total_pause_time ,full_gc_max_pause_time));
-    }
+    recs.extend(full_gc_cause_recommendations(
+        flags, &jvm_flags, pauses, jvm_info,
+    ));
 
     let total_resize_attempts = total_resizes_up + total_resizes_down;
     if total_resize_attempts > 0 && (flags.max_heap_size_gb - flags.min_heap_size_gb).abs() > 0.01 {
@@ -103,6 +1005,23 @@ pub fn generate_recommendations(flags: &GCFlags, pauses: &Vec<GCPause>) -> Strin
         ))
     }
 
+    recs.extend(cpu_time_recommendations(
+        flags, &jvm_flags, pauses, jvm_info,
+    ));
+    recs.extend(reference_recommendations(&jvm_flags, pauses));
+    recs.extend(class_histogram_recommendations(pauses));
+    recs.extend(tenuring_recommendations(flags, &jvm_flags, pauses));
+    recs.extend(safepoint_recommendations(pauses, safepoints, jvm_info));
+    recs.extend(jvm_recommendations(flags, jvm_info));
+    recs.extend(flag_recommendations(flags, &jvm_flags, pauses));
+    recs.extend(memory_recommendations(memory_stats));
+    recs.extend(memory_budget_recommendations(
+        flags,
+        &jvm_flags,
+        memory_stats,
+        pauses,
+    ));
+
     if recs.is_empty() {
         return "".to_string();
     }
@@ -117,9 +1036,19 @@ pub fn generate_recommendations(flags: &GCFlags, pauses: &Vec<GCPause>) -> Strin
 #[cfg(test)]
 mod tests {
     use crate::glog::{
-        flags::GCFlags,
+        classhistogram::{ClassCount, ClassHistogram},
+        ergonomics::parse_ergonomics,
+        flags::{parse_gc_flags, GCFlags},
+        g1phases::G1Phase,
+        jvmstats::{parse_jvm_info, parse_memory, JvmInfo, MemoryStats},
+        occupancy::{Space, SpaceOccupancy},
         pauses::{GCPause, HeapSizing},
         recommendations::generate_recommendations,
+        references::ReferenceStats,
+        safepoints::Safepoint,
+        stalls::AllocationStall,
+        tenuring::{AgeBytes, TenuringDistribution},
+        times::CpuTimes,
     };
 
     #[test]
@@ -130,16 +1059,18 @@ mod tests {
                 gc_type: "G1 Humongous Allocation".to_string(),
                 is_full_gc: false,
                 pause_time_seconds: 3.10,
-                time_epoch: 1658739348,
+                time_epoch_micros: 1658739348000000,
                 heap_sizing: HeapSizing::None,
+                ..Default::default()
             },
             GCPause {
                 attributes: vec!["young".to_string()],
                 gc_type: "G1 Humongous Allocation".to_string(),
                 is_full_gc: false,
                 pause_time_seconds: 3.10,
-                time_epoch: 1658739348,
+                time_epoch_micros: 1658739348000000,
                 heap_sizing: HeapSizing::None,
+                ..Default::default()
             },
         ];
         let recs = generate_recommendations(
@@ -153,6 +1084,10 @@ mod tests {
                 all_flags: vec![],
             },
             &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert_eq!(recs, "recommendations
@@ -170,6 +1105,10 @@ mod tests {
                 all_flags: vec![],
             },
             &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert_eq!(recs, "recommendations
@@ -186,6 +1125,10 @@ mod tests {
                 all_flags: vec![],
             },
             &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert_eq!(recs, "recommendations
@@ -202,6 +1145,10 @@ mod tests {
                 all_flags: vec![],
             },
             &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert_eq!(recs, "recommendations
@@ -218,6 +1165,10 @@ mod tests {
                 all_flags: vec![],
             },
             &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert_eq!(recs, "recommendations
@@ -233,16 +1184,18 @@ mod tests {
                 gc_type: "G1 Humongous Allocation".to_string(),
                 is_full_gc: false,
                 pause_time_seconds: 3.10,
-                time_epoch: 1658739348,
+                time_epoch_micros: 1658739348000000,
                 heap_sizing: HeapSizing::None,
+                ..Default::default()
             },
             GCPause {
                 attributes: vec!["young".to_string()],
                 gc_type: "G1 Humongous Allocation".to_string(),
                 is_full_gc: false,
                 pause_time_seconds: 3.10,
-                time_epoch: 1658739348,
+                time_epoch_micros: 1658739348000000,
                 heap_sizing: HeapSizing::None,
+                ..Default::default()
             },
         ];
         let recs = generate_recommendations(
@@ -256,6 +1209,10 @@ mod tests {
                 all_flags: vec![],
             },
             &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert_eq!(recs, "recommendations
@@ -276,6 +1233,10 @@ mod tests {
                 all_flags: vec![],
             },
             &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(recs.is_empty());
     }
@@ -293,11 +1254,95 @@ mod tests {
                 all_flags: vec![],
             },
             &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert!(recs.contains("CMS GC collector detected"));
     }
 
+    #[test]
+    fn test_cms_concurrent_mode_and_promotion_failures() {
+        let concurrent_mode_failure = GCPause {
+            is_full_gc: true,
+            attributes: vec!["concurrent mode failure".to_string()],
+            gc_type: "Allocation Failure".to_string(),
+            pause_time_seconds: 2.5,
+            ..Default::default()
+        };
+        let promotion_failed = GCPause {
+            is_full_gc: true,
+            attributes: vec!["promotion failed".to_string()],
+            gc_type: "Allocation Failure".to_string(),
+            pause_time_seconds: 1.5,
+            ..Default::default()
+        };
+        let mut flags = GCFlags {
+            collector: crate::glog::flags::Collector::CMS,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 32.0,
+            region_size_mb: 0.0,
+            target_pause_millis: 0,
+            max_direct_memory_gb: 40.0,
+            all_flags: vec![],
+        };
+        let pauses = vec![concurrent_mode_failure, promotion_failed];
+        let recs = generate_recommendations(
+            &flags,
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("1 concurrent mode failures adding 2.50 total seconds pause time"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("CMSInitiatingOccupancyFraction is not set so the cycle starts at about 92% of old gen, set -XX:CMSInitiatingOccupancyFraction=70 and add -XX:+UseCMSInitiatingOccupancyOnly"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("1 promotion failures adding 1.50 total seconds pause time"),
+            "was {recs}"
+        );
+        flags.all_flags = vec![
+            "-XX:CMSInitiatingOccupancyFraction=75".to_string(),
+            "-XX:+UseCMSInitiatingOccupancyOnly".to_string(),
+        ];
+        let recs = generate_recommendations(
+            &flags,
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("CMSInitiatingOccupancyFraction is set to 75, lower it to -XX:CMSInitiatingOccupancyFraction=65."),
+            "was {recs}"
+        );
+        //the last one wins just like on the java command line
+        flags
+            .all_flags
+            .push("-XX:-UseCMSInitiatingOccupancyOnly".to_string());
+        let recs = generate_recommendations(
+            &flags,
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("lower it to -XX:CMSInitiatingOccupancyFraction=65 and add -XX:+UseCMSInitiatingOccupancyOnly"),
+            "was {recs}"
+        );
+    }
+
     #[test]
     fn test_parallel_gc_detected() {
         let recs = generate_recommendations(
@@ -311,10 +1356,68 @@ mod tests {
                 all_flags: vec![],
             },
             &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert!(recs.contains("Parallel GC collector detected"));
     }
+    #[test]
+    fn test_parallel_gc_full_gc_causes() {
+        let old_gen_full = GCPause {
+            attributes: vec!["Ergonomics".to_string()],
+            gc_type: "Full GC".to_string(),
+            is_full_gc: true,
+            pause_time_seconds: 2.0,
+            occupancy: vec![SpaceOccupancy {
+                space: Space::Old,
+                before_bytes: 99,
+                after_bytes: 80,
+                capacity_bytes: 100,
+            }],
+            ..Default::default()
+        };
+        let ergonomics = GCPause {
+            attributes: vec!["Ergonomics".to_string()],
+            gc_type: "Full GC".to_string(),
+            is_full_gc: true,
+            pause_time_seconds: 1.0,
+            occupancy: vec![SpaceOccupancy {
+                space: Space::Old,
+                before_bytes: 50,
+                after_bytes: 20,
+                capacity_bytes: 100,
+            }],
+            ..Default::default()
+        };
+        let recs = generate_recommendations(
+            &GCFlags {
+                collector: crate::glog::flags::Collector::Parallel,
+                max_heap_size_gb: 32.0,
+                min_heap_size_gb: 32.0,
+                region_size_mb: 0.0,
+                target_pause_millis: 0,
+                max_direct_memory_gb: 40.0,
+                all_flags: vec![],
+            },
+            &vec![old_gen_full, ergonomics],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* 1 Full GCs started with the old generation at least 90% full adding 2.00 total seconds pause time, after collection old gen was still 80.00% full on average."),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* 1 Full GCs were triggered by Ergonomics while the old generation was only 50.00% full on average adding 1.00 total seconds pause time."),
+            "was {recs}"
+        );
+    }
+
     #[test]
     fn test_serial_gc_detected() {
         let recs = generate_recommendations(
@@ -328,6 +1431,10 @@ mod tests {
                 all_flags: vec![],
             },
             &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert!(recs.contains("Serial GC collector detected"));
@@ -345,11 +1452,44 @@ mod tests {
                 all_flags: vec![],
             },
             &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert!(recs.contains("ZGC GC collector detected"));
     }
 
+    #[test]
+    fn test_zgc_allocation_stalls() {
+        let stall = |stall_seconds| AllocationStall {
+            thread: "main".to_string(),
+            stall_seconds,
+            ..Default::default()
+        };
+        let recs = generate_recommendations(
+            &GCFlags {
+                collector: crate::glog::flags::Collector::ZGC,
+                max_heap_size_gb: 32.0,
+                min_heap_size_gb: 32.0,
+                region_size_mb: 0.0,
+                target_pause_millis: 0,
+                max_direct_memory_gb: 40.0,
+                all_flags: vec![],
+            },
+            &vec![],
+            &[],
+            &[stall(0.25), stall(0.5)],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("2 allocation stalls blocked application threads for 0.75 total seconds with a max stall of 0.50 seconds"),
+            "was {recs}"
+        );
+    }
+
     #[test]
     fn test_serial_shenandoah_detected() {
         let recs = generate_recommendations(
@@ -363,6 +1503,10 @@ mod tests {
                 all_flags: vec![],
             },
             &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert!(recs.contains("Shenandoah GC collector detected"));
@@ -381,6 +1525,10 @@ mod tests {
                 all_flags: vec![],
             },
             &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert!(recs.contains("Unknown GC collector detected"));
@@ -394,16 +1542,18 @@ mod tests {
                 gc_type: "G1 Evacuation Pause".to_string(),
                 is_full_gc: false,
                 pause_time_seconds: 30.00,
-                time_epoch: 1658739348,
+                time_epoch_micros: 1658739348000000,
                 heap_sizing: HeapSizing::None,
+                ..Default::default()
             },
             GCPause {
                 attributes: vec!["young".to_string(), "to-space exhausted".to_string()],
                 gc_type: "G1 Evacuation Pause".to_string(),
                 is_full_gc: false,
                 pause_time_seconds: 30.00,
-                time_epoch: 1658739348,
+                time_epoch_micros: 1658739348000000,
                 heap_sizing: HeapSizing::None,
+                ..Default::default()
             },
         ];
         let recs = generate_recommendations(
@@ -417,10 +1567,907 @@ mod tests {
                 all_flags: vec![],
             },
             &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
         );
         assert!(!recs.is_empty());
         assert_eq!("recommendations
 ---------------
 * 100.00% of GCs were to-space exhausted adding 60.00 total seconds pause time with a max pause time of 30.00 seconds, this means the max heap size was too small for use case during that time. Raising the heap size will help minimize the chance of this occuring again.", recs);
     }
+
+    fn timed_pause(user_seconds: f64, sys_seconds: f64, real_seconds: f64) -> GCPause {
+        GCPause {
+            gc_type: "G1 Evacuation Pause".to_string(),
+            pause_time_seconds: real_seconds,
+            cpu_times: Some(CpuTimes {
+                user_seconds,
+                sys_seconds,
+                real_seconds,
+            }),
+            gc_workers: Some(8),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cpu_time_recommendations() {
+        let pauses = vec![
+            timed_pause(4.76, 1.97, 4.14),
+            timed_pause(0.40, 0.30, 2.00),
+            timed_pause(0.01, 0.00, 0.01),
+        ];
+        let recs = generate_recommendations(
+            &GCFlags {
+                collector: crate::glog::flags::Collector::G1GC,
+                max_heap_size_gb: 32.0,
+                min_heap_size_gb: 32.0,
+                region_size_mb: 32.0,
+                target_pause_millis: 500,
+                max_direct_memory_gb: 40.0,
+                all_flags: vec![],
+            },
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* 2 pauses spent more than 30% of their user time in sys time with a max sys time of 1.97 seconds"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* 1 pauses took longer in real time than the user and sys time of all GC threads combined, adding 1.30 total seconds of waiting with a max real time of 2.00 seconds"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains(
+                "* GC threads averaged a parallelism of 0.84 (user / real) with 8 GC threads"
+            ),
+            "was {recs}"
+        );
+    }
+
+    #[test]
+    fn test_cpu_time_recommendations_healthy() {
+        let recs = generate_recommendations(
+            &GCFlags {
+                collector: crate::glog::flags::Collector::G1GC,
+                max_heap_size_gb: 32.0,
+                min_heap_size_gb: 32.0,
+                region_size_mb: 32.0,
+                target_pause_millis: 500,
+                max_direct_memory_gb: 40.0,
+                all_flags: vec![],
+            },
+            &vec![timed_pause(1.60, 0.02, 0.25)],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert_eq!(recs, "");
+    }
+
+    #[test]
+    fn test_cpu_time_recommendations_single_threaded_full_gc() {
+        let recs = generate_recommendations(
+            &GCFlags {
+                collector: crate::glog::flags::Collector::G1GC,
+                max_heap_size_gb: 32.0,
+                min_heap_size_gb: 32.0,
+                region_size_mb: 32.0,
+                target_pause_millis: 500,
+                max_direct_memory_gb: 40.0,
+                all_flags: vec![],
+            },
+            &vec![
+                timed_pause(1.60, 0.02, 0.25),
+                //jdk 8 G1 runs full gcs on a single thread
+                GCPause {
+                    is_full_gc: true,
+                    ..timed_pause(2.00, 0.00, 2.00)
+                },
+            ],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(!recs.contains("GC threads averaged"), "was {recs}");
+    }
+
+    fn phase(name: &str, millis: f64, children: Vec<G1Phase>) -> G1Phase {
+        G1Phase {
+            name: name.to_string(),
+            millis: Some(millis),
+            workers: None,
+            children,
+        }
+    }
+
+    #[test]
+    fn test_g1_phase_recommendations() {
+        let pauses = vec![GCPause {
+            gc_type: "G1 Evacuation Pause".to_string(),
+            pause_time_seconds: 0.2,
+            g1_phases: vec![
+                phase(
+                    "Parallel Time",
+                    120.0,
+                    vec![
+                        phase("Update RS", 60.0, vec![]),
+                        phase("Termination", 40.0, vec![]),
+                    ],
+                ),
+                phase(
+                    "Other",
+                    80.0,
+                    vec![
+                        phase("Ref Proc", 40.0, vec![]),
+                        phase("Free CSet", 30.0, vec![]),
+                    ],
+                ),
+            ],
+            ..Default::default()
+        }];
+        let recs = generate_recommendations(
+            &GCFlags {
+                collector: crate::glog::flags::Collector::G1GC,
+                max_heap_size_gb: 32.0,
+                min_heap_size_gb: 32.0,
+                region_size_mb: 32.0,
+                target_pause_millis: 500,
+                max_direct_memory_gb: 40.0,
+                all_flags: vec!["-XX:+ParallelRefProcEnabled".to_string()],
+            },
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* 1 pauses spent more than 20% of Parallel Time in Termination with a max of 33.33%"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains(
+                "* 1 pauses spent more than 25% of the pause in Update RS with a max of 30.00%"
+            ),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* 1 pauses spent more than 10% of the pause in Ref Proc with a max of 20.00%. -XX:+ParallelRefProcEnabled is already set"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains(
+                "* 1 pauses spent more than 10% of the pause in Free CSet with a max of 15.00%"
+            ),
+            "was {recs}"
+        );
+    }
+
+    #[test]
+    fn test_reference_recommendations() {
+        let pauses = vec![GCPause {
+            gc_type: "G1 Evacuation Pause".to_string(),
+            pause_time_seconds: 0.2,
+            references: vec![
+                ReferenceStats {
+                    kind: "SoftReference".to_string(),
+                    refs: Some(100),
+                    seconds: 0.001,
+                },
+                ReferenceStats {
+                    kind: "FinalReference".to_string(),
+                    refs: Some(25000),
+                    seconds: 0.05,
+                },
+            ],
+            ..Default::default()
+        }];
+        let recs = generate_recommendations(
+            &GCFlags {
+                collector: crate::glog::flags::Collector::G1GC,
+                max_heap_size_gb: 32.0,
+                min_heap_size_gb: 32.0,
+                region_size_mb: 32.0,
+                target_pause_millis: 500,
+                max_direct_memory_gb: 40.0,
+                all_flags: vec![],
+            },
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* reference processing took 25.50% of pause time adding 0.05 total seconds, most of it in FinalReference with 0.05 seconds. Set -XX:+ParallelRefProcEnabled"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* an average of 25000 FinalReferences were processed per pause"),
+            "was {recs}"
+        );
+    }
+
+    #[test]
+    fn test_mixed_gcs_skipped_recommendation() {
+        let pauses = vec![
+            GCPause {
+                gc_type: "G1 Evacuation Pause".to_string(),
+                pause_time_seconds: 0.1,
+                ergonomics: parse_ergonomics("54056.101: [G1Ergonomics (Mixed GCs) do not start mixed GCs, reason: reclaimable percentage not over threshold, candidate old regions: 46 regions, reclaimable: 77284736 bytes (1.80 %), threshold: 5.00 %]"),
+                ..Default::default()
+            },
+            GCPause {
+                gc_type: "Full GC".to_string(),
+                is_full_gc: true,
+                pause_time_seconds: 3.0,
+                ..Default::default()
+            },
+        ];
+        let recs = generate_recommendations(
+            &GCFlags {
+                collector: crate::glog::flags::Collector::G1GC,
+                max_heap_size_gb: 32.0,
+                min_heap_size_gb: 32.0,
+                region_size_mb: 32.0,
+                target_pause_millis: 500,
+                max_direct_memory_gb: 40.0,
+                all_flags: vec![],
+            },
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* mixed GCs were skipped 1 times because the reclaimable percentage of the old generation was below -XX:G1HeapWastePercent=5 and there were 1 Full GCs"),
+            "was {recs}"
+        );
+    }
+
+    #[test]
+    fn test_marking_cycle_recommendations() {
+        let heap = |before_bytes, after_bytes| SpaceOccupancy {
+            space: Space::Heap,
+            before_bytes,
+            after_bytes,
+            capacity_bytes: 1000,
+        };
+        let pauses = vec![
+            GCPause {
+                gc_type: "G1 Humongous Allocation".to_string(),
+                attributes: vec!["young".to_string(), "initial-mark".to_string()],
+                time_epoch_micros: 1_000_000,
+                pause_time_seconds: 0.02,
+                occupancy: vec![heap(650, 600)],
+                ..Default::default()
+            },
+            GCPause {
+                gc_type: "Full GC".to_string(),
+                is_full_gc: true,
+                time_epoch_micros: 2_000_000,
+                pause_time_seconds: 3.0,
+                occupancy: vec![heap(990, 400)],
+                ..Default::default()
+            },
+        ];
+        let flags = |all_flags| GCFlags {
+            collector: crate::glog::flags::Collector::G1GC,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 32.0,
+            region_size_mb: 32.0,
+            target_pause_millis: 500,
+            max_direct_memory_gb: 40.0,
+            all_flags,
+        };
+        let recs = generate_recommendations(
+            &flags(vec![]),
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* 1 G1 marking cycles were interrupted by a Full GC during concurrent mark, the cycles started too late to finish before the heap filled up, the heap grew by up to 39% of its capacity while marking. Lower -XX:InitiatingHeapOccupancyPercent from 45 to 40"),
+            "was {recs}"
+        );
+        let recs = generate_recommendations(
+            &flags(vec!["-XX:InitiatingHeapOccupancyPercent=60".to_string()]),
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("Lower -XX:InitiatingHeapOccupancyPercent from 60 to 50"),
+            "was {recs}"
+        );
+        let recs = generate_recommendations(
+            &flags(vec!["-XX:InitiatingHeapOccupancyPercent=5".to_string()]),
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            !recs.contains("Lower -XX:InitiatingHeapOccupancyPercent"),
+            "was {recs}"
+        );
+    }
+
+    #[test]
+    fn test_class_histogram_recommendations() {
+        let full_gc = |arrow_bytes: i64, byte_array_bytes: i64| GCPause {
+            gc_type: "Full GC".to_string(),
+            is_full_gc: true,
+            pause_time_seconds: 3.0,
+            histogram_after: Some(ClassHistogram {
+                classes: vec![
+                    ClassCount {
+                        class_name: "org.apache.arrow.memory.ArrowBuf".to_string(),
+                        instances: arrow_bytes / 64,
+                        bytes: arrow_bytes,
+                    },
+                    ClassCount {
+                        class_name: "[B".to_string(),
+                        instances: byte_array_bytes / 1024,
+                        bytes: byte_array_bytes,
+                    },
+                ],
+                total_instances: 0,
+                total_bytes: arrow_bytes + byte_array_bytes,
+            }),
+            ..Default::default()
+        };
+        let mb = 1024 * 1024;
+        let mut pauses = vec![
+            full_gc(100 * mb, 2000 * mb),
+            full_gc(200 * mb, 1000 * mb),
+            full_gc(400 * mb, 3000 * mb),
+        ];
+        let flags = GCFlags {
+            collector: crate::glog::flags::Collector::G1GC,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 32.0,
+            region_size_mb: 32.0,
+            target_pause_millis: 500,
+            max_direct_memory_gb: 40.0,
+            all_flags: vec![],
+        };
+        let recs = generate_recommendations(
+            &flags,
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* org.apache.arrow.memory.ArrowBuf grew from 100.00 mb to 400.00 mb without going down across 3 Full GCs, it is a leak suspect."),
+            "was {recs}"
+        );
+        assert!(!recs.contains("* [B grew"), "was {recs}");
+        assert!(
+            recs.contains("* Arrow and Netty buffer objects that keep growing"),
+            "was {recs}"
+        );
+        pauses.remove(0);
+        let recs = generate_recommendations(
+            &flags,
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(!recs.contains("leak suspect"), "was {recs}");
+    }
+
+    #[test]
+    fn test_safepoint_recommendations() {
+        let safepoint = |stopped_seconds, ttsp_seconds| Safepoint {
+            stopped_seconds,
+            ttsp_seconds,
+            ..Default::default()
+        };
+        let pauses = vec![GCPause {
+            gc_type: "G1 Evacuation Pause".to_string(),
+            pause_time_seconds: 1.0,
+            ..Default::default()
+        }];
+        let flags = GCFlags {
+            collector: crate::glog::flags::Collector::G1GC,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 32.0,
+            region_size_mb: 32.0,
+            target_pause_millis: 500,
+            max_direct_memory_gb: 40.0,
+            all_flags: vec![],
+        };
+        let recs = generate_recommendations(
+            &flags,
+            &pauses,
+            &[
+                safepoint(1.0, 0.002),
+                safepoint(2.5, 1.25),
+                safepoint(0.1, 0.06),
+            ],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains(
+                "* 2 safepoints took more than 50 ms to reach with the slowest at 1.250 seconds"
+            ),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* 2.60 seconds (72.22%) of the time the application threads were stopped was outside of GC pauses"),
+            "was {recs}"
+        );
+        let recs = generate_recommendations(
+            &flags,
+            &pauses,
+            &[safepoint(1.1, 0.001)],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(!recs.contains("safepoint"), "was {recs}");
+        let jdk17 = JvmInfo {
+            major_version: 17,
+            ..Default::default()
+        };
+        let recs = generate_recommendations(
+            &flags,
+            &pauses,
+            &[safepoint(3.0, 0.001)],
+            &[],
+            &jdk17,
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("Add -Xlog:safepoint+stats=debug to see which operations they were."),
+            "was {recs}"
+        );
+        assert!(!recs.contains("UseBiasedLocking"), "was {recs}");
+    }
+
+    #[test]
+    fn test_tenuring_recommendations() {
+        let young = |new_threshold, survivor_bytes| GCPause {
+            gc_type: "Allocation Failure".to_string(),
+            pause_time_seconds: 0.05,
+            tenuring: Some(TenuringDistribution {
+                desired_survivor_bytes: 1000,
+                new_threshold,
+                max_threshold: 15,
+                ages: vec![AgeBytes {
+                    age: 1,
+                    bytes: survivor_bytes,
+                    total_bytes: survivor_bytes,
+                }],
+            }),
+            ..Default::default()
+        };
+        let mut pauses = vec![
+            young(1, 1500),
+            young(1, 1200),
+            young(4, 800),
+            young(15, 100),
+        ];
+        pauses.push(GCPause {
+            gc_type: "Ergonomics".to_string(),
+            is_full_gc: true,
+            pause_time_seconds: 2.0,
+            ..Default::default()
+        });
+        let flags = |collector, all_flags| GCFlags {
+            collector,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 32.0,
+            region_size_mb: 32.0,
+            target_pause_millis: 500,
+            max_direct_memory_gb: 40.0,
+            all_flags,
+        };
+        let recs = generate_recommendations(
+            &flags(
+                crate::glog::flags::Collector::Parallel,
+                vec!["-XX:SurvivorRatio=6".to_string()],
+            ),
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* the tenuring threshold dropped to 1 in 2 of 4 young pauses, objects that survive a single young GC are promoted to the old generation before they have a chance to die and have to be cleaned up by the 1 mixed and Full GCs in this log. Make the survivor spaces bigger by lowering -XX:SurvivorRatio from 6 or raising -XX:TargetSurvivorRatio from 50."),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* survivors were over the desired survivor size in 2 of 4 young pauses"),
+            "was {recs}"
+        );
+        let recs = generate_recommendations(
+            &flags(crate::glog::flags::Collector::G1GC, vec![]),
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("raise -XX:TargetSurvivorRatio from 50 to let it fill further"),
+            "was {recs}"
+        );
+        let recs = generate_recommendations(
+            &flags(crate::glog::flags::Collector::Parallel, vec![]),
+            &pauses.split_off(2),
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(!recs.contains("tenuring threshold"), "was {recs}");
+    }
+
+    #[test]
+    fn test_full_gc_cause_recommendations() {
+        let full_gc = |cause: &str, pause_time_seconds| GCPause {
+            gc_type: "Full GC".to_string(),
+            is_full_gc: true,
+            attributes: vec![cause.to_string()],
+            pause_time_seconds,
+            occupancy: vec![SpaceOccupancy {
+                space: Space::Metaspace,
+                before_bytes: 90 * 1024 * 1024,
+                after_bytes: 80 * 1024 * 1024,
+                capacity_bytes: 1024 * 1024 * 1024,
+            }],
+            ..Default::default()
+        };
+        let pauses = vec![
+            full_gc("System.gc()", 1.0),
+            full_gc("System.gc()", 2.0),
+            full_gc("Metadata GC Threshold", 0.5),
+            full_gc("Allocation Failure", 4.0),
+            full_gc("Heap Dump Initiated GC", 3.0),
+            full_gc("Heap Inspection Initiated GC", 1.5),
+            full_gc("GCLocker Initiated GC", 2.5),
+        ];
+        let flags = |collector| GCFlags {
+            collector,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 32.0,
+            region_size_mb: 32.0,
+            target_pause_millis: 500,
+            max_direct_memory_gb: 40.0,
+            all_flags: vec![],
+        };
+        let recs = generate_recommendations(
+            &flags(crate::glog::flags::Collector::G1GC),
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("* 2 Full GCs were caused by System.gc() adding 3.00 total seconds pause time with a max pause time of 2.00 seconds. Something is calling System.gc()"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("Add -XX:+ExplicitGCInvokesConcurrent"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* 1 Full GCs were caused by Metadata GC Threshold adding 0.50 total seconds pause time with a max pause time of 0.50 seconds. Metaspace reached -XX:MetaspaceSize=20.80 mb and the JVM ran a Full GC to unload classes before growing it. Raise -XX:MetaspaceSize to at least 90.00 mb"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* 1 Full GCs were caused by Allocation Failure adding 4.00 total seconds pause time with a max pause time of 4.00 seconds, 14.29% of all GCs. This means the max heap size was too small"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* 1 Full GCs were caused by Heap Inspection Initiated GC"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* 1 Full GCs were caused by GCLocker Initiated GC"),
+            "was {recs}"
+        );
+        assert!(!recs.contains("Heap Dump Initiated GC"), "was {recs}");
+        let recs = generate_recommendations(
+            &flags(crate::glog::flags::Collector::Parallel),
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(recs.contains("Add -XX:+DisableExplicitGC"), "was {recs}");
+        assert!(!recs.contains("caused by Allocation Failure"), "was {recs}");
+        //a full gc without a cause
+        let pauses = vec![GCPause {
+            gc_type: "Full GC".to_string(),
+            is_full_gc: true,
+            pause_time_seconds: 1.0,
+            ..Default::default()
+        }];
+        let recs = generate_recommendations(
+            &flags(crate::glog::flags::Collector::G1GC),
+            &pauses,
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(recs.contains("Add -XX:+PrintGCDetails"), "was {recs}");
+        let unified = JvmInfo {
+            unified_logging: true,
+            ..Default::default()
+        };
+        let recs = generate_recommendations(
+            &flags(crate::glog::flags::Collector::G1GC),
+            &pauses,
+            &[],
+            &[],
+            &unified,
+            &MemoryStats::default(),
+        );
+        assert!(
+            recs.contains("Add -Xlog:gc* to log the cause"),
+            "was {recs}"
+        );
+        assert!(!recs.contains("-XX:+PrintGCDetails"), "was {recs}");
+        let recs = generate_recommendations(
+            &flags(crate::glog::flags::Collector::Shenandoah),
+            &pauses,
+            &[],
+            &[],
+            &unified,
+            &MemoryStats::default(),
+        );
+        assert!(!recs.contains("caused by Unknown"), "was {recs}");
+        assert!(
+            recs.contains("1 Full GCs after Shenandoah gave up"),
+            "was {recs}"
+        );
+        let requested: Vec<GCPause> = ["System.gc()", "Heap Dump Initiated GC"]
+            .iter()
+            .map(|cause| GCPause {
+                gc_type: "Full GC".to_string(),
+                attributes: vec![cause.to_string()],
+                is_full_gc: true,
+                pause_time_seconds: 1.0,
+                ..Default::default()
+            })
+            .collect();
+        let recs = generate_recommendations(
+            &flags(crate::glog::flags::Collector::Shenandoah),
+            &requested,
+            &[],
+            &[],
+            &unified,
+            &MemoryStats::default(),
+        );
+        assert!(!recs.contains("Shenandoah gave up"), "was {recs}");
+        assert!(
+            recs.contains("1 Full GCs were caused by System.gc()"),
+            "was {recs}"
+        );
+    }
+
+    #[test]
+    fn test_jvm_recommendations() {
+        let mut flags = GCFlags {
+            collector: crate::glog::flags::Collector::G1GC,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 32.0,
+            region_size_mb: 32.0,
+            target_pause_millis: 500,
+            max_direct_memory_gb: 40.0,
+            all_flags: vec![],
+        };
+        let old = parse_jvm_info("OpenJDK 64-Bit Server VM (25.31-b07) for linux-amd64 JRE (1.8.0_31-b07), built on Jan 21 2015 13:24:22 by \"mockbuild\" with gcc 4.4.7 20120313 (Red Hat 4.4.7-11)".to_string());
+        let recs =
+            generate_recommendations(&flags, &vec![], &[], &[], &old, &MemoryStats::default());
+        assert!(
+            recs.contains("* G1 is running on Red Hat 1.8.0_31-b07, G1 before 8u40"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* Red Hat 1.8.0_31-b07 predates container support (added in 8u191)"),
+            "was {recs}"
+        );
+        flags.collector = crate::glog::flags::Collector::CMS;
+        let recs =
+            generate_recommendations(&flags, &vec![], &[], &[], &old, &MemoryStats::default());
+        assert!(!recs.contains("G1 is running on"), "was {recs}");
+        assert!(recs.contains("predates container support"), "was {recs}");
+        let current = parse_jvm_info("OpenJDK 64-Bit Server VM (25.332-b09) for linux-amd64 JRE (1.8.0_332-b09), built on Apr 20 2022 08:18:57 by \"openjdk\" with gcc 4.4.7 20120313 (Red Hat 4.4.7-23)".to_string());
+        let recs =
+            generate_recommendations(&flags, &vec![], &[], &[], &current, &MemoryStats::default());
+        assert!(!recs.contains("predates container support"), "was {recs}");
+        let recs = generate_recommendations(
+            &flags,
+            &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(!recs.contains("predates container support"), "was {recs}");
+    }
+
+    #[test]
+    fn test_memory_recommendations() {
+        let flags = GCFlags {
+            collector: crate::glog::flags::Collector::G1GC,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 32.0,
+            region_size_mb: 32.0,
+            target_pause_millis: 500,
+            max_direct_memory_gb: 40.0,
+            all_flags: vec![],
+        };
+        let memory = parse_memory(
+            "Memory: 64k page, physical 16000000k(1500000k free), swap 8000000k(7000000k free)"
+                .to_string(),
+        );
+        let recs =
+            generate_recommendations(&flags, &vec![], &[], &[], &JvmInfo::default(), &memory);
+        assert!(
+            recs.contains("* swap is enabled with 7.63 gb (6.68 gb free)"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* only 1.43 gb of 15.26 gb (9.38%) system RAM was free"),
+            "was {recs}"
+        );
+        assert!(
+            recs.contains("* the page size was 64.00 kb instead of the usual 4 kb"),
+            "was {recs}"
+        );
+        let memory = parse_memory(
+            "Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)".to_string(),
+        );
+        let recs =
+            generate_recommendations(&flags, &vec![], &[], &[], &JvmInfo::default(), &memory);
+        assert!(!recs.contains("swap"), "was {recs}");
+        assert!(!recs.contains("was free"), "was {recs}");
+        assert!(!recs.contains("page size"), "was {recs}");
+    }
+
+    #[test]
+    fn test_flag_recommendations() {
+        let flags = |collector, all_flags: &str| GCFlags {
+            collector,
+            max_heap_size_gb: 32.0,
+            min_heap_size_gb: 8.0,
+            region_size_mb: 32.0,
+            target_pause_millis: 50,
+            max_direct_memory_gb: 40.0,
+            all_flags: all_flags.split(' ').map(|f| f.to_string()).collect(),
+        };
+        let young = |pause_time_seconds| GCPause {
+            gc_type: "G1 Evacuation Pause".to_string(),
+            pause_time_seconds,
+            ..Default::default()
+        };
+        let mut pauses: Vec<GCPause> = (0..10).map(|i| young(0.1 + i as f64 * 0.01)).collect();
+        let recs = generate_recommendations(
+            &flags(
+                crate::glog::flags::Collector::G1GC,
+                "-XX:+UseG1GC -XX:+UseParallelGC -XX:NewSize=1073741824 -XX:CMSInitiatingOccupancyFraction=70 -XX:G1HeapRegionSize=32000000 -XX:+AlwaysPreTouch -XX:+DisableExplicitGC -XX:+ExplicitGCInvokesConcurrent -XX:+UseGCLogFileRotation -XX:+HeapDumpOnOutOfMemoryError -XX:MaxGCPauseMillis=50 -XX:MaxGCPauseMillis=50 -XX:ConcGCThreads=many",
+            ),
+            &pauses,
+            &[], &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        for expected in [
+            "* more than one collector was selected with -XX:+UseG1GC -XX:+UseParallelGC",
+            "* flags for a collector other than G1GC are set (CMSInitiatingOccupancyFraction)",
+            "* the young generation is fixed with -Xmn",
+            "* -XX:MaxGCPauseMillis=50 but the median young pause was 150.00 ms",
+            "* -XX:G1HeapRegionSize=32000000 is not a power of two",
+            "* -XX:+AlwaysPreTouch is on but the initial heap of 8.00 gb is smaller than the max heap of 32.00 gb",
+            "* both -XX:+DisableExplicitGC and -XX:+ExplicitGCInvokesConcurrent are set",
+            "* -XX:+UseGCLogFileRotation is on without -XX:NumberOfGCLogFiles",
+            "* -XX:+HeapDumpOnOutOfMemoryError is on without -XX:HeapDumpPath",
+            "* MaxGCPauseMillis set more than once",
+            "* -XX:ConcGCThreads=many could not be read",
+        ] {
+            assert!(recs.contains(expected), "{expected} was {recs}");
+        }
+        let recs = generate_recommendations(
+            &flags(
+                crate::glog::flags::Collector::G1GC,
+                "-XX:+UseG1GC -XX:MaxNewSize=10301210624 -XX:G1HeapRegionSize=33554432 -XX:+UseGCLogFileRotation -XX:NumberOfGCLogFiles=10 -XX:+HeapDumpOnOutOfMemoryError -XX:HeapDumpPath=/opt/dremio/data",
+            ),
+            &pauses.split_off(5),
+            &[], &[],
+            &JvmInfo::default(),
+            &MemoryStats::default(),
+        );
+        assert!(!recs.contains("-XX:"), "was {recs}");
+    }
+
+    #[test]
+    fn test_memory_budget_recommendations() {
+        let memory = |line: &str| parse_memory(line.to_string());
+        let flags = |direct: &str, memory: &MemoryStats| {
+            parse_gc_flags(
+                format!("CommandLine flags: -XX:MaxDirectMemorySize={direct} -XX:MaxHeapSize=17179869184 -XX:+UseG1GC"),
+                memory.physical_memory_bytes,
+            )
+        };
+        let no_swap =
+            memory("Memory: 4k page, physical 128000000k(127996468k free), swap 0k(0k free)");
+        let recs = generate_recommendations(
+            &flags("120259084288", &no_swap),
+            &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &no_swap,
+        );
+        assert!(
+            recs.contains("* the JVM can commit up to 128.97 gb (16.00 gb heap, 112.00 gb direct memory and about 996.00 mb of metaspace, code cache and thread stacks) but the host only has 122.07 gb of RAM and 0 bytes of swap."),
+            "was {recs}"
+        );
+        let swap = memory(
+            "Memory: 4k page, physical 128000000k(127996468k free), swap 16000000k(16000000k free)",
+        );
+        let recs = generate_recommendations(
+            &flags("120259084288", &swap),
+            &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &swap,
+        );
+        assert!(
+            recs.contains("which is more than the 122.07 gb of RAM on the host, the rest would come from swap"),
+            "was {recs}"
+        );
+        let recs = generate_recommendations(
+            &flags("107374182400", &no_swap),
+            &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &no_swap,
+        );
+        assert!(
+            recs.contains("which leaves less than 10% of the 122.07 gb of RAM for the OS"),
+            "was {recs}"
+        );
+        let recs = generate_recommendations(
+            &flags("8589934592", &no_swap),
+            &vec![],
+            &[],
+            &[],
+            &JvmInfo::default(),
+            &no_swap,
+        );
+        assert!(!recs.contains("the JVM can commit"), "was {recs}");
+    }
 }
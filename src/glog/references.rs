@@ -0,0 +1,240 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// parsing for the reference processing times written with -XX:+PrintReferenceGC
+//
+// 2022-08-24T01:54:40.318+0000: 190270.071: [SoftReference, 24521 refs, 0.0035689 secs]
+// 2022-08-24T01:54:40.324+0000: 190270.076: [PhantomReference, 0 refs, 12778 refs, 0.0004892 secs]
+// 2022-08-24T01:54:40.324+0000: 190270.077: [JNI Weak Reference, 0.0000910 secs]
+
+use std::str::FromStr;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use super::pauses::GCPause;
+
+// the order the jvm processes them in, which is also the order they are reported in
+pub const REFERENCE_KINDS: [&str; 5] = [
+    "SoftReference",
+    "WeakReference",
+    "FinalReference",
+    "PhantomReference",
+    "JNI Weak Reference",
+];
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ReferenceStats {
+    pub kind: String,
+    //None for JNI Weak Reference which is not counted
+    pub refs: Option<u64>,
+    pub seconds: f64,
+}
+
+// reads SoftReference, 24521 refs, 0.0035689 secs. PhantomReference has a second count for the
+// cleaners processed with it so all the counts are added up
+fn parse_reference_entry(entry: &str) -> Option<ReferenceStats> {
+    let mut parts = entry.split(", ");
+    let kind = parts.next()?;
+    if !REFERENCE_KINDS.contains(&kind) {
+        return None;
+    }
+    let mut refs = None;
+    let mut seconds = None;
+    for part in parts {
+        if let Some(count) = part.strip_suffix(" refs") {
+            refs = Some(refs.unwrap_or(0) + u64::from_str(count.trim()).ok()?);
+        } else if let Some(secs) = part.strip_suffix(" secs") {
+            seconds = Some(f64::from_str(secs.trim()).ok()?);
+        }
+    }
+    Some(ReferenceStats {
+        kind: kind.to_string(),
+        refs,
+        seconds: seconds?,
+    })
+}
+
+// finds every reference entry in the text of a pause
+pub fn parse_references(text: &str) -> Vec<ReferenceStats> {
+    let mut references = vec![];
+    for piece in text.split('[').skip(1) {
+        let entry = match piece.split_once(']') {
+            Some((entry, _)) => entry,
+            None => continue,
+        };
+        if let Some(stats) = parse_reference_entry(entry) {
+            references.push(stats);
+        }
+    }
+    references
+}
+
+#[derive(Tabled)]
+struct ReferenceRow {
+    #[tabled(rename = "Reference")]
+    kind: String,
+    #[tabled(rename = "Pauses")]
+    pauses: i64,
+    #[tabled(display_with = "format_refs", rename = "Total Refs")]
+    total_refs: Option<u64>,
+    #[tabled(display_with = "format_refs", rename = "Max Refs")]
+    max_refs: Option<u64>,
+    #[tabled(display_with = "format_seconds", rename = "Total Secs")]
+    total_seconds: f64,
+    #[tabled(display_with = "format_seconds", rename = "Max Secs")]
+    max_seconds: f64,
+}
+
+fn format_refs(refs: &Option<u64>) -> String {
+    match refs {
+        Some(refs) => refs.to_string(),
+        None => "n/a".to_string(),
+    }
+}
+
+fn format_seconds(seconds: &f64) -> String {
+    format!("{seconds:.4}")
+}
+
+// the total reference processing time of the pauses that logged it and the total time of those
+// pauses
+pub fn get_reference_seconds(pauses: &[GCPause]) -> (f64, f64) {
+    let mut reference_seconds = 0.0;
+    let mut pause_seconds = 0.0;
+    for pause in pauses {
+        if pause.references.is_empty() {
+            continue;
+        }
+        reference_seconds += pause.references.iter().map(|r| r.seconds).sum::<f64>();
+        pause_seconds += pause.pause_time_seconds;
+    }
+    (reference_seconds, pause_seconds)
+}
+
+// counts and times summed up for each type of reference across all pauses
+pub fn generate_reference_table(pauses: &[GCPause]) -> String {
+    let mut rows = vec![];
+    for kind in REFERENCE_KINDS {
+        let mut row = ReferenceRow {
+            kind: kind.to_string(),
+            pauses: 0,
+            total_refs: None,
+            max_refs: None,
+            total_seconds: 0.0,
+            max_seconds: 0.0,
+        };
+        for stats in pauses
+            .iter()
+            .flat_map(|p| p.references.iter())
+            .filter(|r| r.kind == kind)
+        {
+            row.pauses += 1;
+            if let Some(refs) = stats.refs {
+                row.total_refs = Some(row.total_refs.unwrap_or(0) + refs);
+                row.max_refs = row.max_refs.max(Some(refs));
+            }
+            row.total_seconds += stats.seconds;
+            row.max_seconds = row.max_seconds.max(stats.seconds);
+        }
+        if row.pauses > 0 {
+            rows.push(row);
+        }
+    }
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    let (reference_seconds, pause_seconds) = get_reference_seconds(pauses);
+    let mut table = Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string();
+    if pause_seconds > 0.0 {
+        table.push_str(&format!(
+            "reference processing took {:.2}% of the time of the pauses that logged it\n",
+            reference_seconds / pause_seconds * 100.0
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::pauses::GCPause;
+
+    use super::{generate_reference_table, parse_references, ReferenceStats};
+
+    const REFERENCES: &str = "2022-08-24T01:54:40.318+0000: 190270.071: [SoftReference, 24521 refs, 0.0035689 secs]2022-08-24T01:54:40.322+0000: 190270.074: [WeakReference, 24515 refs, 0.0017063 secs]2022-08-24T01:54:40.323+0000: 190270.076: [FinalReference, 1360 refs, 0.0003365 secs]2022-08-24T01:54:40.324+0000: 190270.076: [PhantomReference, 0 refs, 12778 refs, 0.0004892 secs]2022-08-24T01:54:40.324+0000: 190270.077: [JNI Weak Reference, 0.0000910 secs] 16364M->4966M(16384M), 3.6564555 secs]";
+
+    #[test]
+    fn test_parse_references() {
+        let references = parse_references(REFERENCES);
+        assert_eq!(references.len(), 5);
+        assert_eq!(
+            references[0],
+            ReferenceStats {
+                kind: "SoftReference".to_string(),
+                refs: Some(24521),
+                seconds: 0.0035689,
+            }
+        );
+        assert_eq!(references[3].refs, Some(12778));
+        assert_eq!(references[4].kind, "JNI Weak Reference");
+        assert_eq!(references[4].refs, None);
+        assert_eq!(references[4].seconds, 0.000091);
+        assert!(parse_references("[Eden: 0.0B(800.0M)->0.0B(8064.0M)]").is_empty());
+    }
+
+    #[test]
+    fn test_generate_reference_table() {
+        let pauses = vec![
+            GCPause {
+                pause_time_seconds: 0.1,
+                references: parse_references(REFERENCES),
+                ..Default::default()
+            },
+            GCPause {
+                pause_time_seconds: 0.1,
+                references: vec![ReferenceStats {
+                    kind: "FinalReference".to_string(),
+                    refs: Some(40),
+                    seconds: 0.0196635,
+                }],
+                ..Default::default()
+            },
+            GCPause {
+                pause_time_seconds: 5.0,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            generate_reference_table(&pauses),
+            "+--------------------+--------+------------+----------+------------+----------+
+| Reference          | Pauses | Total Refs | Max Refs | Total Secs | Max Secs |
++--------------------+--------+------------+----------+------------+----------+
+| SoftReference      |   1    |   24521    |  24521   |   0.0036   |  0.0036  |
++--------------------+--------+------------+----------+------------+----------+
+| WeakReference      |   1    |   24515    |  24515   |   0.0017   |  0.0017  |
++--------------------+--------+------------+----------+------------+----------+
+| FinalReference     |   2    |    1400    |   1360   |   0.0200   |  0.0197  |
++--------------------+--------+------------+----------+------------+----------+
+| PhantomReference   |   1    |   12778    |  12778   |   0.0005   |  0.0005  |
++--------------------+--------+------------+----------+------------+----------+
+| JNI Weak Reference |   1    |    n/a     |   n/a    |   0.0001   |  0.0001  |
++--------------------+--------+------------+----------+------------+----------+
+reference processing took 12.93% of the time of the pauses that logged it
+"
+        );
+        assert_eq!(generate_reference_table(&[]), "");
+    }
+}
@@ -0,0 +1,352 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// stitching the files written by -XX:+UseGCLogFileRotation back into one timeline
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+use time::{format_description, PrimitiveDateTime, UtcOffset};
+
+use crate::human::{human_duration, human_time};
+
+use super::exec::ParsedLog;
+
+// the jvm rotates to the next file as soon as one is full, so a longer silence between the
+// end of one file and the start of the next means the file in between is gone
+const ROTATION_GAP_SECONDS: i64 = 60;
+
+#[derive(Debug)]
+pub struct NoLogFilesError {
+    pub location: String,
+}
+
+impl Error for NoLogFilesError {}
+
+impl fmt::Display for NoLogFilesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no gc log files found in {}", self.location)
+    }
+}
+
+// reads the time out of the header the jvm writes at the top of every rotated file, it is in
+// the local time of the host and has no zone
+// 2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log.4
+pub fn parse_log_created_time(line: &str) -> Option<PrimitiveDateTime> {
+    let (datetime, _) = line.split_once(" GC log file created ")?;
+    let format = format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second]").ok()?;
+    PrimitiveDateTime::parse(datetime.trim(), &format).ok()
+}
+
+// the offset comes from the date stamps in the same file or --tz when it has none
+pub fn created_epoch_micros(created_time: PrimitiveDateTime, offset: UtcOffset) -> i64 {
+    created_time.assume_offset(offset).unix_timestamp() * 1_000_000
+}
+
+pub fn is_gc_log_name(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase().contains("gc"))
+        .unwrap_or_default()
+}
+
+// directories are replaced with the gc log files inside of them, only files with gc in
+// the name are picked up so the rest of a diagnostic bundle is skipped
+pub fn expand_log_files(file_names: &[String]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut paths = vec![];
+    for file_name in file_names {
+        let path = PathBuf::from(file_name);
+        if !path.is_dir() {
+            paths.push(path);
+            continue;
+        }
+        let mut files = vec![];
+        for entry in fs::read_dir(&path)? {
+            let entry_path = entry?.path();
+            if entry_path.is_file() && is_gc_log_name(&entry_path) {
+                files.push(entry_path);
+            }
+        }
+        if files.is_empty() {
+            return Err(Box::new(NoLogFilesError {
+                location: file_name.to_string(),
+            }));
+        }
+        files.sort();
+        paths.extend(files);
+    }
+    Ok(paths)
+}
+
+// the number the jvm appends to a rotated file, gc.log.3 and gc.log.3.current are both 3
+pub fn rotation_index(file_name: &str) -> i64 {
+    let name = file_name.trim_end_matches(".current");
+    name.rsplit_once('.')
+        .and_then(|(_, index)| index.parse().ok())
+        .unwrap_or_default()
+}
+
+fn first_event_epoch(log: &ParsedLog) -> i64 {
+    log.pauses
+        .iter()
+        .map(|p| p.time_epoch_micros)
+        .chain(log.concurrent_phases.iter().map(|p| p.time_epoch_micros))
+        .filter(|epoch| *epoch > 0)
+        .min()
+        .unwrap_or_default()
+}
+
+fn last_event_epoch(log: &ParsedLog) -> i64 {
+    log.pauses
+        .iter()
+        .map(|p| p.time_epoch_micros)
+        .chain(log.concurrent_phases.iter().map(|p| p.time_epoch_micros))
+        .max()
+        .unwrap_or_default()
+}
+
+fn start_epoch(log: &ParsedLog) -> i64 {
+    if log.created_epoch_micros > 0 {
+        return log.created_epoch_micros;
+    }
+    first_event_epoch(log)
+}
+
+// orders by the created header or first timestamp, the rotation number breaks ties for
+// logs without any times in them
+pub fn order_logs(mut logs: Vec<ParsedLog>) -> Vec<ParsedLog> {
+    logs.sort_by_key(|log| {
+        (
+            start_epoch(log),
+            rotation_index(&log.file_name),
+            log.file_name.to_string(),
+        )
+    });
+    logs
+}
+
+pub struct LogGap {
+    pub before_file: String,
+    pub after_file: String,
+    pub start_epoch: i64,
+    pub end_epoch: i64,
+}
+
+// only a file with a created header says when the rotation happened, the first event of a file
+// without one can come long after it when the jvm was quiet
+pub fn find_gaps(logs: &[ParsedLog]) -> Vec<LogGap> {
+    let mut gaps = vec![];
+    for pair in logs.windows(2) {
+        let end = last_event_epoch(&pair[0]);
+        let start = pair[1].created_epoch_micros;
+        if end > 0 && start > 0 && start - end > ROTATION_GAP_SECONDS * 1_000_000 {
+            gaps.push(LogGap {
+                before_file: pair[0].file_name.to_string(),
+                after_file: pair[1].file_name.to_string(),
+                start_epoch: end,
+                end_epoch: start,
+            });
+        }
+    }
+    gaps
+}
+
+#[derive(Tabled)]
+struct LogFileRow {
+    #[tabled(rename = "File")]
+    file_name: String,
+    #[tabled(rename = "Start")]
+    start: String,
+    #[tabled(rename = "End")]
+    end: String,
+    #[tabled(rename = "Pauses")]
+    pauses: usize,
+}
+
+fn format_epoch(epoch_micros: i64, offset: UtcOffset) -> String {
+    if epoch_micros == 0 {
+        return "unknown".to_string();
+    }
+    human_time(epoch_micros, offset)
+}
+
+// lists the files in the order they were stitched together followed by any gaps between them
+pub fn generate_log_files_table(logs: &[ParsedLog], offset: UtcOffset) -> String {
+    let rows: Vec<LogFileRow> = logs
+        .iter()
+        .map(|log| LogFileRow {
+            file_name: log.file_name.to_string(),
+            start: format_epoch(start_epoch(log), offset),
+            end: format_epoch(last_event_epoch(log), offset),
+            pauses: log.pauses.len(),
+        })
+        .collect();
+    let mut output = vec![Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()];
+    for gap in find_gaps(logs) {
+        output.push(format!(
+            "* no gc events for {} between {} ({}) and {} ({}), a rotated log file may have been overwritten or is missing",
+            human_duration((gap.end_epoch - gap.start_epoch) / 1000),
+            format_epoch(gap.start_epoch, offset),
+            gap.before_file,
+            format_epoch(gap.end_epoch, offset),
+            gap.after_file,
+        ));
+    }
+    output.join("\n")
+}
+
+// every rotated file repeats the jvm, memory and flags headers so the first one found wins
+pub fn merge_logs(logs: Vec<ParsedLog>) -> Option<ParsedLog> {
+    let mut logs = logs.into_iter();
+    let mut merged = logs.next()?;
+    for log in logs {
+        if merged.gc_flags.all_flags.is_empty() && !log.gc_flags.all_flags.is_empty() {
+            merged.gc_flags = log.gc_flags;
+        }
+        if merged.jvm_info.header.is_empty() {
+            merged.jvm_info = log.jvm_info;
+        }
+        if merged.memory_stats.physical_memory_bytes == 0 {
+            merged.memory_stats = log.memory_stats;
+        }
+        merged.pauses.extend(log.pauses);
+        merged.concurrent_phases.extend(log.concurrent_phases);
+        merged.cycles.extend(log.cycles);
+        merged.safepoints.extend(log.safepoints);
+        merged.allocation_stalls.extend(log.allocation_stalls);
+    }
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use time::UtcOffset;
+
+    use crate::glog::exec::{parse_log, ParsedLog};
+
+    use super::{
+        created_epoch_micros, find_gaps, merge_logs, order_logs, parse_log_created_time,
+        rotation_index,
+    };
+
+    fn parse(file_name: &str, lines: &str) -> ParsedLog {
+        parse_log(file_name.to_string(), Cursor::new(lines.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_parse_log_created_time() {
+        let created_time = parse_log_created_time(
+            "2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log.4",
+        )
+        .unwrap();
+        assert_eq!(
+            created_epoch_micros(created_time, UtcOffset::UTC),
+            1658515266000000
+        );
+        assert_eq!(
+            parse_log_created_time("2022-07-22 18:41:06 GC log file has reached the maximum size. Saved as /opt/dremio/data/gc.log.3"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_created_time_uses_offset_of_date_stamps() {
+        let log = parse(
+            "gc.log.1",
+            "2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log.1
+CommandLine flags: -XX:+UseG1GC -XX:+UseGCLogFileRotation
+2022-07-22T18:41:07.000+0530: 1.000: [GC pause (G1 Evacuation Pause) (young), 0.0100000 secs]
+",
+        );
+        assert_eq!(
+            log.created_epoch_micros,
+            1658515266000000 - 19800 * 1_000_000
+        );
+        //without date stamps the zone is not known until --tz is applied
+        let log = parse(
+            "gc.log.1",
+            "2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log.1
+CommandLine flags: -XX:+UseG1GC -XX:+UseGCLogFileRotation
+1.000: [GC pause (G1 Evacuation Pause) (young), 0.0100000 secs]
+",
+        );
+        assert_eq!(log.created_epoch_micros, 0);
+        assert!(log.created_time.is_some());
+    }
+
+    #[test]
+    fn test_rotation_index() {
+        assert_eq!(rotation_index("/opt/dremio/data/gc.log.3"), 3);
+        assert_eq!(rotation_index("/opt/dremio/data/gc.log.4.current"), 4);
+        assert_eq!(rotation_index("gc.log"), 0);
+    }
+
+    #[test]
+    fn test_order_and_merge_logs() {
+        let newest = parse(
+            "gc.log.0.current",
+            "2022-07-22 18:45:00 GC log file created /opt/dremio/data/gc.log.0.current
+CommandLine flags: -XX:+UseG1GC -XX:+UseGCLogFileRotation
+2022-07-22T18:45:01.000+0000: 300.000: [GC pause (G1 Evacuation Pause) (young), 0.0500000 secs]
+",
+        );
+        let oldest = parse(
+            "gc.log.3",
+            "2022-07-22 18:41:06 GC log file created /opt/dremio/data/gc.log.3
+CommandLine flags: -XX:+UseG1GC -XX:+UseGCLogFileRotation
+2022-07-22T18:41:07.000+0000: 1.000: [GC pause (G1 Evacuation Pause) (young), 0.0100000 secs]
+2022-07-22T18:41:10.000+0000: 4.000: [GC pause (G1 Evacuation Pause) (young), 0.0200000 secs]
+2022-07-22 18:41:10 GC log file has reached the maximum size. Saved as /opt/dremio/data/gc.log.3
+",
+        );
+        let logs = order_logs(vec![newest, oldest]);
+        assert_eq!(logs[0].file_name, "gc.log.3");
+        assert_eq!(logs[1].file_name, "gc.log.0.current");
+        let gaps = find_gaps(&logs);
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].before_file, "gc.log.3");
+        assert_eq!(gaps[0].end_epoch - gaps[0].start_epoch, 230_000_000);
+        let merged = merge_logs(logs).unwrap();
+        assert_eq!(merged.pauses.len(), 3);
+        assert_eq!(merged.pauses[0].pause_time_seconds, 0.01);
+        assert_eq!(merged.pauses[2].pause_time_seconds, 0.05);
+    }
+
+    #[test]
+    fn test_no_gap_without_created_header() {
+        //a jvm that was quiet for five minutes after the rotation
+        let first = parse(
+            "gc.log.1",
+            "CommandLine flags: -XX:+UseG1GC -XX:+UseGCLogFileRotation
+2022-07-22T18:41:07.000+0000: 1.000: [GC pause (G1 Evacuation Pause) (young), 0.0100000 secs]
+",
+        );
+        let second = parse(
+            "gc.log.2",
+            "CommandLine flags: -XX:+UseG1GC -XX:+UseGCLogFileRotation
+2022-07-22T18:46:07.000+0000: 301.000: [GC pause (G1 Evacuation Pause) (young), 0.0100000 secs]
+",
+        );
+        let logs = order_logs(vec![second, first]);
+        assert_eq!(logs[0].file_name, "gc.log.1");
+        assert!(find_gaps(&logs).is_empty());
+    }
+}
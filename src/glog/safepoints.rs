@@ -0,0 +1,425 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// parsing for the safepoint times written with -XX:+PrintGCApplicationStoppedTime and
+// -XX:+PrintGCApplicationConcurrentTime, or -Xlog:safepoint on jdk 9+
+//
+// 2022-08-24T01:54:42.739+0000: 190272.492: Application time: 0.0001233 seconds
+// 2022-08-24T01:54:42.739+0000: 190272.492: Total time for which application threads were stopped: 4.1367352 seconds, Stopping threads took: 0.0001233 seconds
+// [2.345s][info][safepoint] Total time for which application threads were stopped: 0.0012345 seconds, Stopping threads took: 0.0000456 seconds
+// [3.456s][info][safepoint] Safepoint "G1CollectForAllocation", Time since last: 102837000 ns, Reaching safepoint: 182700 ns, At safepoint: 10049100 ns, Total: 10231800 ns
+
+use std::str::FromStr;
+
+use histogram::Histogram;
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+use time::UtcOffset;
+
+use crate::human::human_time;
+
+use super::{pauses::get_epoch_micros, pauses::GCPause, unified::parse_decorators};
+
+// time to safepoint above this is reported as an outlier
+pub const TTSP_OUTLIER_SECONDS: f64 = 0.05;
+// how many of the slowest safepoints to reach are listed
+const MAX_OUTLIERS: usize = 10;
+
+const STOPPED: &str = "Total time for which application threads were stopped: ";
+const APPLICATION_TIME: &str = "Application time: ";
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Safepoint {
+    pub time_epoch_micros: i64,
+    pub uptime_seconds: Option<f64>,
+    //the vm operation, only jdk 17+ logs it
+    pub operation: Option<String>,
+    //includes the time to safepoint
+    pub stopped_seconds: f64,
+    pub ttsp_seconds: f64,
+    //how long the application ran since the previous safepoint when that was logged
+    pub application_seconds: Option<f64>,
+}
+
+fn parse_seconds(text: &str) -> Option<f64> {
+    f64::from_str(text.trim().strip_suffix("seconds")?.trim()).ok()
+}
+
+fn parse_nanos_seconds(text: &str) -> Option<f64> {
+    let nanos = i64::from_str(text.trim().strip_suffix("ns")?.trim()).ok()?;
+    Some(nanos as f64 / 1_000_000_000.0)
+}
+
+// reads the 2022-08-24T01:54:42.739+0000: 190272.492: prefix of a jdk 8 line
+fn parse_prefix(prefix: &str) -> (i64, Option<f64>) {
+    let mut time_epoch_micros = 0;
+    let mut uptime_seconds = None;
+    for token in prefix.split(": ") {
+        let token = token.trim().trim_end_matches(':');
+        if let Ok(uptime) = f64::from_str(token) {
+            uptime_seconds = Some(uptime);
+        } else if let Ok(epoch) = get_epoch_micros(token.to_string()) {
+            time_epoch_micros = epoch;
+        }
+    }
+    (time_epoch_micros, uptime_seconds)
+}
+
+// splits the time decorations off the message of either a jdk 8 or a unified logging line
+fn split_message(line: &str) -> (i64, Option<f64>, &str) {
+    if let Some((decorators, message)) = parse_decorators(line) {
+        return (
+            decorators.time_epoch_micros,
+            decorators.uptime_seconds,
+            message,
+        );
+    }
+    for marker in [STOPPED, APPLICATION_TIME] {
+        if let Some(index) = line.find(marker) {
+            let (time_epoch_micros, uptime_seconds) = parse_prefix(&line[..index]);
+            return (time_epoch_micros, uptime_seconds, &line[index..]);
+        }
+    }
+    (0, None, line)
+}
+
+// the time the application ran before the next safepoint, logged right before it
+pub fn parse_application_time(line: &str) -> Option<f64> {
+    let (_, _, message) = split_message(line);
+    parse_seconds(message.strip_prefix(APPLICATION_TIME)?)
+}
+
+// reads the jdk 17+ Safepoint "G1CollectForAllocation", Time since last: 102837000 ns, ...
+fn parse_safepoint_operation(message: &str) -> Option<Safepoint> {
+    let rest = message.strip_prefix("Safepoint \"")?;
+    let (operation, rest) = rest.split_once('"')?;
+    let mut safepoint = Safepoint {
+        operation: Some(operation.to_string()),
+        ..Default::default()
+    };
+    let mut total = None;
+    for part in rest.split(", ") {
+        let (name, value) = match part.split_once(": ") {
+            Some(split) => split,
+            None => continue,
+        };
+        match name {
+            "Time since last" => safepoint.application_seconds = parse_nanos_seconds(value),
+            "Reaching safepoint" => safepoint.ttsp_seconds = parse_nanos_seconds(value)?,
+            "Total" => total = parse_nanos_seconds(value),
+            _ => {}
+        }
+    }
+    safepoint.stopped_seconds = total?;
+    Some(safepoint)
+}
+
+pub fn parse_safepoint(line: &str) -> Option<Safepoint> {
+    let (time_epoch_micros, uptime_seconds, message) = split_message(line);
+    let mut safepoint = match message.strip_prefix(STOPPED) {
+        Some(rest) => {
+            let (stopped, ttsp) = rest.split_once(", Stopping threads took: ")?;
+            Safepoint {
+                stopped_seconds: parse_seconds(stopped)?,
+                ttsp_seconds: parse_seconds(ttsp)?,
+                ..Default::default()
+            }
+        }
+        None => parse_safepoint_operation(message)?,
+    };
+    safepoint.time_epoch_micros = time_epoch_micros;
+    safepoint.uptime_seconds = uptime_seconds;
+    Some(safepoint)
+}
+
+// logs with only an uptime and no way to anchor them still show the uptime
+fn format_safepoint_time(safepoint: &Safepoint, offset: UtcOffset) -> String {
+    match safepoint.uptime_seconds {
+        Some(uptime) if safepoint.time_epoch_micros == 0 => {
+            format!("{uptime:.3} seconds after jvm start")
+        }
+        _ => human_time(safepoint.time_epoch_micros, offset),
+    }
+}
+
+// the total stopped time compared with the time the gc pauses account for
+pub fn generate_safepoint_summary(safepoints: &[Safepoint], pauses: &[GCPause]) -> String {
+    if safepoints.is_empty() {
+        return "".to_string();
+    }
+    let stopped_seconds: f64 = safepoints.iter().map(|s| s.stopped_seconds).sum();
+    let ttsp_seconds: f64 = safepoints.iter().map(|s| s.ttsp_seconds).sum();
+    let pause_seconds: f64 = pauses.iter().map(|p| p.pause_time_seconds).sum();
+    let mut lines = vec![
+        format!("safepoints:                {}", safepoints.len()),
+        format!("total stopped time:        {stopped_seconds:.2} seconds"),
+        format!("gc pause time:             {pause_seconds:.2} seconds"),
+        format!(
+            "stopped outside gc pauses: {:.2} seconds",
+            (stopped_seconds - pause_seconds).max(0.0)
+        ),
+        format!("time to safepoint:         {ttsp_seconds:.2} seconds"),
+    ];
+    let application_seconds: f64 = safepoints
+        .iter()
+        .filter_map(|s| s.application_seconds)
+        .sum();
+    if application_seconds > 0.0 {
+        lines.push(format!(
+            "application time:          {:.2} seconds, stopped {:.2}% of the time",
+            application_seconds,
+            stopped_seconds / (stopped_seconds + application_seconds) * 100.0
+        ));
+    }
+    lines.join("\n")
+}
+
+#[derive(Tabled)]
+struct SafepointRow {
+    #[tabled(rename = "Millis")]
+    name: String,
+    #[tabled(display_with = "format_millis", rename = "Min")]
+    min: f64,
+    #[tabled(display_with = "format_millis", rename = "P50")]
+    p50: f64,
+    #[tabled(display_with = "format_millis", rename = "P90")]
+    p90: f64,
+    #[tabled(display_with = "format_millis", rename = "P99")]
+    p99: f64,
+    #[tabled(display_with = "format_millis", rename = "Max")]
+    max: f64,
+}
+
+fn format_millis(millis: &f64) -> String {
+    format!("{millis:.3}")
+}
+
+fn distribution_row(name: &str, seconds: &[f64]) -> SafepointRow {
+    let mut histo_micros = Histogram::new();
+    for value in seconds {
+        histo_micros
+            .increment((value * 1_000_000.0) as u64)
+            .expect("unable to increment safepoint histo");
+    }
+    let min = seconds.iter().cloned().fold(f64::MAX, f64::min) * 1000.0;
+    let max = seconds.iter().cloned().fold(0.0, f64::max) * 1000.0;
+    //the histogram buckets round up so keep the percentiles within what was logged
+    let percentile = |p: f64| {
+        let millis = histo_micros.percentile(p).unwrap_or_default() as f64 / 1000.0;
+        millis.clamp(min, max)
+    };
+    SafepointRow {
+        name: name.to_string(),
+        min,
+        p50: percentile(50.0),
+        p90: percentile(90.0),
+        p99: percentile(99.0),
+        max,
+    }
+}
+
+// the distribution of the stopped time and the time to safepoint in millis
+pub fn generate_safepoint_table(safepoints: &[Safepoint]) -> String {
+    if safepoints.is_empty() {
+        return "".to_string();
+    }
+    let stopped: Vec<f64> = safepoints.iter().map(|s| s.stopped_seconds).collect();
+    let ttsp: Vec<f64> = safepoints.iter().map(|s| s.ttsp_seconds).collect();
+    let rows = vec![
+        distribution_row("Stopped", &stopped),
+        distribution_row("Time To Safepoint", &ttsp),
+    ];
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[derive(Tabled)]
+struct OutlierRow {
+    #[tabled(rename = "Time")]
+    time: String,
+    #[tabled(rename = "Operation")]
+    operation: String,
+    #[tabled(display_with = "format_seconds", rename = "Time To Safepoint")]
+    ttsp_seconds: f64,
+    #[tabled(display_with = "format_seconds", rename = "Stopped")]
+    stopped_seconds: f64,
+}
+
+fn format_seconds(seconds: &f64) -> String {
+    format!("{seconds:.4}")
+}
+
+pub fn find_ttsp_outliers(safepoints: &[Safepoint]) -> Vec<&Safepoint> {
+    let mut outliers: Vec<&Safepoint> = safepoints
+        .iter()
+        .filter(|s| s.ttsp_seconds > TTSP_OUTLIER_SECONDS)
+        .collect();
+    outliers.sort_by(|a, b| b.ttsp_seconds.total_cmp(&a.ttsp_seconds));
+    outliers
+}
+
+// the safepoints that took the longest to reach
+pub fn generate_ttsp_outlier_table(safepoints: &[Safepoint], offset: UtcOffset) -> String {
+    let rows: Vec<OutlierRow> = find_ttsp_outliers(safepoints)
+        .into_iter()
+        .take(MAX_OUTLIERS)
+        .map(|s| OutlierRow {
+            time: format_safepoint_time(s, offset),
+            operation: s.operation.clone().unwrap_or_else(|| "n/a".to_string()),
+            ttsp_seconds: s.ttsp_seconds,
+            stopped_seconds: s.stopped_seconds,
+        })
+        .collect();
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::UtcOffset;
+
+    use crate::glog::pauses::GCPause;
+
+    use super::{
+        generate_safepoint_summary, generate_safepoint_table, generate_ttsp_outlier_table,
+        parse_application_time, parse_safepoint, Safepoint,
+    };
+
+    fn safepoint(stopped_seconds: f64, ttsp_seconds: f64) -> Safepoint {
+        Safepoint {
+            time_epoch_micros: 1661306082739000,
+            stopped_seconds,
+            ttsp_seconds,
+            application_seconds: Some(1.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_safepoint() {
+        let safepoint = parse_safepoint("2022-08-24T01:54:42.739+0000: 190272.492: Total time for which application threads were stopped: 4.1367352 seconds, Stopping threads took: 0.0001233 seconds").unwrap();
+        assert_eq!(
+            safepoint,
+            Safepoint {
+                time_epoch_micros: 1661306082739000,
+                uptime_seconds: Some(190272.492),
+                operation: None,
+                stopped_seconds: 4.1367352,
+                ttsp_seconds: 0.0001233,
+                application_seconds: None,
+            }
+        );
+        let safepoint = parse_safepoint("190272.492: Total time for which application threads were stopped: 0.0012345 seconds, Stopping threads took: 0.0000456 seconds").unwrap();
+        assert_eq!(safepoint.time_epoch_micros, 0);
+        assert_eq!(safepoint.uptime_seconds, Some(190272.492));
+        let safepoint = parse_safepoint("[2.345s][info][safepoint] Total time for which application threads were stopped: 0.0012345 seconds, Stopping threads took: 0.0000456 seconds").unwrap();
+        assert_eq!(safepoint.uptime_seconds, Some(2.345));
+        assert_eq!(safepoint.ttsp_seconds, 0.0000456);
+        assert!(parse_safepoint(
+            "2022-08-24T01:54:42.739+0000: 190272.492: Application time: 0.0001233 seconds"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_parse_jdk17_safepoint() {
+        let safepoint = parse_safepoint("[3.456s][info][safepoint] Safepoint \"G1CollectForAllocation\", Time since last: 102837000 ns, Reaching safepoint: 182700 ns, At safepoint: 10049100 ns, Total: 10231800 ns").unwrap();
+        assert_eq!(
+            safepoint,
+            Safepoint {
+                time_epoch_micros: 0,
+                uptime_seconds: Some(3.456),
+                operation: Some("G1CollectForAllocation".to_string()),
+                stopped_seconds: 0.0102318,
+                ttsp_seconds: 0.0001827,
+                application_seconds: Some(0.102837),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_application_time() {
+        assert_eq!(
+            parse_application_time(
+                "2022-08-24T01:54:42.739+0000: 190272.492: Application time: 0.5001233 seconds"
+            ),
+            Some(0.5001233)
+        );
+        assert_eq!(
+            parse_application_time("[2.345s][info][safepoint] Application time: 0.2000000 seconds"),
+            Some(0.2)
+        );
+        assert_eq!(
+            parse_application_time("[GC pause (G1 Evacuation Pause) (young)"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_generate_safepoint_summary() {
+        let safepoints = vec![safepoint(0.5, 0.001), safepoint(1.5, 0.25)];
+        let pauses = vec![GCPause {
+            pause_time_seconds: 0.5,
+            ..Default::default()
+        }];
+        assert_eq!(
+            generate_safepoint_summary(&safepoints, &pauses),
+            "safepoints:                2
+total stopped time:        2.00 seconds
+gc pause time:             0.50 seconds
+stopped outside gc pauses: 1.50 seconds
+time to safepoint:         0.25 seconds
+application time:          2.00 seconds, stopped 50.00% of the time"
+        );
+        assert_eq!(generate_safepoint_summary(&[], &pauses), "");
+    }
+
+    #[test]
+    fn test_generate_safepoint_tables() {
+        let mut safepoints: Vec<Safepoint> = (1..=10)
+            .map(|i| safepoint(0.01 * i as f64, 0.001))
+            .collect();
+        safepoints.push(safepoint(1.5, 0.25));
+        assert_eq!(
+            generate_safepoint_table(&safepoints),
+            "+-------------------+--------+--------+----------+----------+----------+
+| Millis            |  Min   |  P50   |   P90    |   P99    |   Max    |
++-------------------+--------+--------+----------+----------+----------+
+| Stopped           | 10.000 | 70.058 | 1500.000 | 1500.000 | 1500.000 |
++-------------------+--------+--------+----------+----------+----------+
+| Time To Safepoint | 1.000  | 1.000  | 250.000  | 250.000  | 250.000  |
++-------------------+--------+--------+----------+----------+----------+
+"
+        );
+        assert_eq!(
+            generate_ttsp_outlier_table(&safepoints, UtcOffset::UTC),
+            "+--------------------------+-----------+-------------------+---------+
+| Time                     | Operation | Time To Safepoint | Stopped |
++--------------------------+-----------+-------------------+---------+
+| 2022-08-24T01:54:42.739Z |    n/a    |      0.2500       | 1.5000  |
++--------------------------+-----------+-------------------+---------+
+"
+        );
+        assert_eq!(generate_safepoint_table(&[]), "");
+        assert_eq!(
+            generate_ttsp_outlier_table(&safepoints[..1], UtcOffset::UTC),
+            ""
+        );
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+
+use super::{
+    occupancy::{find_space, find_transition_after, Space, SpaceOccupancy},
+    pauses::{get_outer_pause_seconds, has_gc, parse_gc_pause, GCPause},
+};
+
+pub fn is_serial_pause(text: &str) -> bool {
+    text.contains("[DefNew") || text.contains("[Tenured")
+}
+
+pub fn parse_serial_occupancy(text: &str) -> Vec<SpaceOccupancy> {
+    //0.500: [GC (Allocation Failure) 0.500: [DefNew: 4416K->512K(4928K), 0.0035 secs] 4416K->1500K(15872K), 0.0036 secs]
+    //2.000: [Full GC (Allocation Failure) 2.000: [Tenured: 10000K->8000K(10944K), 0.0200 secs] 14000K->8000K(15872K), [Metaspace: 2000K->2000K(1056768K)], 0.0201 secs]
+    let mut occupancy = vec![];
+    let mut last_generation_end = None;
+    if let Some((young, end)) = find_space(text, "DefNew", Space::Young) {
+        occupancy.push(young);
+        last_generation_end = Some(end);
+    }
+    if let Some((old, end)) = find_space(text, "Tenured", Space::Old) {
+        occupancy.push(old);
+        last_generation_end = Some(end);
+    }
+    if let Some(end) = last_generation_end {
+        if let Some((heap, _)) = find_transition_after(text, end, Space::Heap) {
+            occupancy.push(heap);
+        }
+    }
+    if let Some((metaspace, _)) = find_space(text, "Metaspace", Space::Metaspace) {
+        occupancy.push(metaspace);
+    }
+    occupancy
+}
+
+pub fn parse_serial_pause(text: String) -> Result<GCPause, Box<dyn Error>> {
+    //a young gc that cannot promote collects tenured inside the same pause
+    //3.000: [GC (Allocation Failure) 3.000: [DefNew (promotion failed) : 4416K->4416K(4928K), 0.0030 secs]3.003: [Tenured: 10000K->9000K(10944K), 0.0300 secs] 14416K->9000K(15872K), [Metaspace: 2000K->2000K(1056768K)], 0.0331 secs]
+    let first_line = text
+        .split('\n')
+        .find(|line| has_gc(line))
+        .unwrap_or_default()
+        .to_string();
+    let mut pause = parse_gc_pause(first_line)?;
+    if let Some(seconds) = get_outer_pause_seconds(&text) {
+        pause.pause_time_seconds = seconds;
+    }
+    if text.contains("promotion failed") {
+        pause.attributes.push("promotion failed".to_string());
+    }
+    //any collection of tenured is a full collection of the heap with the serial collector
+    if text.contains("[Tenured") {
+        pause.is_full_gc = true;
+    }
+    pause.occupancy = parse_serial_occupancy(&text);
+    Ok(pause)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::occupancy::{get_occupancy, Space};
+
+    use super::{is_serial_pause, parse_serial_pause};
+
+    #[test]
+    fn test_parse_serial_young_pause() {
+        let line = "2022-01-02T11:11:01.111+0000: 0.500: [GC (Allocation Failure) 2022-01-02T11:11:01.111+0000: 0.500: [DefNew: 4416K->512K(4928K), 0.0035 secs] 4416K->1500K(15872K), 0.0036 secs] [Times: user=0.00 sys=0.00, real=0.00 secs]";
+        assert!(is_serial_pause(line));
+        let pause = parse_serial_pause(line.to_string()).unwrap();
+        assert_eq!(pause.gc_type, "Allocation Failure");
+        assert!(!pause.is_full_gc);
+        assert_eq!(pause.pause_time_seconds, 0.0036);
+        let young = get_occupancy(&pause, Space::Young).unwrap();
+        assert_eq!(young.before_bytes, 4416 * 1024);
+        assert_eq!(young.after_bytes, 512 * 1024);
+        assert_eq!(young.capacity_bytes, 4928 * 1024);
+        let heap = get_occupancy(&pause, Space::Heap).unwrap();
+        assert_eq!(heap.after_bytes, 1500 * 1024);
+        assert!(get_occupancy(&pause, Space::Old).is_none());
+    }
+
+    #[test]
+    fn test_parse_serial_full_pause() {
+        let line = "2.000: [Full GC (Allocation Failure) 2.000: [Tenured: 10000K->8000K(10944K), 0.0200 secs] 14000K->8000K(15872K), [Metaspace: 2000K->1900K(1056768K)], 0.0201 secs] [Times: user=0.02 sys=0.00, real=0.02 secs]";
+        let pause = parse_serial_pause(line.to_string()).unwrap();
+        assert!(pause.is_full_gc);
+        assert_eq!(pause.gc_type, "Full GC");
+        assert_eq!(pause.attributes, vec!["Allocation Failure"]);
+        assert_eq!(pause.pause_time_seconds, 0.0201);
+        let old = get_occupancy(&pause, Space::Old).unwrap();
+        assert_eq!(old.before_bytes, 10000 * 1024);
+        assert_eq!(old.after_bytes, 8000 * 1024);
+        let heap = get_occupancy(&pause, Space::Heap).unwrap();
+        assert_eq!(heap.before_bytes, 14000 * 1024);
+        let metaspace = get_occupancy(&pause, Space::Metaspace).unwrap();
+        assert_eq!(metaspace.after_bytes, 1900 * 1024);
+    }
+
+    #[test]
+    fn test_parse_serial_promotion_failed() {
+        let line = "3.000: [GC (Allocation Failure) 3.000: [DefNew (promotion failed) : 4416K->4416K(4928K), 0.0030 secs]3.003: [Tenured: 10000K->9000K(10944K), 0.0300 secs] 14416K->9000K(15872K), [Metaspace: 2000K->2000K(1056768K)], 0.0331 secs] [Times: user=0.03 sys=0.00, real=0.03 secs]";
+        let pause = parse_serial_pause(line.to_string()).unwrap();
+        assert!(pause.is_full_gc);
+        assert_eq!(pause.attributes, vec!["promotion failed"]);
+        assert_eq!(pause.pause_time_seconds, 0.0331);
+        let young = get_occupancy(&pause, Space::Young).unwrap();
+        assert_eq!(young.after_bytes, 4416 * 1024);
+        let old = get_occupancy(&pause, Space::Old).unwrap();
+        assert_eq!(old.after_bytes, 9000 * 1024);
+        let heap = get_occupancy(&pause, Space::Heap).unwrap();
+        assert_eq!(heap.before_bytes, 14416 * 1024);
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// zgc allocation stalls, an application thread waiting for the collector to free memory. Only
+// that thread is blocked so they are kept apart from the safepoint pauses
+//
+// [2.100s][info][gc] Allocation Stall (main) 12.345ms
+
+use std::collections::HashMap;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::format_float;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AllocationStall {
+    pub time_epoch_micros: i64,
+    pub uptime_seconds: Option<f64>,
+    //the application thread that was blocked
+    pub thread: String,
+    pub stall_seconds: f64,
+}
+
+pub fn generate_allocation_stall_summary(stalls: &[AllocationStall]) -> String {
+    if stalls.is_empty() {
+        return "".to_string();
+    }
+    let total_seconds: f64 = stalls.iter().map(|s| s.stall_seconds).sum();
+    [
+        format!("allocation stalls:   {}", stalls.len()),
+        format!("total stall time:    {total_seconds:.2} seconds"),
+    ]
+    .join("\n")
+}
+
+#[derive(Tabled)]
+struct AllocationStallRow {
+    #[tabled(rename = "Thread")]
+    thread: String,
+    #[tabled(rename = "Count")]
+    count: i64,
+    #[tabled(display_with = "format_float", rename = "Total Time")]
+    total_seconds: f64,
+    #[tabled(display_with = "format_float", rename = "Max Time")]
+    max_seconds: f64,
+}
+
+// the stalls of each thread, the threads stalled the longest first
+pub fn generate_allocation_stall_table(stalls: &[AllocationStall]) -> String {
+    let mut rows: HashMap<String, AllocationStallRow> = HashMap::new();
+    for stall in stalls {
+        rows.entry(stall.thread.to_string())
+            .and_modify(|row| {
+                row.count += 1;
+                row.total_seconds += stall.stall_seconds;
+                row.max_seconds = row.max_seconds.max(stall.stall_seconds);
+            })
+            .or_insert(AllocationStallRow {
+                thread: stall.thread.to_string(),
+                count: 1,
+                total_seconds: stall.stall_seconds,
+                max_seconds: stall.stall_seconds,
+            });
+    }
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    let mut rows: Vec<AllocationStallRow> = rows.into_values().collect();
+    rows.sort_by(|a, b| {
+        b.total_seconds
+            .total_cmp(&a.total_seconds)
+            .then_with(|| a.thread.cmp(&b.thread))
+    });
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_allocation_stall_summary, generate_allocation_stall_table, AllocationStall,
+    };
+
+    fn stall(thread: &str, stall_seconds: f64) -> AllocationStall {
+        AllocationStall {
+            thread: thread.to_string(),
+            stall_seconds,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_allocation_stall_table() {
+        let stalls = vec![
+            stall("main", 0.25),
+            stall("worker-1", 0.5),
+            stall("main", 0.1),
+        ];
+        assert_eq!(
+            generate_allocation_stall_summary(&stalls),
+            "allocation stalls:   3
+total stall time:    0.85 seconds"
+        );
+        assert_eq!(
+            generate_allocation_stall_table(&stalls),
+            "+----------+-------+------------+----------+
+| Thread   | Count | Total Time | Max Time |
++----------+-------+------------+----------+
+| worker-1 |   1   |    0.50    |   0.50   |
++----------+-------+------------+----------+
+| main     |   2   |    0.35    |   0.25   |
++----------+-------+------------+----------+
+"
+        );
+        assert_eq!(generate_allocation_stall_summary(&[]), "");
+        assert_eq!(generate_allocation_stall_table(&[]), "");
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// parsing for the survivor ages written inside young pauses with -XX:+PrintTenuringDistribution
+//
+// 2022-08-24T01:50:12.381+0000: 190002.134: [GC pause (G1 Evacuation Pause) (young)
+// Desired survivor size 54525952 bytes, new threshold 2 (max 15)
+// - age   1:   41232376 bytes,   41232376 total
+// - age   2:   19583168 bytes,   60815544 total
+// , 0.0412567 secs]
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::human_bytes_ref;
+
+use super::pauses::GCPause;
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct AgeBytes {
+    pub age: u32,
+    pub bytes: i64,
+    //bytes of this age and younger
+    pub total_bytes: i64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TenuringDistribution {
+    pub desired_survivor_bytes: i64,
+    pub new_threshold: u32,
+    pub max_threshold: u32,
+    //empty for the parallel collector which only logs the threshold
+    pub ages: Vec<AgeBytes>,
+}
+
+impl TenuringDistribution {
+    pub fn survivor_bytes(&self) -> i64 {
+        self.ages.last().map(|a| a.total_bytes).unwrap_or_default()
+    }
+
+    // more survived than the survivor space was meant to hold
+    pub fn overflowed(&self) -> bool {
+        self.survivor_bytes() > self.desired_survivor_bytes
+    }
+}
+
+// reads Desired survivor size 54525952 bytes, new threshold 2 (max 15)
+fn parse_desired_survivor(line: &str) -> Option<TenuringDistribution> {
+    let rest = line.trim().strip_prefix("Desired survivor size ")?;
+    let (bytes, rest) = rest.split_once(" bytes, new threshold ")?;
+    let (threshold, rest) = rest.split_once(" (max ")?;
+    let max = rest.split(')').next()?;
+    Some(TenuringDistribution {
+        desired_survivor_bytes: i64::from_str(bytes.trim()).ok()?,
+        new_threshold: u32::from_str(threshold.trim()).ok()?,
+        max_threshold: u32::from_str(max.trim()).ok()?,
+        ages: vec![],
+    })
+}
+
+// reads - age   1:   41232376 bytes,   41232376 total
+fn parse_age(line: &str) -> Option<AgeBytes> {
+    let rest = line.trim().strip_prefix("- age ")?;
+    let (age, rest) = rest.split_once(':')?;
+    let (bytes, total) = rest.split_once(" bytes,")?;
+    Some(AgeBytes {
+        age: u32::from_str(age.trim()).ok()?,
+        bytes: i64::from_str(bytes.trim()).ok()?,
+        total_bytes: i64::from_str(total.trim().strip_suffix("total")?.trim()).ok()?,
+    })
+}
+
+// finds the tenuring distribution in the text of a young pause
+pub fn parse_tenuring(text: &str) -> Option<TenuringDistribution> {
+    let mut distribution: Option<TenuringDistribution> = None;
+    for line in text.lines() {
+        if let Some(desired) = parse_desired_survivor(line) {
+            distribution = Some(desired);
+        } else if let Some(age) = parse_age(line) {
+            if let Some(distribution) = distribution.as_mut() {
+                distribution.ages.push(age);
+            }
+        }
+    }
+    distribution
+}
+
+// how the threshold moved and how often the survivors went past the desired size
+pub fn generate_tenuring_summary(pauses: &[GCPause]) -> String {
+    let distributions: Vec<&TenuringDistribution> =
+        pauses.iter().filter_map(|p| p.tenuring.as_ref()).collect();
+    if distributions.is_empty() {
+        return "".to_string();
+    }
+    let thresholds: Vec<u32> = distributions.iter().map(|d| d.new_threshold).collect();
+    let min = thresholds.iter().min().unwrap_or(&0);
+    let max = thresholds.iter().max().unwrap_or(&0);
+    let avg = thresholds.iter().sum::<u32>() as f64 / thresholds.len() as f64;
+    let max_threshold = distributions.iter().map(|d| d.max_threshold).max();
+    [
+        format!("young pauses:             {}", distributions.len()),
+        format!(
+            "tenuring threshold:       min {min}, avg {avg:.1}, max {max} (max {})",
+            max_threshold.unwrap_or_default()
+        ),
+        format!(
+            "threshold of 1:           {} pauses",
+            thresholds.iter().filter(|t| **t == 1).count()
+        ),
+        format!(
+            "survivors over desired:   {} pauses",
+            distributions.iter().filter(|d| d.overflowed()).count()
+        ),
+    ]
+    .join("\n")
+}
+
+#[derive(Tabled)]
+struct AgeRow {
+    #[tabled(rename = "Age")]
+    age: u32,
+    #[tabled(rename = "Pauses")]
+    pauses: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Avg Bytes")]
+    avg_bytes: i64,
+    #[tabled(display_with = "human_bytes_ref", rename = "Max Bytes")]
+    max_bytes: i64,
+}
+
+// the bytes of each age averaged over the pauses that had objects of that age
+pub fn generate_age_table(pauses: &[GCPause]) -> String {
+    let mut rows: BTreeMap<u32, AgeRow> = BTreeMap::new();
+    for age in pauses
+        .iter()
+        .filter_map(|p| p.tenuring.as_ref())
+        .flat_map(|d| d.ages.iter())
+    {
+        let row = rows.entry(age.age).or_insert(AgeRow {
+            age: age.age,
+            pauses: 0,
+            avg_bytes: 0,
+            max_bytes: 0,
+        });
+        //summed here and divided below
+        row.avg_bytes += age.bytes;
+        row.max_bytes = row.max_bytes.max(age.bytes);
+        row.pauses += 1;
+    }
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    let rows: Vec<AgeRow> = rows
+        .into_values()
+        .map(|mut row| {
+            row.avg_bytes /= row.pauses;
+            row
+        })
+        .collect();
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::pauses::GCPause;
+
+    use super::{
+        generate_age_table, generate_tenuring_summary, parse_tenuring, AgeBytes,
+        TenuringDistribution,
+    };
+
+    const YOUNG_PAUSE: &str =
+        "2022-08-24T01:50:12.381+0000: 190002.134: [GC pause (G1 Evacuation Pause) (young)
+Desired survivor size 54525952 bytes, new threshold 2 (max 15)
+- age   1:   41232376 bytes,   41232376 total
+- age   2:   19583168 bytes,   60815544 total
+, 0.0412567 secs]";
+
+    #[test]
+    fn test_parse_tenuring() {
+        let distribution = parse_tenuring(YOUNG_PAUSE).unwrap();
+        assert_eq!(distribution.desired_survivor_bytes, 54525952);
+        assert_eq!(distribution.new_threshold, 2);
+        assert_eq!(distribution.max_threshold, 15);
+        assert_eq!(
+            distribution.ages[1],
+            AgeBytes {
+                age: 2,
+                bytes: 19583168,
+                total_bytes: 60815544,
+            }
+        );
+        assert_eq!(distribution.survivor_bytes(), 60815544);
+        assert!(distribution.overflowed());
+        let parallel = parse_tenuring("2022-08-24T01:50:12.381+0000: 190002.134: [GC (Allocation Failure) \nDesired survivor size 10485760 bytes, new threshold 7 (max 15)\n[PSYoungGen: 65536K->10720K(76288K)] 65536K->10728K(251392K), 0.0123 secs]").unwrap();
+        assert_eq!(parallel.new_threshold, 7);
+        assert!(parallel.ages.is_empty());
+        assert!(!parallel.overflowed());
+        assert!(
+            parse_tenuring("[GC pause (G1 Evacuation Pause) (young), 0.0412567 secs]").is_none()
+        );
+    }
+
+    #[test]
+    fn test_generate_tenuring_tables() {
+        let pause = |tenuring| GCPause {
+            gc_type: "G1 Evacuation Pause".to_string(),
+            tenuring,
+            ..Default::default()
+        };
+        let pauses = vec![
+            pause(parse_tenuring(YOUNG_PAUSE)),
+            pause(Some(TenuringDistribution {
+                desired_survivor_bytes: 54525952,
+                new_threshold: 1,
+                max_threshold: 15,
+                ages: vec![AgeBytes {
+                    age: 1,
+                    bytes: 1048576,
+                    total_bytes: 1048576,
+                }],
+            })),
+            pause(None),
+        ];
+        assert_eq!(
+            generate_tenuring_summary(&pauses),
+            "young pauses:             2
+tenuring threshold:       min 1, avg 1.5, max 2 (max 15)
+threshold of 1:           1 pauses
+survivors over desired:   1 pauses"
+        );
+        assert_eq!(
+            generate_age_table(&pauses),
+            "+-----+--------+-----------+-----------+
+| Age | Pauses | Avg Bytes | Max Bytes |
++-----+--------+-----------+-----------+
+| 1   |   2    | 20.16 mb  | 39.32 mb  |
++-----+--------+-----------+-----------+
+| 2   |   1    | 18.68 mb  | 18.68 mb  |
++-----+--------+-----------+-----------+
+"
+        );
+        assert_eq!(generate_tenuring_summary(&[]), "");
+        assert_eq!(generate_age_table(&[]), "");
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use tabled::object::Columns;
+use tabled::{Alignment, Modify, Table, Tabled};
+
+use crate::human::format_float;
+
+use super::{
+    flags::Collector,
+    jvmflags::JvmFlags,
+    jvmstats::JvmInfo,
+    pauses::{generate_gc_name, GCPause},
+};
+
+// the cpu time all gc threads spent in user and kernel mode next to the wall clock time of a pause
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct CpuTimes {
+    pub user_seconds: f64,
+    pub sys_seconds: f64,
+    pub real_seconds: f64,
+}
+
+// reads user=4.76 sys=0.97, real=4.14 and the unified logging User=0.03s Sys=0.00s Real=0.01s
+pub fn parse_cpu_time_values(text: &str) -> Option<CpuTimes> {
+    let mut user = None;
+    let mut sys = None;
+    let mut real = None;
+    for token in text.split([' ', ',']) {
+        let (key, value) = match token.split_once('=') {
+            Some(split) => split,
+            None => continue,
+        };
+        let value = match f64::from_str(value.trim_end_matches('s')) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        match key.to_lowercase().as_str() {
+            "user" => user = Some(value),
+            "sys" => sys = Some(value),
+            "real" => real = Some(value),
+            _ => (),
+        }
+    }
+    Some(CpuTimes {
+        user_seconds: user?,
+        sys_seconds: sys?,
+        real_seconds: real?,
+    })
+}
+
+// reads the last [Times: ...] of a log entry, earlier ones belong to phases logged in the
+// middle of the pause such as the cms concurrent phases
+// [Times: user=4.76 sys=0.97, real=4.14 secs]
+pub fn parse_cpu_times(text: &str) -> Option<CpuTimes> {
+    let start = text.rfind("[Times: ")? + "[Times: ".len();
+    let rest = &text[start..];
+    parse_cpu_time_values(&rest[..rest.find(']')?])
+}
+
+// reads the number of threads G1 used for a pause
+//    [Parallel Time: 205.6 ms, GC Workers: 23]
+pub fn parse_gc_workers(line: &str) -> Option<u32> {
+    let start = line.find("GC Workers: ")? + "GC Workers: ".len();
+    let workers = line[start..].split(']').next()?;
+    u32::from_str(workers.trim()).ok()
+}
+
+fn get_flag_gc_threads(jvm_flags: &JvmFlags) -> Option<u32> {
+    jvm_flags
+        .get_number("ParallelGCThreads")
+        .map(|threads| threads as u32)
+}
+
+// the thread count from -XX:ParallelGCThreads or else the most workers seen in a pause
+pub fn get_gc_threads(jvm_flags: &JvmFlags, pauses: &[GCPause]) -> Option<u32> {
+    if let Some(threads) = get_flag_gc_threads(jvm_flags) {
+        return Some(threads);
+    }
+    pauses.iter().filter_map(|p| p.gc_workers).max()
+}
+
+// jdk 8 G1 and CMS run a full gc on a single thread whatever the thread count is, jdk 10 made
+// the G1 full gc parallel. A version of 0 is a jdk 8 log without the jvm header
+pub fn is_single_threaded_full_gc(
+    pause: &GCPause,
+    collector: &Collector,
+    jvm_info: &JvmInfo,
+) -> bool {
+    pause.is_full_gc
+        && match collector {
+            Collector::CMS => true,
+            Collector::G1GC => jvm_info.major_version < 10,
+            _ => false,
+        }
+}
+
+#[derive(Tabled)]
+struct ParallelismRow {
+    #[tabled(rename = "GC")]
+    gc_name: String,
+    #[tabled(rename = "Count")]
+    count: i64,
+    #[tabled(display_with = "format_float", rename = "User")]
+    user_seconds: f64,
+    #[tabled(display_with = "format_float", rename = "Sys")]
+    sys_seconds: f64,
+    #[tabled(display_with = "format_float", rename = "Real")]
+    real_seconds: f64,
+    #[tabled(display_with = "format_parallelism", rename = "Parallelism")]
+    parallelism: Option<f64>,
+    #[tabled(display_with = "format_threads", rename = "GC Threads")]
+    gc_threads: Option<u32>,
+}
+
+fn format_parallelism(parallelism: &Option<f64>) -> String {
+    match parallelism {
+        Some(parallelism) => format!("{parallelism:.2}"),
+        None => "n/a".to_string(),
+    }
+}
+
+fn format_threads(threads: &Option<u32>) -> String {
+    match threads {
+        Some(threads) => threads.to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+// user divided by real is how many gc threads were busy on average during the pause, compared
+// against the thread count it shows if the gc threads actually got the cpus they expected
+pub fn generate_parallelism_table(
+    jvm_flags: &JvmFlags,
+    collector: &Collector,
+    jvm_info: &JvmInfo,
+    pauses: &[GCPause],
+) -> String {
+    let flag_threads = get_flag_gc_threads(jvm_flags);
+    let mut rows: HashMap<String, ParallelismRow> = HashMap::new();
+    for pause in pauses {
+        let times = match pause.cpu_times {
+            Some(times) => times,
+            None => continue,
+        };
+        let gc_threads = if is_single_threaded_full_gc(pause, collector, jvm_info) {
+            Some(1)
+        } else {
+            flag_threads.or(pause.gc_workers)
+        };
+        rows.entry(generate_gc_name(pause))
+            .and_modify(|row| {
+                row.count += 1;
+                row.user_seconds += times.user_seconds;
+                row.sys_seconds += times.sys_seconds;
+                row.real_seconds += times.real_seconds;
+                row.gc_threads = row.gc_threads.max(gc_threads);
+            })
+            .or_insert(ParallelismRow {
+                gc_name: generate_gc_name(pause),
+                count: 1,
+                user_seconds: times.user_seconds,
+                sys_seconds: times.sys_seconds,
+                real_seconds: times.real_seconds,
+                parallelism: None,
+                gc_threads,
+            });
+    }
+    if rows.is_empty() {
+        return "".to_string();
+    }
+    let mut rows: Vec<ParallelismRow> = rows.into_values().collect();
+    for row in rows.iter_mut() {
+        //real=0.00 is common for short pauses and says nothing about parallelism
+        if row.real_seconds > 0.0 {
+            row.parallelism = Some(row.user_seconds / row.real_seconds);
+        }
+    }
+    rows.sort_by_key(|r| r.gc_name.to_string());
+    Table::new(rows)
+        .with(Modify::new(Columns::first()).with(Alignment::left()))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::{
+        flags::Collector,
+        jvmflags::{parse_jvm_flags, JvmFlags},
+        jvmstats::JvmInfo,
+        pauses::GCPause,
+    };
+
+    use super::{
+        generate_parallelism_table, get_gc_threads, is_single_threaded_full_gc,
+        parse_cpu_time_values, parse_cpu_times, parse_gc_workers, CpuTimes,
+    };
+
+    #[test]
+    fn test_parse_cpu_times() {
+        let times = parse_cpu_times("    [Times: user=4.76 sys=0.97, real=4.14 secs]").unwrap();
+        assert_eq!(
+            times,
+            CpuTimes {
+                user_seconds: 4.76,
+                sys_seconds: 0.97,
+                real_seconds: 4.14,
+            }
+        );
+        let cms = "5.000: [GC (Allocation Failure) 5.000: [ParNew: 30720K->30720K(30720K), 0.0000 secs]5.000: [CMS5.100: [CMS-concurrent-mark: 0.100/0.120 secs] [Times: user=0.20 sys=0.00, real=0.12 secs]
+ (concurrent mode failure): 68000K->50000K(68288K), 0.5000000 secs] 98720K->50000K(99008K), [Metaspace: 3000K->3000K(1056768K)], 0.5123456 secs] [Times: user=0.50 sys=0.01, real=0.51 secs]";
+        assert_eq!(parse_cpu_times(cms).unwrap().real_seconds, 0.51);
+        assert!(parse_cpu_times("[Times: user=0.50").is_none());
+        let unified = parse_cpu_time_values("User=0.03s Sys=0.01s Real=0.02s").unwrap();
+        assert_eq!(unified.user_seconds, 0.03);
+        assert_eq!(unified.sys_seconds, 0.01);
+        assert_eq!(unified.real_seconds, 0.02);
+    }
+
+    #[test]
+    fn test_parse_gc_workers() {
+        assert_eq!(
+            parse_gc_workers("    [Parallel Time: 205.6 ms, GC Workers: 23]"),
+            Some(23)
+        );
+        assert_eq!(parse_gc_workers("    [Clear CT: 4.3 ms]"), None);
+    }
+
+    #[test]
+    fn test_get_gc_threads() {
+        let pauses = vec![GCPause {
+            gc_workers: Some(23),
+            ..Default::default()
+        }];
+        let no_flags = parse_jvm_flags(&[], &Collector::G1GC);
+        assert_eq!(get_gc_threads(&no_flags, &pauses), Some(23));
+        let eight = parse_jvm_flags(&["-XX:ParallelGCThreads=8".to_string()], &Collector::G1GC);
+        assert_eq!(get_gc_threads(&eight, &pauses), Some(8));
+        assert_eq!(get_gc_threads(&no_flags, &[]), None);
+        //a malformed value is reported with the flags and the pauses are used instead
+        let malformed = parse_jvm_flags(
+            &["-XX:ParallelGCThreads=eight".to_string()],
+            &Collector::G1GC,
+        );
+        assert_eq!(malformed.invalid, vec!["-XX:ParallelGCThreads=eight"]);
+        assert_eq!(get_gc_threads(&malformed, &pauses), Some(23));
+    }
+
+    #[test]
+    fn test_is_single_threaded_full_gc() {
+        let full_gc = GCPause {
+            is_full_gc: true,
+            ..Default::default()
+        };
+        let jdk11 = JvmInfo {
+            major_version: 11,
+            ..Default::default()
+        };
+        assert!(is_single_threaded_full_gc(
+            &full_gc,
+            &Collector::G1GC,
+            &JvmInfo::default()
+        ));
+        assert!(is_single_threaded_full_gc(
+            &full_gc,
+            &Collector::CMS,
+            &jdk11
+        ));
+        assert!(!is_single_threaded_full_gc(
+            &full_gc,
+            &Collector::G1GC,
+            &jdk11
+        ));
+        assert!(!is_single_threaded_full_gc(
+            &full_gc,
+            &Collector::Parallel,
+            &JvmInfo::default()
+        ));
+        assert!(!is_single_threaded_full_gc(
+            &GCPause::default(),
+            &Collector::CMS,
+            &jdk11
+        ));
+    }
+
+    #[test]
+    fn test_generate_parallelism_table() {
+        let pauses = vec![
+            GCPause {
+                gc_type: "G1 Evacuation Pause".to_string(),
+                cpu_times: Some(CpuTimes {
+                    user_seconds: 1.0,
+                    sys_seconds: 0.1,
+                    real_seconds: 0.25,
+                }),
+                gc_workers: Some(8),
+                ..Default::default()
+            },
+            GCPause {
+                gc_type: "G1 Evacuation Pause".to_string(),
+                cpu_times: Some(CpuTimes {
+                    user_seconds: 3.0,
+                    sys_seconds: 0.1,
+                    real_seconds: 0.75,
+                }),
+                ..Default::default()
+            },
+            GCPause {
+                gc_type: "Full GC".to_string(),
+                ..Default::default()
+            },
+            GCPause {
+                gc_type: "Full GC".to_string(),
+                is_full_gc: true,
+                attributes: vec!["Allocation Failure".to_string()],
+                cpu_times: Some(CpuTimes {
+                    user_seconds: 2.0,
+                    sys_seconds: 0.0,
+                    real_seconds: 2.0,
+                }),
+                ..Default::default()
+            },
+        ];
+        let output = generate_parallelism_table(
+            &parse_jvm_flags(&["-XX:ParallelGCThreads=8".to_string()], &Collector::G1GC),
+            &Collector::G1GC,
+            &JvmInfo::default(),
+            &pauses,
+        );
+        assert_eq!(
+            output,
+            "+--------------------------------+-------+------+------+------+-------------+------------+
+| GC                             | Count | User | Sys  | Real | Parallelism | GC Threads |
++--------------------------------+-------+------+------+------+-------------+------------+
+| Full GC - (Allocation Failure) |   1   | 2.00 | 0.00 | 2.00 |    1.00     |     1      |
++--------------------------------+-------+------+------+------+-------------+------------+
+| G1 Evacuation Pause            |   2   | 4.00 | 0.20 | 1.00 |    4.00     |     8      |
++--------------------------------+-------+------+------+------+-------------+------------+
+"
+        );
+        assert_eq!(
+            generate_parallelism_table(
+                &JvmFlags::default(),
+                &Collector::G1GC,
+                &JvmInfo::default(),
+                &[]
+            ),
+            ""
+        );
+    }
+}
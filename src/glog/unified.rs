@@ -0,0 +1,724 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// parsing for the jdk 9+ unified logging format (-Xlog:gc*) where every line
+// is prefixed with a set of decorators such as
+// [2023-01-01T10:00:00.123+0000][12.345s][info][gc,phases]
+
+use std::str::FromStr;
+
+use super::{
+    concurrent::ConcurrentPhase,
+    flags::Collector,
+    occupancy::{parse_transition, Space, SpaceOccupancy},
+    pauses::{get_epoch_micros, GCPause, HeapSizing},
+    stalls::AllocationStall,
+    times::{parse_cpu_time_values, CpuTimes},
+};
+
+// how many lines at the start of a file we look at to decide which parser to use
+pub const SNIFF_LINES: usize = 20;
+
+const LEVELS: [&str; 6] = ["trace", "debug", "info", "warning", "error", "off"];
+
+#[derive(Debug, PartialEq, Default)]
+pub struct Decorators {
+    pub time_epoch_micros: i64,
+    pub uptime_seconds: Option<f64>,
+    pub level: String,
+    pub tags: Vec<String>,
+}
+
+// splits the leading [..] decorators off a unified logging line, returning the
+// decorators and the remaining message. None is returned when the line does not
+// look like a unified logging line
+pub fn parse_decorators(line: &str) -> Option<(Decorators, &str)> {
+    let mut decorators = Decorators::default();
+    let mut rest = line;
+    let mut recognized = 0;
+    while rest.starts_with('[') {
+        let end = rest.find(']')?;
+        let raw = rest[1..end].trim();
+        rest = &rest[end + 1..];
+        if LEVELS.contains(&raw) {
+            decorators.level = raw.to_string();
+            recognized += 1;
+        } else if let Some(millis) = raw.strip_suffix("ms").and_then(|m| i64::from_str(m).ok()) {
+            //uptimemillis and timemillis look the same, anything past a few years
+            //of uptime is assumed to be a wall clock
+            if millis > 100_000_000_000 {
+                decorators.time_epoch_micros = millis * 1000;
+            } else {
+                decorators.uptime_seconds = Some(millis as f64 / 1000.0);
+            }
+            recognized += 1;
+        } else if let Some(nanos) = raw.strip_suffix("ns").and_then(|n| i64::from_str(n).ok()) {
+            decorators.uptime_seconds = Some(nanos as f64 / 1_000_000_000.0);
+            recognized += 1;
+        } else if let Some(seconds) = raw.strip_suffix('s').and_then(|s| f64::from_str(s).ok()) {
+            decorators.uptime_seconds = Some(seconds);
+            recognized += 1;
+        } else if raw.contains('T') && raw.contains('-') {
+            if let Ok(epoch) = get_epoch_micros(raw.to_string()) {
+                decorators.time_epoch_micros = epoch;
+                recognized += 1;
+            }
+        } else if raw.starts_with(|c: char| c.is_ascii_alphabetic())
+            && raw
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == ',' || c == '_' || c == ' ')
+        {
+            decorators.tags = raw.split(',').map(|t| t.trim().to_string()).collect();
+            recognized += 1;
+        }
+        //pid, tid and hostname decorators are ignored
+    }
+    if recognized == 0 {
+        return None;
+    }
+    Some((decorators, rest.trim_start()))
+}
+
+// looks at the first few lines of a log and decides if it was written by the
+// jdk 9+ unified logging framework
+pub fn is_unified_log(lines: &[String]) -> bool {
+    lines.iter().any(|line| {
+        if !line.starts_with('[') {
+            return false;
+        }
+        match parse_decorators(line) {
+            Some((decorators, _)) => {
+                !decorators.tags.is_empty()
+                    && (!decorators.level.is_empty()
+                        || decorators.uptime_seconds.is_some()
+                        || decorators.time_epoch_micros > 0)
+            }
+            None => false,
+        }
+    })
+}
+
+// removes the GC(12) id that prefixes most gc messages along with the y: or O: generation
+// prefix generational zgc adds
+fn strip_gc_id(message: &str) -> &str {
+    let mut message = message;
+    if let Some(rest) = message.strip_prefix("GC(") {
+        if let Some(end) = rest.find(") ") {
+            message = &rest[end + 2..];
+        }
+    }
+    for generation in ["y: ", "Y: ", "O: "] {
+        if let Some(rest) = message.strip_prefix(generation) {
+            return rest;
+        }
+    }
+    message
+}
+
+// returns the 12 from the GC(12) id that prefixes most gc messages
+fn get_gc_id(message: &str) -> Option<&str> {
+    let rest = message.strip_prefix("GC(")?;
+    Some(&rest[..rest.find(')')?])
+}
+
+// parses the trailing 12.345ms of a gc message into seconds
+fn parse_duration_seconds(token: &str) -> Option<f64> {
+    if let Some(millis) = token.strip_suffix("ms") {
+        return f64::from_str(millis).ok().map(|m| m / 1000.0);
+    }
+    if let Some(micros) = token.strip_suffix("us") {
+        return f64::from_str(micros).ok().map(|m| m / 1_000_000.0);
+    }
+    if let Some(seconds) = token.strip_suffix('s') {
+        return f64::from_str(seconds).ok();
+    }
+    None
+}
+
+// splits "Young (Normal) (G1 Evacuation Pause)" into the name and the
+// parenthesized groups, nested parens such as (System.gc()) are kept intact
+fn split_name_and_groups(text: &str) -> (String, Vec<String>) {
+    let mut name = String::new();
+    let mut groups = vec![];
+    let mut group = String::new();
+    let mut depth = 0;
+    for c in text.chars() {
+        if c == '(' {
+            depth += 1;
+            if depth == 1 {
+                continue;
+            }
+        } else if c == ')' {
+            depth -= 1;
+            if depth == 0 {
+                groups.push(group.trim().to_string());
+                group = String::new();
+                continue;
+            }
+        }
+        if depth > 0 {
+            group.push(c);
+        } else if groups.is_empty() {
+            name.push(c);
+        }
+    }
+    (name.trim().to_string(), groups)
+}
+
+// parses a unified logging pause line into the same GCPause the jdk 8 parser produces
+//
+// [2023-01-01T10:00:00.123+0000][info][gc] GC(12) Pause Young (Normal) (G1 Evacuation Pause) 512M->128M(2048M) 12.345ms
+// [12.345s][info][gc] GC(13) Pause Full (System.gc()) 600M->200M(2048M) 123.456ms
+// [12.345s][info][gc] GC(14) Pause Remark 600M->600M(2048M) 3.456ms
+pub fn parse_unified_pause(line: &str) -> Option<GCPause> {
+    let (decorators, message) = parse_decorators(line)?;
+    let message = strip_gc_id(message);
+    let pause_body = message.strip_prefix("Pause ")?;
+    let (body, duration) = pause_body.trim_end().rsplit_once(' ')?;
+    let pause_time_seconds = parse_duration_seconds(duration)?;
+    //drop the heap transition 512M->128M(2048M) so only the name and causes are left
+    let description: Vec<&str> = body
+        .split(' ')
+        .filter(|token| !token.contains("->"))
+        .collect();
+    let (name, groups) = split_name_and_groups(&description.join(" "));
+    let heap = body
+        .split(' ')
+        .filter(|token| token.contains("->"))
+        .find_map(|token| parse_transition(Space::Heap, token));
+
+    let mut attributes: Vec<String> = vec![];
+    let gc_type: String;
+    let mut is_full_gc = false;
+    match name.as_str() {
+        "Young" | "Mixed" => {
+            attributes.push(name.to_lowercase());
+            let mut cause = None;
+            for group in groups.into_iter().rev() {
+                if group == "Evacuation Failure" {
+                    //jdk 8 calls this to-space exhausted
+                    attributes.push("to-space exhausted".to_string());
+                } else if cause.is_none() {
+                    cause = Some(group);
+                } else if group != "Normal" {
+                    attributes.push(group.to_lowercase());
+                }
+            }
+            gc_type = cause.unwrap_or(name);
+        }
+        "Full" => {
+            is_full_gc = true;
+            gc_type = "Full GC".to_string();
+            attributes = groups;
+        }
+        _ => {
+            gc_type = name;
+            attributes = groups;
+        }
+    }
+    Some(GCPause {
+        is_full_gc,
+        attributes,
+        gc_type,
+        pause_time_seconds,
+        time_epoch_micros: decorators.time_epoch_micros,
+        uptime_seconds: decorators.uptime_seconds,
+        heap_sizing: HeapSizing::None,
+        occupancy: heap.into_iter().collect(),
+        ..Default::default()
+    })
+}
+
+// returns the 12 from a line with a GC(12) id
+pub fn parse_unified_gc_id(line: &str) -> Option<String> {
+    let (_, message) = parse_decorators(line)?;
+    get_gc_id(message).map(|id| id.to_string())
+}
+
+// reads the cpu time of a pause, logged after the pause line
+//
+// [12.345s][info][gc,cpu      ] GC(12) User=0.03s Sys=0.00s Real=0.01s
+pub fn parse_unified_cpu_times(line: &str) -> Option<(String, CpuTimes)> {
+    let (decorators, message) = parse_decorators(line)?;
+    if !decorators.tags.contains(&"cpu".to_string()) {
+        return None;
+    }
+    let gc_id = get_gc_id(message)?.to_string();
+    parse_cpu_time_values(strip_gc_id(message)).map(|times| (gc_id, times))
+}
+
+// reads how many threads the collector used, logged before the pause line
+//
+// [12.340s][info][gc,task     ] GC(12) Using 8 workers of 8 for evacuation
+pub fn parse_unified_gc_workers(line: &str) -> Option<(String, u32)> {
+    let (_, message) = parse_decorators(line)?;
+    let gc_id = get_gc_id(message)?.to_string();
+    let workers = strip_gc_id(message)
+        .strip_prefix("Using ")?
+        .split(' ')
+        .next()?;
+    u32::from_str(workers).ok().map(|workers| (gc_id, workers))
+}
+
+// parses 24->0(25) region counts into bytes
+fn parse_region_transition(
+    space: Space,
+    transition: &str,
+    region_size_bytes: i64,
+) -> Option<SpaceOccupancy> {
+    let (before, after_raw) = transition.trim().split_once("->")?;
+    let (after, capacity) = match after_raw.split_once('(') {
+        Some((after, capacity)) => (after, capacity.trim_end_matches(')')),
+        None => (after_raw, "0"),
+    };
+    Some(SpaceOccupancy {
+        space,
+        before_bytes: i64::from_str(before).ok()? * region_size_bytes,
+        after_bytes: i64::from_str(after).ok()? * region_size_bytes,
+        capacity_bytes: i64::from_str(capacity).unwrap_or_default() * region_size_bytes,
+    })
+}
+
+// reads the occupancy G1 logs for each space before the pause line, the regions are only
+// turned into bytes once the region size is known
+//
+// [12.340s][info][gc,heap     ] GC(12) Eden regions: 24->0(25)
+// [12.340s][info][gc,heap     ] GC(12) Survivor regions: 3->2(4)
+// [12.340s][info][gc,heap     ] GC(12) Old regions: 100->120
+// [12.340s][info][gc,metaspace] GC(12) Metaspace: 21248K(21504K)->21248K(21504K) NonClass: 18816K(18944K)->18816K(18944K) Class: 2432K(2560K)->2432K(2560K)
+// [12.340s][info][gc,metaspace] GC(12) Metaspace: 3000K->3000K(1056768K)
+pub fn parse_unified_occupancy(
+    line: &str,
+    region_size_bytes: i64,
+) -> Option<(String, SpaceOccupancy)> {
+    let (_, message) = parse_decorators(line)?;
+    let gc_id = get_gc_id(message)?.to_string();
+    let message = strip_gc_id(message).trim();
+    if let Some(metaspace) = message.strip_prefix("Metaspace: ") {
+        let transition = metaspace.split(' ').next()?;
+        return parse_transition(Space::Metaspace, transition).map(|o| (gc_id, o));
+    }
+    if region_size_bytes <= 0 {
+        return None;
+    }
+    let (space, transition) = [
+        ("Eden regions: ", Space::Eden),
+        ("Survivor regions: ", Space::Survivors),
+        ("Old regions: ", Space::Old),
+    ]
+    .iter()
+    .find_map(|(prefix, space)| message.strip_prefix(prefix).map(|rest| (*space, rest)))?;
+    parse_region_transition(space, transition, region_size_bytes).map(|o| (gc_id, o))
+}
+
+// a zgc allocation stall is an application thread waiting on the collector to free memory
+//
+// [2.100s][info][gc] Allocation Stall (main) 12.345ms
+pub fn parse_unified_allocation_stall(line: &str) -> Option<AllocationStall> {
+    let (decorators, message) = parse_decorators(line)?;
+    let stall = strip_gc_id(message).strip_prefix("Allocation Stall ")?;
+    let (thread, duration) = stall.trim_end().rsplit_once(' ')?;
+    Some(AllocationStall {
+        time_epoch_micros: decorators.time_epoch_micros,
+        uptime_seconds: decorators.uptime_seconds,
+        thread: thread
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .to_string(),
+        stall_seconds: parse_duration_seconds(duration)?,
+    })
+}
+
+// parses the timing of a phase that ran alongside the application threads. Only the [gc] and
+// [gc,phases] tags are read so the G1 marking sub phases are not counted twice
+//
+// [2.400s][info][gc,phases] GC(3) Concurrent Mark 53.839ms
+// [10.200s][info][gc] GC(5) Concurrent marking (unload classes) 600M->610M(1024M) 75.905ms
+// [15.100s][info][gc] GC(5) Concurrent Mark Cycle 45.123ms
+pub fn parse_unified_concurrent(line: &str) -> Option<ConcurrentPhase> {
+    let (decorators, message) = parse_decorators(line)?;
+    if decorators.tags != ["gc"] && decorators.tags != ["gc", "phases"] {
+        return None;
+    }
+    let message = strip_gc_id(message);
+    if !message.starts_with("Concurrent ") {
+        return None;
+    }
+    let (body, duration) = message.trim_end().rsplit_once(' ')?;
+    let duration_seconds = parse_duration_seconds(duration)?;
+    let description: Vec<&str> = body
+        .split(' ')
+        .filter(|token| !token.contains("->"))
+        .collect();
+    let (name, _) = split_name_and_groups(&description.join(" "));
+    Some(ConcurrentPhase {
+        name,
+        time_epoch_micros: decorators.time_epoch_micros,
+        duration_seconds,
+    })
+}
+
+// a whole zgc cycle, logged once when it starts and again with the heap transition when
+// it finishes, newer jdks also put the duration at the end
+//
+// [2.345s][info][gc,start] GC(3) Garbage Collection (Allocation Rate)
+// [2.420s][info][gc      ] GC(3) Garbage Collection (Allocation Rate) 1234M(60%)->456M(22%)
+// [2.420s][info][gc      ] GC(3) Major Collection (Allocation Rate) 1234M(60%)->456M(22%) 0.075s
+pub struct UnifiedCycle {
+    pub gc_id: String,
+    pub name: String,
+    pub time_epoch_micros: i64,
+    //uptime when the line has one otherwise the wall clock
+    pub seconds: f64,
+    pub finished: bool,
+    //only on the finished line and only when the jdk logs it
+    pub duration_seconds: Option<f64>,
+}
+
+pub fn parse_unified_cycle(line: &str) -> Option<UnifiedCycle> {
+    let (decorators, message) = parse_decorators(line)?;
+    let gc_id = get_gc_id(message)?.to_string();
+    let message = strip_gc_id(message);
+    //generational zgc calls them major and minor collections
+    if ![
+        "Garbage Collection (",
+        "Major Collection (",
+        "Minor Collection (",
+    ]
+    .iter()
+    .any(|prefix| message.starts_with(prefix))
+    {
+        return None;
+    }
+    let finished = message.contains("->");
+    let mut description: Vec<&str> = message
+        .trim_end()
+        .split(' ')
+        .filter(|token| !token.contains("->"))
+        .collect();
+    let duration_seconds = if finished {
+        description
+            .last()
+            .and_then(|token| parse_duration_seconds(token))
+    } else {
+        None
+    };
+    if duration_seconds.is_some() {
+        description.pop();
+    }
+    Some(UnifiedCycle {
+        gc_id,
+        name: description.join(" "),
+        time_epoch_micros: decorators.time_epoch_micros,
+        seconds: decorators
+            .uptime_seconds
+            .unwrap_or(decorators.time_epoch_micros as f64 / 1_000_000.0),
+        finished,
+        duration_seconds,
+    })
+}
+
+// reads the collector out of the "Using G1" line logged at jvm startup
+pub fn parse_unified_collector(line: &str) -> Option<Collector> {
+    let (_, message) = parse_decorators(line)?;
+    let collector = message.strip_prefix("Using ")?.trim();
+    match collector {
+        "G1" => Some(Collector::G1GC),
+        "Parallel" => Some(Collector::Parallel),
+        "Serial" => Some(Collector::SerialGC),
+        "Concurrent Mark Sweep" => Some(Collector::CMS),
+        "The Z Garbage Collector" => Some(Collector::ZGC),
+        "Shenandoah" => Some(Collector::Shenandoah),
+        _ => None,
+    }
+}
+
+// reads the region size out of "Heap Region Size: 1M" (jdk 17) or "Heap region size: 1M" (jdk 11)
+pub fn parse_unified_region_size_mb(line: &str) -> Option<f32> {
+    let (_, message) = parse_decorators(line)?;
+    let lower = message.to_lowercase();
+    let size = lower.strip_prefix("heap region size: ")?.trim();
+    if let Some(mb) = size.strip_suffix('m') {
+        return f32::from_str(mb).ok();
+    }
+    if let Some(kb) = size.strip_suffix('k') {
+        return f32::from_str(kb).ok().map(|k| k / 1024.0);
+    }
+    None
+}
+
+// reads the jdk version out of "Version: 17.0.2+8 (release)"
+pub fn parse_unified_version(line: &str) -> Option<String> {
+    let (_, message) = parse_decorators(line)?;
+    message
+        .strip_prefix("Version: ")
+        .map(|version| format!("JDK {}", version.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::{flags::Collector, occupancy::Space};
+
+    use super::{
+        is_unified_log, parse_decorators, parse_unified_allocation_stall, parse_unified_collector,
+        parse_unified_concurrent, parse_unified_cpu_times, parse_unified_cycle,
+        parse_unified_gc_workers, parse_unified_occupancy, parse_unified_pause,
+        parse_unified_region_size_mb, parse_unified_version,
+    };
+
+    #[test]
+    fn test_parse_decorators() {
+        let line = "[2023-01-01T10:00:00.123+0000][12.345s][info][gc,phases   ] GC(12) Pause Young (Normal) (G1 Evacuation Pause) 512M->128M(2048M) 12.345ms";
+        let (decorators, message) = parse_decorators(line).expect("decorators not parsed");
+        assert_eq!(decorators.time_epoch_micros, 1672567200123000);
+        assert_eq!(decorators.uptime_seconds, Some(12.345));
+        assert_eq!(decorators.level, "info");
+        assert_eq!(decorators.tags, vec!["gc", "phases"]);
+        assert!(message.starts_with("GC(12) Pause Young"));
+    }
+
+    #[test]
+    fn test_parse_decorators_millis() {
+        let (decorators, _) =
+            parse_decorators("[1672567200123ms][12345ms][debug][gc] hello").unwrap();
+        assert_eq!(decorators.time_epoch_micros, 1672567200123000);
+        assert_eq!(decorators.uptime_seconds, Some(12.345));
+        assert_eq!(decorators.level, "debug");
+    }
+
+    #[test]
+    fn test_is_unified_log() {
+        let unified = vec![
+            "[0.005s][info][gc] Using G1".to_string(),
+            "[0.010s][info][gc,init] Version: 17.0.2+8 (release)".to_string(),
+        ];
+        assert!(is_unified_log(&unified));
+        let jdk8 = vec![
+            "OpenJDK 64-Bit Server VM (25.332-b09) for linux-amd64 JRE (1.8.0_332-b09)".to_string(),
+            "2021-02-22T01:01:02.120+0000: 22000.498: [GC pause (G1 Evacuation Pause) (young), 0.0911111 secs]".to_string(),
+            "    [Times: user=4.76 sys=0.97, real=4.14 secs]".to_string(),
+        ];
+        assert!(!is_unified_log(&jdk8));
+    }
+
+    #[test]
+    fn test_parse_unified_young_pause() {
+        let line = "[2023-01-01T10:00:00.123+0000][info][gc] GC(12) Pause Young (Normal) (G1 Evacuation Pause) 512M->128M(2048M) 12.345ms";
+        let pause = parse_unified_pause(line).expect("expected pause");
+        assert_eq!(pause.gc_type, "G1 Evacuation Pause");
+        assert_eq!(pause.attributes, vec!["young"]);
+        assert!(!pause.is_full_gc);
+        assert_eq!(pause.time_epoch_micros, 1672567200123000);
+        assert!((pause.pause_time_seconds - 0.012345).abs() < 0.000001);
+        assert_eq!(pause.occupancy.len(), 1);
+        assert_eq!(pause.occupancy[0].space, Space::Heap);
+        assert_eq!(pause.occupancy[0].after_bytes, 128 * 1024 * 1024);
+        assert_eq!(pause.occupancy[0].capacity_bytes, 2048 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_unified_cpu_times_and_workers() {
+        let (gc_id, times) = parse_unified_cpu_times(
+            "[12.345s][info][gc,cpu      ] GC(12) User=0.03s Sys=0.01s Real=0.02s",
+        )
+        .expect("expected cpu times");
+        assert_eq!(gc_id, "12");
+        assert_eq!(times.user_seconds, 0.03);
+        assert_eq!(times.real_seconds, 0.02);
+        assert!(parse_unified_cpu_times("[12.345s][info][gc] GC(12) User=0.03s").is_none());
+        assert_eq!(
+            parse_unified_gc_workers(
+                "[12.340s][info][gc,task     ] GC(12) Using 8 workers of 8 for evacuation"
+            ),
+            Some(("12".to_string(), 8))
+        );
+    }
+
+    #[test]
+    fn test_parse_unified_occupancy() {
+        let region = 4 * 1024 * 1024;
+        let (gc_id, eden) = parse_unified_occupancy(
+            "[12.340s][info][gc,heap     ] GC(12) Eden regions: 24->0(25)",
+            region,
+        )
+        .expect("expected eden");
+        assert_eq!(gc_id, "12");
+        assert_eq!(eden.space, Space::Eden);
+        assert_eq!(eden.before_bytes, 24 * region);
+        assert_eq!(eden.capacity_bytes, 25 * region);
+        let (_, old) = parse_unified_occupancy(
+            "[12.340s][info][gc,heap     ] GC(12) Old regions: 100->120",
+            region,
+        )
+        .expect("expected old");
+        assert_eq!(old.after_bytes, 120 * region);
+        assert!(parse_unified_occupancy(
+            "[12.340s][info][gc,heap     ] GC(12) Eden regions: 24->0(25)",
+            0
+        )
+        .is_none());
+        let (_, metaspace) = parse_unified_occupancy("[12.340s][info][gc,metaspace] GC(12) Metaspace: 21248K(21504K)->21000K(21504K) NonClass: 18816K(18944K)->18816K(18944K) Class: 2432K(2560K)->2432K(2560K)", 0)
+            .expect("expected metaspace");
+        assert_eq!(metaspace.before_bytes, 21248 * 1024);
+        assert_eq!(metaspace.after_bytes, 21000 * 1024);
+        assert_eq!(metaspace.capacity_bytes, 21504 * 1024);
+    }
+
+    #[test]
+    fn test_parse_unified_concurrent_start_pause() {
+        let line = "[12.345s][info][gc] GC(3) Pause Young (Concurrent Start) (G1 Humongous Allocation) 900M->850M(2048M) 5.1ms";
+        let pause = parse_unified_pause(line).expect("expected pause");
+        assert_eq!(pause.gc_type, "G1 Humongous Allocation");
+        assert_eq!(pause.attributes, vec!["young", "concurrent start"]);
+    }
+
+    #[test]
+    fn test_parse_unified_evacuation_failure() {
+        let line = "[12.345s][info][gc] GC(3) Pause Young (Normal) (G1 Evacuation Pause) (Evacuation Failure) 4000M->4000M(4096M) 25.1ms";
+        let pause = parse_unified_pause(line).expect("expected pause");
+        assert_eq!(pause.gc_type, "G1 Evacuation Pause");
+        assert_eq!(pause.attributes, vec!["young", "to-space exhausted"]);
+    }
+
+    #[test]
+    fn test_parse_unified_full_pause() {
+        let line =
+            "[12.345s][info][gc] GC(13) Pause Full (System.gc()) 600M->200M(2048M) 123.456ms";
+        let pause = parse_unified_pause(line).expect("expected pause");
+        assert!(pause.is_full_gc);
+        assert_eq!(pause.gc_type, "Full GC");
+        assert_eq!(pause.attributes, vec!["System.gc()"]);
+    }
+
+    #[test]
+    fn test_parse_unified_remark() {
+        let line = "[12.345s][info][gc] GC(14) Pause Remark 600M->600M(2048M) 3.456ms";
+        let pause = parse_unified_pause(line).expect("expected pause");
+        assert_eq!(pause.gc_type, "Remark");
+        assert!(pause.attributes.is_empty());
+    }
+
+    #[test]
+    fn test_parse_unified_not_a_pause() {
+        assert!(parse_unified_pause(
+            "[12.345s][info][gc,start] GC(12) Pause Young (Normal) (G1 Evacuation Pause)"
+        )
+        .is_none());
+        assert!(
+            parse_unified_pause("[12.345s][info][gc,heap] GC(12) Eden regions: 24->0(25)")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_zgc_events() {
+        let pause =
+            parse_unified_pause("[2.346s][info][gc,phases   ] GC(3) Pause Mark Start 0.012ms")
+                .expect("expected pause");
+        assert_eq!(pause.gc_type, "Mark Start");
+        assert!((pause.pause_time_seconds - 0.000012).abs() < 0.0000001);
+        let pause = parse_unified_pause(
+            "[9.346s][info][gc,phases   ] GC(9) y: Pause Relocate Start 0.015ms",
+        )
+        .expect("expected pause");
+        assert_eq!(pause.gc_type, "Relocate Start");
+        let stall = parse_unified_allocation_stall(
+            "[2.100s][info][gc          ] Allocation Stall (main) 12.345ms",
+        )
+        .expect("expected stall");
+        assert_eq!(stall.thread, "main");
+        assert!((stall.stall_seconds - 0.012345).abs() < 0.000001);
+        let phase = parse_unified_concurrent(
+            "[2.405s][info][gc,phases   ] GC(3) Concurrent Process Non-Strong References 3.100ms",
+        )
+        .expect("expected phase");
+        assert_eq!(phase.name, "Concurrent Process Non-Strong References");
+        assert!((phase.duration_seconds - 0.0031).abs() < 0.000001);
+        let start = parse_unified_cycle(
+            "[2.345s][info][gc,start    ] GC(3) Garbage Collection (Allocation Rate)",
+        )
+        .expect("expected cycle");
+        assert_eq!(start.gc_id, "3");
+        assert_eq!(start.name, "Garbage Collection (Allocation Rate)");
+        assert!(!start.finished);
+        let end = parse_unified_cycle(
+            "[2.420s][info][gc          ] GC(3) Garbage Collection (Allocation Rate) 1234M(60%)->456M(22%)",
+        )
+        .expect("expected cycle");
+        assert_eq!(end.name, "Garbage Collection (Allocation Rate)");
+        assert!(end.finished);
+        assert!((end.seconds - 2.420).abs() < 0.000001);
+        assert!(end.duration_seconds.is_none());
+        let timed = parse_unified_cycle(
+            "[2.420s][info][gc          ] GC(3) Major Collection (Allocation Rate) 1234M(60%)->456M(22%) 0.075s",
+        )
+        .expect("expected cycle");
+        assert_eq!(timed.name, "Major Collection (Allocation Rate)");
+        assert!((timed.duration_seconds.unwrap() - 0.075).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_parse_shenandoah_events() {
+        let pause = parse_unified_pause(
+            "[10.124s][info][gc] GC(5) Pause Init Mark (unload classes) 0.345ms",
+        )
+        .expect("expected pause");
+        assert_eq!(pause.gc_type, "Init Mark");
+        assert_eq!(pause.attributes, vec!["unload classes"]);
+        let degenerated = parse_unified_pause(
+            "[11.000s][info][gc] GC(6) Pause Degenerated GC (Mark) 900M->600M(1024M) 150.123ms",
+        )
+        .expect("expected pause");
+        assert_eq!(degenerated.gc_type, "Degenerated GC");
+        assert_eq!(degenerated.attributes, vec!["Mark"]);
+        assert!(!degenerated.is_full_gc);
+        let full =
+            parse_unified_pause("[12.000s][info][gc] GC(7) Pause Full 900M->400M(1024M) 1234.5ms")
+                .expect("expected pause");
+        assert!(full.is_full_gc);
+        let phase = parse_unified_concurrent(
+            "[10.200s][info][gc] GC(5) Concurrent marking (unload classes) 600M->610M(1024M) 75.905ms",
+        )
+        .expect("expected phase");
+        assert_eq!(phase.name, "Concurrent marking");
+        assert!(parse_unified_concurrent(
+            "[10.200s][info][gc,marking] GC(5) Concurrent Mark From Roots 75.905ms"
+        )
+        .is_none());
+        assert!(
+            parse_unified_concurrent("[10.100s][info][gc,start] GC(5) Concurrent Mark Cycle")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_unified_header() {
+        assert_eq!(
+            parse_unified_collector("[0.005s][info][gc] Using G1"),
+            Some(Collector::G1GC)
+        );
+        assert_eq!(
+            parse_unified_collector("[0.005s][info][gc] Using The Z Garbage Collector"),
+            Some(Collector::ZGC)
+        );
+        assert_eq!(
+            parse_unified_region_size_mb("[0.010s][info][gc,init] Heap Region Size: 4M"),
+            Some(4.0)
+        );
+        assert_eq!(
+            parse_unified_version("[0.010s][info][gc,init] Version: 17.0.2+8 (release)"),
+            Some("JDK 17.0.2+8 (release)".to_string())
+        );
+    }
+}
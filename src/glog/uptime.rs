@@ -0,0 +1,131 @@
+// Copyright 2022 Dremio
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// rebuilding wall clock times for logs that only have -XX:+PrintGCTimeStamps
+
+use super::{pauses::GCPause, safepoints::Safepoint};
+
+fn uptime_micros(uptime_seconds: f64) -> i64 {
+    (uptime_seconds * 1_000_000.0).round() as i64
+}
+
+// the exact start of the jvm from a pause with both a date stamp and an uptime
+pub fn find_dated_jvm_start_epoch(pauses: &[GCPause]) -> Option<i64> {
+    pauses.iter().find_map(|pause| match pause.uptime_seconds {
+        Some(uptime) if pause.time_epoch_micros > 0 => {
+            Some(pause.time_epoch_micros - uptime_micros(uptime))
+        }
+        _ => None,
+    })
+}
+
+// works out when the jvm started from the "GC log file created" header when no pause has a
+// date stamp. The first file is created when the jvm starts, every later file right before
+// the event that did not fit in the previous one so the first uptime in it is subtracted
+pub fn find_jvm_start_epoch(
+    pauses: &[GCPause],
+    created_epoch_micros: i64,
+    first_file: bool,
+) -> Option<i64> {
+    if let Some(start) = find_dated_jvm_start_epoch(pauses) {
+        return Some(start);
+    }
+    if created_epoch_micros == 0 {
+        return None;
+    }
+    if first_file {
+        return Some(created_epoch_micros);
+    }
+    let first_uptime = pauses.iter().find_map(|p| p.uptime_seconds)?;
+    Some(created_epoch_micros - uptime_micros(first_uptime))
+}
+
+// fills in the time of every pause that only has an uptime
+pub fn anchor_to_jvm_start(pauses: &mut [GCPause], jvm_start_epoch_micros: i64) {
+    for pause in pauses {
+        if pause.time_epoch_micros != 0 {
+            continue;
+        }
+        if let Some(uptime) = pause.uptime_seconds {
+            pause.time_epoch_micros = jvm_start_epoch_micros + uptime_micros(uptime);
+        }
+    }
+}
+
+// the same for the safepoints, which carry the same uptime
+pub fn anchor_safepoints(safepoints: &mut [Safepoint], jvm_start_epoch_micros: i64) {
+    for safepoint in safepoints {
+        if safepoint.time_epoch_micros != 0 {
+            continue;
+        }
+        if let Some(uptime) = safepoint.uptime_seconds {
+            safepoint.time_epoch_micros = jvm_start_epoch_micros + uptime_micros(uptime);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glog::pauses::GCPause;
+
+    use super::{anchor_to_jvm_start, find_jvm_start_epoch};
+
+    fn uptime_pause(uptime: f64) -> GCPause {
+        GCPause {
+            uptime_seconds: Some(uptime),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_jvm_start_epoch() {
+        let dated = GCPause {
+            time_epoch_micros: 1658515266240000,
+            uptime_seconds: Some(66.24),
+            ..Default::default()
+        };
+        assert_eq!(
+            find_jvm_start_epoch(&[uptime_pause(10.0), dated], 1658515266000000, true),
+            Some(1658515200000000)
+        );
+        //a rotated file is created right before its first event
+        assert_eq!(
+            find_jvm_start_epoch(&[uptime_pause(3600.0)], 1658515266000000, false),
+            Some(1658511666000000)
+        );
+        //the first file is created when the jvm starts
+        assert_eq!(
+            find_jvm_start_epoch(&[uptime_pause(3600.0)], 1658515266000000, true),
+            Some(1658515266000000)
+        );
+        assert_eq!(find_jvm_start_epoch(&[uptime_pause(10.0)], 0, true), None);
+    }
+
+    #[test]
+    fn test_anchor_to_jvm_start() {
+        let mut pauses = vec![
+            uptime_pause(19999.636),
+            GCPause {
+                time_epoch_micros: 1,
+                uptime_seconds: Some(5.0),
+                ..Default::default()
+            },
+            GCPause::default(),
+        ];
+        anchor_to_jvm_start(&mut pauses, 1658500000000000);
+        assert_eq!(pauses[0].time_epoch_micros, 1658519999636000);
+        assert_eq!(pauses[1].time_epoch_micros, 1);
+        assert_eq!(pauses[2].time_epoch_micros, 0);
+    }
+}
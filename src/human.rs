@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use time::{format_description, OffsetDateTime};
+use time::{format_description, OffsetDateTime, UtcOffset};
 
 const SECOND: i64 = 1000;
 const MINUTE: i64 = SECOND * 60;
@@ -33,14 +33,22 @@ pub fn human_duration(duration_millis: i64) -> String {
     }
 }
 
-pub fn human_time(ts_milli: i64) -> String {
-    let ts = ts_milli / 1000;
-    let naive = OffsetDateTime::from_unix_timestamp(ts);
+// formats microseconds since the unix epoch with millisecond precision in the given zone
+pub fn human_time(ts_micros: i64, offset: UtcOffset) -> String {
+    let naive = OffsetDateTime::from_unix_timestamp_nanos(ts_micros as i128 * 1000);
     if let Ok(result) = naive {
-        let format =
-            format_description::parse("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]Z")
-                .expect("unable to setup format");
-        result.format(&format).expect("unable to handle format")
+        let zone = if offset == UtcOffset::UTC {
+            "Z"
+        } else {
+            "[offset_hour sign:mandatory]:[offset_minute]"
+        };
+        let description =
+            format!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]{zone}");
+        let format = format_description::parse(&description).expect("unable to setup format");
+        result
+            .to_offset(offset)
+            .format(&format)
+            .expect("unable to handle format")
     } else {
         "".to_string()
     }
@@ -85,6 +93,20 @@ pub fn human_bytes(bytes: i64) -> String {
     format!("{bytes:.2} bytes")
 }
 
+// tabled hands the field to display_with by reference. Survivors and metaspace can grow during
+// a gc so reclaimed bytes may be negative
+pub fn human_bytes_ref(bytes: &i64) -> String {
+    if *bytes < 0 {
+        return format!("-{}", human_bytes(-bytes));
+    }
+    human_bytes(*bytes)
+}
+
+// seconds and ratios in tables
+pub fn format_float(float: &f64) -> String {
+    format!("{float:.2}")
+}
+
 pub fn human_bytes_base_1k(bytes: i64) -> String {
     let base = 1000.0;
     let base_i64 = 1000;